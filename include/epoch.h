@@ -0,0 +1,59 @@
+#ifndef EPOCH_H
+#define EPOCH_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Outcome of an `epoch_*` call.
+ */
+typedef struct EpochResult {
+  /**
+   * Whether the call succeeded; `error` is set otherwise.
+   */
+  bool ok;
+  /**
+   * The parsed instant as unix milliseconds (`epoch_parse` only).
+   */
+  int64_t unix_millis;
+  /**
+   * The formatted instant (`epoch_format` only), or NULL.
+   */
+  char *value;
+  /**
+   * Error message, or NULL.
+   */
+  char *error;
+} EpochResult;
+
+/**
+ * Parse `input` like the CLI does. `input_tz` may be NULL for the local zone.
+ *
+ * # Safety
+ * `input` must be a valid NUL-terminated string; `input_tz` must be NULL or one.
+ */
+struct EpochResult epoch_parse(const char *input, const char *input_tz);
+
+/**
+ * Format unix milliseconds in `output_tz` (NULL for UTC) with a strftime `format`
+ * (NULL for RFC 3339).
+ *
+ * # Safety
+ * `output_tz` and `format` must each be NULL or a valid NUL-terminated string.
+ */
+struct EpochResult epoch_format(int64_t unix_millis, const char *output_tz, const char *format);
+
+/**
+ * Release the strings owned by `result`.
+ *
+ * # Safety
+ * `result` must come from an `epoch_*` call and not have been freed already.
+ */
+void epoch_result_free(struct EpochResult result);
+
+#endif  /* EPOCH_H */
@@ -0,0 +1,82 @@
+//! Calendar-exact differences between dates, as used by the `age` subcommand.
+
+use chrono::{Datelike, Months, NaiveDate};
+
+/// Whole years, months and days between two dates.
+///
+/// All components share the sign of the span: a `to` before `from` yields negative values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Age {
+    pub years: i64,
+    pub months: i64,
+    pub days: i64,
+}
+
+/// Break the span from `from` to `to` into years, months and days, counting whole
+/// calendar months first and the remaining days after that.
+///
+/// Month ends clamp like they do on a calendar: from January 31 to March 1 is one month
+/// (to the end of February) plus the days after it, and someone born on February 29
+/// turns one on March 1 in common years.
+pub fn age_between(from: NaiveDate, to: NaiveDate) -> Age {
+    if to < from {
+        let age = age_between(to, from);
+        return Age {
+            years: -age.years,
+            months: -age.months,
+            days: -age.days,
+        };
+    }
+
+    let mut months =
+        i64::from(to.year() - from.year()) * 12 + i64::from(to.month()) - i64::from(from.month());
+    if to.day() < from.day() {
+        months -= 1;
+    }
+    let anchor = from + Months::new(months as u32);
+    Age {
+        years: months / 12,
+        months: months % 12,
+        days: (to - anchor).num_days(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn age(from: NaiveDate, to: NaiveDate) -> (i64, i64, i64) {
+        let a = age_between(from, to);
+        (a.years, a.months, a.days)
+    }
+
+    #[test]
+    fn counts_whole_months_then_days() {
+        assert_eq!(age(ymd(1990, 6, 15), ymd(2023, 11, 14)), (33, 4, 30));
+        assert_eq!(age(ymd(1990, 6, 15), ymd(2023, 6, 15)), (33, 0, 0));
+        assert_eq!(age(ymd(2024, 1, 1), ymd(2024, 1, 1)), (0, 0, 0));
+    }
+
+    #[test]
+    fn handles_month_lengths() {
+        assert_eq!(age(ymd(2023, 1, 31), ymd(2023, 3, 1)), (0, 1, 1));
+        assert_eq!(age(ymd(2024, 1, 31), ymd(2024, 3, 1)), (0, 1, 1));
+        assert_eq!(age(ymd(2023, 1, 31), ymd(2023, 2, 28)), (0, 0, 28));
+    }
+
+    #[test]
+    fn handles_leap_day_birthdays() {
+        assert_eq!(age(ymd(2000, 2, 29), ymd(2001, 2, 28)), (0, 11, 30));
+        assert_eq!(age(ymd(2000, 2, 29), ymd(2001, 3, 1)), (1, 0, 1));
+        assert_eq!(age(ymd(2000, 2, 29), ymd(2004, 2, 29)), (4, 0, 0));
+    }
+
+    #[test]
+    fn reversed_span_is_negative() {
+        assert_eq!(age(ymd(2023, 11, 14), ymd(1990, 6, 15)), (-33, -4, -30));
+    }
+}
@@ -0,0 +1,236 @@
+//! Amounts of time for the `add` / `sub` subcommands, including business days, and the
+//! weekday steps of `next`, `prev` and `nth`.
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use std::str::FromStr;
+
+use crate::duration;
+use crate::holidays::Calendar;
+
+/// An amount to add to an instant.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Amount {
+    /// A fixed span of time, e.g. `90s` or `3d` (3 x 24h).
+    Fixed(Duration),
+    /// Working days on the calendar, e.g. `3bd`; weekend days are skipped.
+    BusinessDays(i64),
+}
+
+impl Amount {
+    pub fn negate(self) -> Self {
+        match self {
+            Amount::Fixed(d) => Amount::Fixed(-d),
+            Amount::BusinessDays(n) => Amount::BusinessDays(-n),
+        }
+    }
+}
+
+impl FromStr for Amount {
+    type Err = String;
+
+    /// Parse `<N>bd` business days, or any duration literal accepted by
+    /// [`duration::parse_duration`] (`90s`, `1h30m`, `PT1H30M`, ...).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(n) = s.strip_suffix("bd") {
+            return n.parse().map(Amount::BusinessDays).map_err(|_| {
+                format!("invalid amount `{s}`: expected a whole number of business days")
+            });
+        }
+        duration::parse_duration(s).map(Amount::Fixed)
+    }
+}
+
+/// Parse a weekday name: `mon`, `Monday`, ...
+pub fn parse_weekday(s: &str) -> Result<Weekday, String> {
+    s.parse::<Weekday>()
+        .map_err(|_| format!("invalid weekday `{s}`: expected mon, tue, ... sun"))
+}
+
+/// The first `weekday` after `date`, a week on when `date` is one.
+pub fn next_weekday(date: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let ahead = (weekday.num_days_from_monday() + 7 - date.weekday().num_days_from_monday()) % 7;
+    date + Duration::days(if ahead == 0 { 7 } else { i64::from(ahead) })
+}
+
+/// The last `weekday` before `date`, a week back when `date` is one.
+pub fn prev_weekday(date: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let back = (date.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+    date - Duration::days(if back == 0 { 7 } else { i64::from(back) })
+}
+
+/// The `n`th `weekday` of a month, counting from its end when `n` is negative (`-1` is
+/// the last); `None` when the month has no such day.
+pub fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: i8) -> Option<NaiveDate> {
+    match n {
+        1..=5 => NaiveDate::from_weekday_of_month_opt(year, month, weekday, n as u8),
+        -5..=-1 => {
+            let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+            let last = first
+                .checked_add_months(chrono::Months::new(1))?
+                .pred_opt()?;
+            let date = prev_weekday(last + Duration::days(1), weekday)
+                - Duration::weeks(i64::from(-n - 1));
+            (date.month() == month).then_some(date)
+        }
+        _ => None,
+    }
+}
+
+/// Whether `date` is neither a weekend day nor a holiday in `holidays`.
+pub fn is_business_day(date: NaiveDate, weekend: &[Weekday], holidays: Option<Calendar>) -> bool {
+    !weekend.contains(&date.weekday()) && holidays.is_none_or(|cal| cal.holiday_on(date).is_none())
+}
+
+fn all_weekdays() -> impl Iterator<Item = Weekday> {
+    (0..7u8).filter_map(|d| Weekday::try_from(d).ok())
+}
+
+/// Move `n` business days from `date` (backwards when negative), skipping `weekend` days
+/// and, when a calendar is given, its holidays.
+///
+/// Starting on a weekend is fine: one business day after a Saturday is the Monday.
+pub fn add_business_days(
+    date: NaiveDate,
+    n: i64,
+    weekend: &[Weekday],
+    holidays: Option<Calendar>,
+) -> Result<NaiveDate, String> {
+    if all_weekdays().all(|d| weekend.contains(&d)) {
+        return Err("the weekend cannot cover every day of the week".to_string());
+    }
+
+    let step = if n < 0 { -1 } else { 1 };
+    let mut date = date;
+    let mut remaining = n.abs();
+    while remaining > 0 {
+        date = date
+            .checked_add_signed(Duration::days(step))
+            .ok_or("date out of range")?;
+        if is_business_day(date, weekend, holidays) {
+            remaining -= 1;
+        }
+    }
+    Ok(date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAT_SUN: &[Weekday] = &[Weekday::Sat, Weekday::Sun];
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn parses_amounts() {
+        assert_eq!("90s".parse(), Ok(Amount::Fixed(Duration::seconds(90))));
+        assert_eq!("-2h".parse(), Ok(Amount::Fixed(Duration::hours(-2))));
+        assert_eq!("1w".parse(), Ok(Amount::Fixed(Duration::days(7))));
+        assert_eq!("3bd".parse(), Ok(Amount::BusinessDays(3)));
+        assert_eq!("1h30m".parse(), Ok(Amount::Fixed(Duration::minutes(90))));
+        assert_eq!("PT1H".parse(), Ok(Amount::Fixed(Duration::hours(1))));
+        assert!("3y".parse::<Amount>().is_err());
+        assert!("bd".parse::<Amount>().is_err());
+    }
+
+    #[test]
+    fn finds_the_next_and_previous_weekday() {
+        // 2023-11-14 is a Tuesday.
+        let tue = ymd(2023, 11, 14);
+        assert_eq!(next_weekday(tue, Weekday::Mon), ymd(2023, 11, 20));
+        assert_eq!(next_weekday(tue, Weekday::Wed), ymd(2023, 11, 15));
+        assert_eq!(next_weekday(tue, Weekday::Tue), ymd(2023, 11, 21));
+        assert_eq!(prev_weekday(tue, Weekday::Fri), ymd(2023, 11, 10));
+        assert_eq!(prev_weekday(tue, Weekday::Mon), ymd(2023, 11, 13));
+        assert_eq!(prev_weekday(tue, Weekday::Tue), ymd(2023, 11, 7));
+    }
+
+    #[test]
+    fn finds_the_nth_weekday_of_a_month() {
+        assert_eq!(
+            nth_weekday(2025, 3, Weekday::Tue, 2),
+            Some(ymd(2025, 3, 11))
+        );
+        // US DST starts on the second Sunday of March and ends on the first of November.
+        assert_eq!(
+            nth_weekday(2024, 3, Weekday::Sun, 2),
+            Some(ymd(2024, 3, 10))
+        );
+        assert_eq!(
+            nth_weekday(2024, 11, Weekday::Sun, 1),
+            Some(ymd(2024, 11, 3))
+        );
+        // The EU's last Sunday of October.
+        assert_eq!(
+            nth_weekday(2024, 10, Weekday::Sun, -1),
+            Some(ymd(2024, 10, 27))
+        );
+        assert_eq!(
+            nth_weekday(2025, 3, Weekday::Mon, 5),
+            Some(ymd(2025, 3, 31))
+        );
+        assert_eq!(
+            nth_weekday(2025, 3, Weekday::Mon, -5),
+            Some(ymd(2025, 3, 3))
+        );
+        assert_eq!(nth_weekday(2025, 3, Weekday::Tue, 5), None);
+        assert_eq!(nth_weekday(2025, 3, Weekday::Tue, -5), None);
+        assert_eq!(nth_weekday(2025, 3, Weekday::Tue, 0), None);
+    }
+
+    #[test]
+    fn business_days_skip_weekends() {
+        // Tuesday 2024-12-24 + 3bd = Friday 2024-12-27
+        assert_eq!(
+            add_business_days(ymd(2024, 12, 24), 3, SAT_SUN, None),
+            Ok(ymd(2024, 12, 27))
+        );
+        // Friday + 1bd = Monday
+        assert_eq!(
+            add_business_days(ymd(2024, 12, 27), 1, SAT_SUN, None),
+            Ok(ymd(2024, 12, 30))
+        );
+        // Monday - 1bd = Friday
+        assert_eq!(
+            add_business_days(ymd(2024, 12, 30), -1, SAT_SUN, None),
+            Ok(ymd(2024, 12, 27))
+        );
+        // Saturday + 1bd = Monday
+        assert_eq!(
+            add_business_days(ymd(2024, 12, 28), 1, SAT_SUN, None),
+            Ok(ymd(2024, 12, 30))
+        );
+    }
+
+    #[test]
+    fn business_days_respect_custom_weekend() {
+        let fri_sat = [Weekday::Fri, Weekday::Sat];
+        // Thursday + 1bd = Sunday
+        assert_eq!(
+            add_business_days(ymd(2024, 12, 26), 1, &fri_sat, None),
+            Ok(ymd(2024, 12, 29))
+        );
+    }
+
+    #[test]
+    fn business_days_skip_holidays() {
+        // Tuesday 2024-12-24 + 1bd skips Christmas Day (US)
+        assert_eq!(
+            add_business_days(ymd(2024, 12, 24), 1, SAT_SUN, Some(Calendar::Us)),
+            Ok(ymd(2024, 12, 26))
+        );
+        // ... and Boxing Day too in England
+        assert_eq!(
+            add_business_days(ymd(2024, 12, 24), 1, SAT_SUN, Some(Calendar::Gb)),
+            Ok(ymd(2024, 12, 27))
+        );
+    }
+
+    #[test]
+    fn rejects_all_week_weekend() {
+        let all: Vec<Weekday> = all_weekdays().collect();
+        assert!(add_business_days(ymd(2024, 12, 26), 1, &all, None).is_err());
+    }
+}
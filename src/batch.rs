@@ -0,0 +1,246 @@
+//! Batch conversion (`epoch -`): one INPUT per line, one output line per input, in input
+//! order. Lines are converted in chunks on a rayon pool sized by `--jobs`.
+//!
+//! Input is streamed from stdin or `--input-file`, so files larger than memory are fine.
+
+use chrono::{DateTime, Utc};
+use rayon::prelude::*;
+use std::cell::Cell;
+use std::io::{self, BufRead, Read, Write};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicI64, AtomicUsize, Ordering};
+
+/// Lines handed to the pool at a time; bounds memory on huge inputs while keeping every
+/// worker busy.
+const CHUNK_LINES: usize = 16 * 1024;
+
+/// Why a line could not be converted: the exit code it maps to and the message.
+pub type LineError = (i32, String);
+
+/// Converts one line, pushing any warnings or traces for it onto the notes.
+pub type Convert<'a> =
+    dyn Fn(&str, &mut Vec<String>) -> Result<Option<String>, LineError> + Sync + 'a;
+
+/// `--progress`: lines converted so far on stderr, with a percentage when the input size
+/// is known.
+pub struct Progress {
+    total_bytes: Option<u64>,
+    read_bytes: Rc<Cell<u64>>,
+}
+
+impl Progress {
+    pub fn new(total_bytes: Option<u64>) -> Self {
+        Progress {
+            total_bytes,
+            read_bytes: Rc::default(),
+        }
+    }
+
+    /// Wrap the input so the bytes read through it count towards the percentage.
+    pub fn track<R: Read>(&self, inner: R) -> impl Read {
+        Tracked {
+            inner,
+            read_bytes: Rc::clone(&self.read_bytes),
+        }
+    }
+
+    fn line(&self, lines: usize) -> String {
+        match self.total_bytes.filter(|&total| total > 0) {
+            Some(total) => {
+                let pct = self.read_bytes.get() as f64 / total as f64 * 100.0;
+                format!("{lines} lines ({:.1}%)", pct.min(100.0))
+            }
+            None => format!("{lines} lines"),
+        }
+    }
+
+    fn report(&self, lines: usize) {
+        eprint!("\r{}", self.line(lines));
+    }
+
+    fn finish(&self, lines: usize) {
+        eprintln!("\r{}", self.line(lines));
+    }
+}
+
+/// `--stats`: how many instants were converted and the range they cover. Workers record
+/// into it concurrently.
+pub struct Stats {
+    count: AtomicUsize,
+    min_micros: AtomicI64,
+    max_micros: AtomicI64,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Stats {
+            count: AtomicUsize::new(0),
+            min_micros: AtomicI64::new(i64::MAX),
+            max_micros: AtomicI64::new(i64::MIN),
+        }
+    }
+}
+
+impl Stats {
+    pub fn record(&self, dt: DateTime<Utc>) {
+        let micros = dt.timestamp_micros();
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.min_micros.fetch_min(micros, Ordering::Relaxed);
+        self.max_micros.fetch_max(micros, Ordering::Relaxed);
+    }
+
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Earliest and latest recorded instants; `None` before anything was recorded.
+    pub fn range(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        if self.count() == 0 {
+            return None;
+        }
+        let at =
+            |micros: &AtomicI64| DateTime::from_timestamp_micros(micros.load(Ordering::Relaxed));
+        Some((at(&self.min_micros)?, at(&self.max_micros)?))
+    }
+}
+
+struct Tracked<R> {
+    inner: R,
+    read_bytes: Rc<Cell<u64>>,
+}
+
+impl<R: Read> Read for Tracked<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read_bytes.set(self.read_bytes.get() + n as u64);
+        Ok(n)
+    }
+}
+
+/// Convert every non-blank line of `input` and write the results to `out` in input order.
+///
+/// Lines `convert` maps to `Ok(None)` are passed over without output. Failed lines are
+/// reported on stderr with their line number and skipped. Returns the exit code of the
+/// first failure, if any. `jobs == 0` uses one worker per CPU.
+///
+/// Notes `convert` pushes for a line (warnings, traces) go to stderr with its line
+/// number, ahead of that line's error if it has one.
+pub fn run(
+    input: impl BufRead,
+    out: &mut impl Write,
+    jobs: usize,
+    progress: Option<&Progress>,
+    convert: &Convert<'_>,
+) -> io::Result<Option<i32>> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .map_err(io::Error::other)?;
+
+    let mut first_error = None;
+    let mut done = 0;
+    let mut lines = input.lines().enumerate();
+    loop {
+        let chunk = lines
+            .by_ref()
+            .take(CHUNK_LINES)
+            .map(|(i, line)| line.map(|line| (i + 1, line)))
+            .collect::<io::Result<Vec<_>>>()?;
+        if chunk.is_empty() {
+            break;
+        }
+        done += chunk.len();
+
+        let results: Vec<_> = pool.install(|| {
+            chunk
+                .par_iter()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(n, line)| {
+                    let mut notes = Vec::new();
+                    let result = convert(line.trim(), &mut notes);
+                    (*n, notes, result)
+                })
+                .collect()
+        });
+        for (n, notes, result) in results {
+            for note in notes {
+                eprintln!("line {n}: {note}");
+            }
+            match result {
+                Ok(Some(converted)) => writeln!(out, "{converted}")?,
+                Ok(None) => {}
+                Err((code, msg)) => {
+                    eprintln!("line {n}: {msg}");
+                    first_error.get_or_insert(code);
+                }
+            }
+        }
+        if let Some(progress) = progress {
+            progress.report(done);
+        }
+    }
+    if let Some(progress) = progress {
+        progress.finish(done);
+    }
+    out.flush()?;
+    Ok(first_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn double(line: &str, _: &mut Vec<String>) -> Result<Option<String>, LineError> {
+        if line == "-" {
+            return Ok(None);
+        }
+        line.parse::<i64>()
+            .map(|n| Some((n * 2).to_string()))
+            .map_err(|e| (3, e.to_string()))
+    }
+
+    #[test]
+    fn preserves_input_order_across_workers() {
+        let input: String = (0..50_000).map(|n| format!("{n}\n")).collect();
+        let mut out = Vec::new();
+        let failed = run(input.as_bytes(), &mut out, 4, None, &double).unwrap();
+
+        assert_eq!(failed, None);
+        let expected: String = (0..50_000).map(|n| format!("{}\n", n * 2)).collect();
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    fn skips_blank_and_failed_lines() {
+        let mut out = Vec::new();
+        let failed = run("1\n\n  \nx\n-\n2\n".as_bytes(), &mut out, 1, None, &double).unwrap();
+
+        assert_eq!(failed, Some(3));
+        assert_eq!(String::from_utf8(out).unwrap(), "2\n4\n");
+    }
+
+    #[test]
+    fn progress_counts_tracked_bytes() {
+        let progress = Progress::new(Some(8));
+        let mut input = String::new();
+        progress
+            .track("1\n2\n".as_bytes())
+            .read_to_string(&mut input)
+            .unwrap();
+        assert_eq!(progress.line(2), "2 lines (50.0%)");
+        assert_eq!(Progress::new(None).line(3), "3 lines");
+    }
+
+    #[test]
+    fn stats_track_count_and_range() {
+        let stats = Stats::default();
+        assert_eq!(stats.range(), None);
+
+        let at = |secs| DateTime::from_timestamp(secs, 0).unwrap();
+        for secs in [1_700_000_060, 1_700_000_000, 1_700_000_030] {
+            stats.record(at(secs));
+        }
+        assert_eq!(stats.count(), 3);
+        assert_eq!(stats.range(), Some((at(1_700_000_000), at(1_700_000_060))));
+    }
+}
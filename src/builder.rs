@@ -0,0 +1,497 @@
+//! Builders for parsing and formatting instants from code.
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local, NaiveDate, SecondsFormat, TimeZone, Utc};
+use std::fmt::{self, Write};
+
+use crate::calendars::CalendarSystem;
+#[cfg(feature = "scan")]
+use crate::detectors::Detector;
+use crate::presets::Preset;
+use crate::zone::TzChoice;
+use crate::{
+    Autodetect, DefaultTime, Encoding, EpochError, Parsed, ParsedAs, SyslogYear, Trace, TsUnit,
+    layouts,
+};
+
+/// Parses INPUT the way the CLI does, configured like its flags.
+///
+/// ```
+/// use epoch::{EpochParser, TsUnit, zone::TzChoice};
+///
+/// let (dt, _) = EpochParser::new()
+///     .input_tz(TzChoice::Utc)
+///     .force_unit(TsUnit::Millis)
+///     .strict(true)
+///     .parse("1700000000000")
+///     .unwrap();
+/// assert_eq!(dt.timestamp(), 1_700_000_000);
+/// ```
+#[derive(Clone, Debug)]
+pub struct EpochParser {
+    pub(crate) input_tz: TzChoice,
+    pub(crate) unit: Option<TsUnit>,
+    pub(crate) autodetect: Autodetect,
+    pub(crate) prefer_zone: Vec<String>,
+    pub(crate) strict: bool,
+    pub(crate) syslog_year: SyslogYear,
+    pub(crate) encoding: Option<Encoding>,
+    pub(crate) input_format: Option<String>,
+    pub(crate) century_pivot: u8,
+    pub(crate) lenient: bool,
+    pub(crate) compact: bool,
+    pub(crate) default_time: DefaultTime,
+    pub(crate) default_date: Option<NaiveDate>,
+    #[cfg(feature = "scan")]
+    pub(crate) detectors: Vec<Detector>,
+}
+
+impl Default for EpochParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EpochParser {
+    /// A parser with the CLI defaults: formatted input is read in the local zone, numeric
+    /// input autodetects seconds vs millis, expressions are allowed.
+    pub fn new() -> Self {
+        EpochParser {
+            input_tz: TzChoice::Local,
+            unit: None,
+            autodetect: Autodetect::default(),
+            prefer_zone: Vec::new(),
+            strict: false,
+            syslog_year: SyslogYear::default(),
+            encoding: None,
+            input_format: None,
+            century_pivot: crate::CENTURY_PIVOT,
+            lenient: false,
+            compact: true,
+            default_time: DefaultTime::Start,
+            default_date: None,
+            #[cfg(feature = "scan")]
+            detectors: Vec::new(),
+        }
+    }
+
+    /// Zone for formatted input without its own designator (`--input-tz`).
+    pub fn input_tz(mut self, tz: TzChoice) -> Self {
+        self.input_tz = tz;
+        self
+    }
+
+    /// Read numeric input in this unit instead of autodetecting it (`--ts`).
+    pub fn force_unit(mut self, unit: TsUnit) -> Self {
+        self.unit = Some(unit);
+        self
+    }
+
+    /// How to tell seconds from millis when no unit is forced (`--detect-threshold`,
+    /// `--detect-window`).
+    pub fn autodetect(mut self, autodetect: Autodetect) -> Self {
+        self.autodetect = autodetect;
+        self
+    }
+
+    /// Regions to prefer for clashing zone abbreviations (`--prefer-zone`).
+    pub fn prefer_zone<I, S>(mut self, regions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.prefer_zone = regions.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Only accept literal instants: expressions such as `now - 90m` are rejected, so the
+    /// result depends on nothing but the input.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Which year syslog timestamps without one (`Nov 14 22:13:20`) fall in
+    /// (`--syslog-year`).
+    pub fn syslog_year(mut self, policy: SyslogYear) -> Self {
+        self.syslog_year = policy;
+        self
+    }
+
+    /// Read every input in `encoding` instead of autodetecting its layout (`--from`).
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = Some(encoding);
+        self
+    }
+
+    /// Read every input with this strftime pattern instead of autodetecting its layout
+    /// (`--input-format`). Without an offset in the pattern the input zone applies, and
+    /// without a time the [`default_time`](Self::default_time).
+    pub fn input_format(mut self, pattern: impl Into<String>) -> Self {
+        self.input_format = Some(pattern.into());
+        self
+    }
+
+    /// Two-digit years below `pivot` are 20YY, the rest 19YY (`--century-pivot`).
+    pub fn century_pivot(mut self, pivot: u8) -> Self {
+        self.century_pivot = pivot;
+        self
+    }
+
+    /// Custom formats to try before or after the built-in ones (`--detectors`).
+    #[cfg(feature = "scan")]
+    pub fn detectors(mut self, detectors: Vec<Detector>) -> Self {
+        self.detectors = detectors;
+        self
+    }
+
+    /// Strip the quotes, brackets, trailing separators and `ms`/`s` suffixes that come
+    /// along when copying values out of JSON and code before parsing (`--lenient`); see
+    /// [`crate::clean_lenient`].
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Whether 14- and 12-digit input that makes a plausible `YYYYMMDDHHMM[SS]` date-time
+    /// is read as one rather than as a timestamp (on by default; `--no-compact` turns it
+    /// off). A forced unit always reads digits as a timestamp.
+    pub fn compact_digits(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+
+    /// Where input that gives only a date lands (`--at`): a day (`2023-11-14`, a week
+    /// date, an `--input-format` without a time) at that time, a month (`2023-11`) on its
+    /// first day, or its last for [`DefaultTime::End`]. The start unless set.
+    pub fn default_time(mut self, time: DefaultTime) -> Self {
+        self.default_time = time;
+        self
+    }
+
+    /// Date for input that gives only a time of day (`22:13`, `10:13pm`): today in the
+    /// input zone unless set (`--date`).
+    pub fn default_date(mut self, date: NaiveDate) -> Self {
+        self.default_date = Some(date);
+        self
+    }
+
+    /// Parse `input` into a UTC instant, reporting how it was read.
+    pub fn parse(&self, input: &str) -> Result<(DateTime<Utc>, ParsedAs), EpochError> {
+        self.parse_inner(input, &mut Trace::off())
+    }
+
+    /// [`parse`](Self::parse), also returning notes on each detection step tried, the unit
+    /// picked for numeric input and why, and the zone the result was read in (`-v`).
+    pub fn parse_traced(&self, input: &str) -> (Parsed, Vec<String>) {
+        let mut trace = Trace::on();
+        let result = self.parse_inner(input, &mut trace);
+        if let Ok((_, parsed_as)) = &result {
+            trace.note(|| match (parsed_as, parsed_as.source_offset()) {
+                (ParsedAs::Timestamp { .. }, _) => "zone: none needed, epochs are UTC".to_string(),
+                (ParsedAs::Expression, _) => "zone: as resolved by each operand".to_string(),
+                (_, Some(offset)) => format!("zone: {offset}, stated by the input"),
+                (_, None) => format!("zone: {} (--input-tz)", self.input_tz),
+            });
+        }
+        (result, trace.into_notes())
+    }
+
+    fn parse_inner(&self, input: &str, trace: &mut Trace) -> Parsed {
+        if !self.lenient {
+            return crate::parse_with(input, self, trace);
+        }
+        let (cleaned, unit) = crate::clean_lenient(input);
+        if cleaned != input {
+            trace.note(|| format!("lenient: reading `{cleaned}`"));
+        }
+        match unit {
+            Some(unit) if self.unit.is_none() => {
+                trace.note(|| format!("lenient: suffix means {}", unit.name()));
+                crate::parse_with(cleaned, &self.clone().force_unit(unit), trace)
+            }
+            _ => crate::parse_with(cleaned, self, trace),
+        }
+    }
+}
+
+/// Renders instants the way the CLI does. The strftime pattern is parsed once, when it is
+/// set, so one formatter can render any number of instants cheaply.
+///
+/// ```
+/// use chrono::DateTime;
+/// use epoch::{Formatter, zone::TzChoice};
+///
+/// let dt = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+/// let jst: TzChoice = "+09:00".parse().unwrap();
+/// assert_eq!(Formatter::new().output_tz(jst).format(dt), "2023-11-15T07:13:20+09:00");
+/// assert_eq!(Formatter::new().strftime("%Y/%m/%d").format(dt), "2023/11/14");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Formatter {
+    output_tz: TzChoice,
+    strftime: Option<Vec<Item<'static>>>,
+    /// Initial capacity of each rendered string.
+    capacity: usize,
+    encoding: Option<Encoding>,
+    /// Wrap the output in an HTML `<time>` element.
+    html_time: bool,
+    calendar: Option<CalendarSystem>,
+    #[cfg(feature = "locale")]
+    locale: Option<chrono::Locale>,
+}
+
+impl Default for Formatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Formatter {
+    /// RFC 3339 in UTC, like the CLI's default output.
+    pub fn new() -> Self {
+        Formatter {
+            output_tz: TzChoice::Utc,
+            strftime: None,
+            capacity: RFC3339_LEN,
+            encoding: None,
+            html_time: false,
+            calendar: None,
+            #[cfg(feature = "locale")]
+            locale: None,
+        }
+    }
+
+    /// Zone to render in (`--output-tz`).
+    pub fn output_tz(mut self, tz: TzChoice) -> Self {
+        self.output_tz = tz;
+        self
+    }
+
+    /// strftime pattern to use instead of RFC 3339 (`--format`).
+    ///
+    /// An invalid pattern makes [`Formatter::format`] panic, like chrono's own formatting;
+    /// check untrusted patterns with [`check_strftime`] first.
+    pub fn strftime(mut self, pattern: impl AsRef<str>) -> Self {
+        let pattern = pattern.as_ref();
+        self.strftime = Some(
+            StrftimeItems::new(pattern)
+                .parse_to_owned()
+                .unwrap_or_else(|_| vec![Item::Error]),
+        );
+        // Specifiers mostly expand to no more than twice their own length.
+        self.capacity = pattern.len() * 2;
+        self
+    }
+
+    /// Render with a named [`Preset`](crate::presets::Preset) (`--format aws-logs`).
+    /// Presets that are defined in UTC replace the output zone.
+    pub fn preset(self, preset: &Preset) -> Self {
+        let formatter = self.strftime(preset.pattern);
+        if preset.utc {
+            formatter.output_tz(TzChoice::Utc)
+        } else {
+            formatter
+        }
+    }
+
+    /// Render an HTML `<time>` element (`--format html-time`): the instant as RFC 3339 in
+    /// its `datetime` attribute (with `Z` in UTC), and `display`, a strftime pattern, as
+    /// its text, e.g. [`HTML_TIME_DISPLAY`].
+    pub fn html_time(self, display: impl AsRef<str>) -> Self {
+        let mut formatter = self.strftime(display);
+        formatter.html_time = true;
+        formatter
+    }
+
+    /// Render in `encoding` (`--to julianday`) instead of RFC 3339 or a strftime pattern;
+    /// the output zone does not apply.
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = Some(encoding);
+        self
+    }
+
+    /// Render the date in the output zone in another calendar (`--calendar hebrew`)
+    /// instead of RFC 3339 or a strftime pattern.
+    pub fn calendar(mut self, calendar: CalendarSystem) -> Self {
+        self.calendar = Some(calendar);
+        self
+    }
+
+    /// Locale for month and weekday names in the strftime pattern (`locale` feature).
+    /// RFC 3339 output is not affected.
+    #[cfg(feature = "locale")]
+    pub fn locale(mut self, locale: chrono::Locale) -> Self {
+        self.locale = Some(locale);
+        self
+    }
+
+    pub fn format(&self, dt: DateTime<Utc>) -> String {
+        if let Some(encoding) = self.encoding {
+            return layouts::encode(dt, encoding);
+        }
+        if let Some(calendar) = self.calendar {
+            return calendar.format(self.output_tz.naive_local(dt).date());
+        }
+        if self.html_time {
+            let datetime = match self.output_tz {
+                TzChoice::Utc => dt.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+                tz => Formatter::new().output_tz(tz).format(dt),
+            };
+            let text = self.in_zone(dt);
+            return format!(
+                "<time datetime=\"{datetime}\">{}</time>",
+                escape_html(&text)
+            );
+        }
+        self.in_zone(dt)
+    }
+
+    /// The RFC 3339 or strftime rendering of `dt` in the output zone.
+    fn in_zone(&self, dt: DateTime<Utc>) -> String {
+        match self.output_tz {
+            TzChoice::Utc => self.render(dt),
+            TzChoice::Local => self.render(dt.with_timezone(&Local)),
+            TzChoice::Fixed(off) => self.render(dt.with_timezone(&off)),
+            #[cfg(feature = "tz")]
+            TzChoice::Named(tz) | TzChoice::PinnedLocal(tz) => self.render(dt.with_timezone(&tz)),
+        }
+    }
+
+    fn render<Tz: TimeZone>(&self, dt: DateTime<Tz>) -> String
+    where
+        Tz::Offset: fmt::Display,
+    {
+        let Some(items) = &self.strftime else {
+            return dt.to_rfc3339();
+        };
+        let mut out = String::with_capacity(self.capacity);
+        #[cfg(feature = "locale")]
+        if let Some(locale) = self.locale {
+            write!(
+                out,
+                "{}",
+                dt.format_localized_with_items(items.iter(), locale)
+            )
+            .expect("invalid strftime pattern");
+            return out;
+        }
+        write!(out, "{}", dt.format_with_items(items.iter())).expect("invalid strftime pattern");
+        out
+    }
+}
+
+/// Default text of [`Formatter::html_time`] elements: `14 Nov 2023, 22:13 UTC`.
+pub const HTML_TIME_DISPLAY: &str = "%-d %b %Y, %H:%M %Z";
+
+/// `text` with the characters HTML gives meaning to escaped.
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Length of an RFC 3339 rendering without fractional seconds.
+const RFC3339_LEN: usize = "2023-11-14T22:13:20+00:00".len();
+
+/// Check that `pattern` is a valid strftime pattern, e.g. before handing it to
+/// [`Formatter::strftime`].
+pub fn check_strftime(pattern: &str) -> Result<(), EpochError> {
+    StrftimeItems::new(pattern)
+        .parse()
+        .map(drop)
+        .map_err(|_| EpochError::InvalidInput(format!("invalid strftime pattern `{pattern}`")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strict_rejects_expressions() {
+        let parser = EpochParser::new().input_tz(TzChoice::Utc);
+        assert!(parser.parse("1700000000 + 1h").is_ok());
+        assert!(
+            parser
+                .clone()
+                .strict(true)
+                .parse("1700000000 + 1h")
+                .is_err()
+        );
+        assert!(parser.strict(true).parse("2023/11/14 22:13:20").is_ok());
+    }
+
+    #[test]
+    fn prefer_zone_resolves_clashes() {
+        let parser = EpochParser::new().prefer_zone(["in"]);
+        let (dt, _) = parser.parse("2023/11/15 03:43:20 IST").unwrap();
+        assert_eq!(dt.timestamp(), 1_700_000_000);
+    }
+
+    #[test]
+    fn rejects_invalid_strftime_patterns() {
+        assert!(check_strftime("%Y-%m-%d %H:%M").is_ok());
+        assert!(check_strftime("%Q").is_err());
+    }
+
+    #[test]
+    fn renders_html_time_elements() {
+        let dt = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        assert_eq!(
+            Formatter::new().html_time(HTML_TIME_DISPLAY).format(dt),
+            r#"<time datetime="2023-11-14T22:13:20Z">14 Nov 2023, 22:13 UTC</time>"#
+        );
+        let jst: TzChoice = "+09:00".parse().unwrap();
+        assert_eq!(
+            Formatter::new()
+                .output_tz(jst)
+                .html_time("%H:%M <%a> & co")
+                .format(dt),
+            r#"<time datetime="2023-11-15T07:13:20+09:00">07:13 &lt;Wed&gt; &amp; co</time>"#
+        );
+    }
+
+    #[test]
+    fn renders_other_calendars() {
+        let dt = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let formatter = Formatter::new().calendar(CalendarSystem::Persian);
+        assert_eq!(formatter.format(dt), "23 Aban 1402");
+        // Already the 15th in Tokyo.
+        let jst: TzChoice = "+09:00".parse().unwrap();
+        assert_eq!(formatter.output_tz(jst).format(dt), "24 Aban 1402");
+    }
+
+    #[test]
+    fn reuses_compiled_pattern() {
+        let fmt = Formatter::new().strftime("%Y/%m/%d %H:%M:%S");
+        let at = |secs| DateTime::from_timestamp(secs, 0).unwrap();
+        assert_eq!(fmt.format(at(1_700_000_000)), "2023/11/14 22:13:20");
+        assert_eq!(fmt.format(at(0)), "1970/01/01 00:00:00");
+    }
+
+    #[cfg(feature = "locale")]
+    #[test]
+    fn formats_with_locale() {
+        let dt = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let fmt = Formatter::new().strftime("%A %d %B %Y");
+        assert_eq!(
+            fmt.clone().locale(chrono::Locale::fr_FR).format(dt),
+            "mardi 14 novembre 2023"
+        );
+        assert_eq!(fmt.format(dt), "Tuesday 14 November 2023");
+        assert!(
+            Formatter::new()
+                .locale(chrono::Locale::fr_FR)
+                .format(dt)
+                .starts_with("2023-11-14T")
+        );
+    }
+}
@@ -0,0 +1,471 @@
+//! Dates in calendars other than the Gregorian (`--calendar`).
+//!
+//! All are computed arithmetically: the Hebrew calendar is fully rule-based, the Islamic
+//! one is the tabular (civil) calendar, which can differ by a day or two from calendars
+//! set by sighting the moon, and the Persian one follows the astronomical solar Hijri
+//! calendar through the leap-year breaks of Borkowski's algorithm.
+
+use chrono::{Datelike, NaiveDate};
+
+/// A calendar to render dates in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum CalendarSystem {
+    /// The Hebrew calendar: `1 Kislev 5784`. Its days start at sunset; the date given is
+    /// the one in force during the civil day's daylight.
+    Hebrew,
+    /// The tabular Islamic calendar: `1 Jumada al-Awwal 1445`.
+    Islamic,
+    /// The Persian (solar Hijri) calendar used in Iran and Afghanistan: `23 Aban 1402`.
+    Persian,
+    /// Japanese era (wareki) dates: `令和5年11月14日`, with `元年` for an era's first
+    /// year. Dates before the Meiji era keep the Gregorian year.
+    Japanese,
+    /// Japanese era dates with the era's initial: `R5.11.14`.
+    JapaneseAscii,
+    /// The proleptic Julian calendar, as used before the Gregorian reform and still by
+    /// Orthodox churches: `1 November 2023`. Years before 1 CE are astronomical (0 is
+    /// 1 BCE), as in the rest of the crate.
+    Julian,
+}
+
+/// A day in one of the [`CalendarSystem`]s.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CalendarDate {
+    pub year: i64,
+    /// Position in the calendar's own numbering: Hebrew months count from Nisan, so the
+    /// year starts in month 7 (Tishrei), and 13 is Adar II.
+    pub month: u32,
+    pub day: u32,
+    pub month_name: &'static str,
+    /// The era the year counts from, where the calendar has them: `令和`.
+    pub era: Option<&'static str>,
+}
+
+impl CalendarSystem {
+    /// `date` in this calendar.
+    pub fn convert(self, date: NaiveDate) -> CalendarDate {
+        let fixed = i64::from(date.num_days_from_ce());
+        match self {
+            CalendarSystem::Hebrew => hebrew(fixed),
+            CalendarSystem::Islamic => islamic(fixed),
+            CalendarSystem::Persian => persian(date),
+            CalendarSystem::Japanese | CalendarSystem::JapaneseAscii => japanese(date),
+            CalendarSystem::Julian => julian(fixed),
+        }
+    }
+
+    /// `date` written out the way the calendar's users write it: day first, `1 Kislev
+    /// 5784`, or year first for Japanese dates.
+    pub fn format(self, date: NaiveDate) -> String {
+        let d = self.convert(date);
+        match self {
+            CalendarSystem::Japanese => {
+                let year = match d.year {
+                    1 if d.era.is_some() => "元".to_string(),
+                    year => year.to_string(),
+                };
+                format!(
+                    "{}{year}年{}月{}日",
+                    d.era.unwrap_or_default(),
+                    d.month,
+                    d.day
+                )
+            }
+            CalendarSystem::JapaneseAscii => {
+                let initial = JAPANESE_ERAS
+                    .iter()
+                    .find(|era| Some(era.name) == d.era)
+                    .map_or(String::new(), |era| era.initial.to_string());
+                format!("{initial}{}.{}.{}", d.year, d.month, d.day)
+            }
+            _ => format!("{} {} {}", d.day, d.month_name, d.year),
+        }
+    }
+}
+
+/// Day 1 of the tabular Islamic calendar, 16 July 622 (Julian), as a fixed day number
+/// (days from the start of the proleptic Gregorian year 1, which is day 1).
+const ISLAMIC_EPOCH: i64 = 227_015;
+
+const ISLAMIC_MONTHS: [&str; 12] = [
+    "Muharram",
+    "Safar",
+    "Rabi al-Awwal",
+    "Rabi al-Thani",
+    "Jumada al-Awwal",
+    "Jumada al-Thani",
+    "Rajab",
+    "Shaban",
+    "Ramadan",
+    "Shawwal",
+    "Dhu al-Qadah",
+    "Dhu al-Hijjah",
+];
+
+fn fixed_from_islamic(year: i64, month: i64, day: i64) -> i64 {
+    ISLAMIC_EPOCH - 1
+        + (year - 1) * 354
+        + (3 + 11 * year).div_euclid(30)
+        + 29 * (month - 1)
+        + (6 * month - 1).div_euclid(11)
+        + day
+}
+
+/// Reingold and Dershowitz, *Calendrical Calculations*, `islamic-from-fixed`.
+fn islamic(fixed: i64) -> CalendarDate {
+    let year = (30 * (fixed - ISLAMIC_EPOCH) + 10_646).div_euclid(10_631);
+    let prior_days = fixed - fixed_from_islamic(year, 1, 1);
+    let month = (11 * prior_days + 330).div_euclid(325);
+    let day = fixed - fixed_from_islamic(year, month, 1) + 1;
+    CalendarDate {
+        year,
+        month: month as u32,
+        day: day as u32,
+        month_name: ISLAMIC_MONTHS[month as usize - 1],
+        era: None,
+    }
+}
+
+/// Day 1 of the Hebrew calendar, 7 October 3761 BCE (Julian), as a fixed day number.
+const HEBREW_EPOCH: i64 = -1_373_427;
+
+const TISHREI: i64 = 7;
+
+fn hebrew_leap_year(year: i64) -> bool {
+    (7 * year + 1).rem_euclid(19) < 7
+}
+
+fn last_hebrew_month(year: i64) -> i64 {
+    if hebrew_leap_year(year) { 13 } else { 12 }
+}
+
+/// Days from the epoch to the molad of Tishrei of `year`, delayed when it falls on a
+/// Sunday, Wednesday or Friday.
+fn hebrew_calendar_elapsed_days(year: i64) -> i64 {
+    let months_elapsed = (235 * year - 234).div_euclid(19);
+    let parts_elapsed = 12_084 + 13_753 * months_elapsed;
+    let days = 29 * months_elapsed + parts_elapsed.div_euclid(25_920);
+    if (3 * (days + 1)).rem_euclid(7) < 3 {
+        days + 1
+    } else {
+        days
+    }
+}
+
+/// Extra delay of the new year that keeps years to their permitted lengths.
+fn hebrew_year_length_correction(year: i64) -> i64 {
+    let ny0 = hebrew_calendar_elapsed_days(year - 1);
+    let ny1 = hebrew_calendar_elapsed_days(year);
+    let ny2 = hebrew_calendar_elapsed_days(year + 1);
+    if ny2 - ny1 == 356 {
+        2
+    } else if ny1 - ny0 == 382 {
+        1
+    } else {
+        0
+    }
+}
+
+fn hebrew_new_year(year: i64) -> i64 {
+    HEBREW_EPOCH + hebrew_calendar_elapsed_days(year) + hebrew_year_length_correction(year)
+}
+
+fn last_day_of_hebrew_month(year: i64, month: i64) -> i64 {
+    let days_in_year = hebrew_new_year(year + 1) - hebrew_new_year(year);
+    let short = match month {
+        2 | 4 | 6 | 10 | 13 => true,
+        12 => !hebrew_leap_year(year),
+        // Cheshvan is long and Kislev short only in "complete" and "deficient" years.
+        8 => !matches!(days_in_year, 355 | 385),
+        9 => matches!(days_in_year, 353 | 383),
+        _ => false,
+    };
+    if short { 29 } else { 30 }
+}
+
+fn fixed_from_hebrew(year: i64, month: i64, day: i64) -> i64 {
+    let months_before: i64 = if month < TISHREI {
+        (TISHREI..=last_hebrew_month(year))
+            .chain(1..month)
+            .map(|m| last_day_of_hebrew_month(year, m))
+            .sum()
+    } else {
+        (TISHREI..month)
+            .map(|m| last_day_of_hebrew_month(year, m))
+            .sum()
+    };
+    hebrew_new_year(year) + months_before + day - 1
+}
+
+/// Reingold and Dershowitz, `hebrew-from-fixed`.
+fn hebrew(fixed: i64) -> CalendarDate {
+    // Mean length of a Hebrew year, 35975351/98496 days; the estimate can be a year off
+    // either way.
+    let approx = (98_496 * (fixed - HEBREW_EPOCH)).div_euclid(35_975_351) + 1;
+    let year = (approx - 1..)
+        .take_while(|&year| hebrew_new_year(year) <= fixed)
+        .last()
+        .expect("the estimate is at most a year ahead");
+    let start = if fixed < fixed_from_hebrew(year, 1, 1) {
+        TISHREI
+    } else {
+        1
+    };
+    let month = (start..)
+        .find(|&m| fixed <= fixed_from_hebrew(year, m, last_day_of_hebrew_month(year, m)))
+        .expect("every day falls in a month");
+    let day = fixed - fixed_from_hebrew(year, month, 1) + 1;
+    let month_name = match month {
+        1 => "Nisan",
+        2 => "Iyyar",
+        3 => "Sivan",
+        4 => "Tammuz",
+        5 => "Av",
+        6 => "Elul",
+        7 => "Tishrei",
+        8 => "Cheshvan",
+        9 => "Kislev",
+        10 => "Tevet",
+        11 => "Shevat",
+        12 if hebrew_leap_year(year) => "Adar I",
+        12 => "Adar",
+        _ => "Adar II",
+    };
+    CalendarDate {
+        year,
+        month: month as u32,
+        day: day as u32,
+        month_name,
+        era: None,
+    }
+}
+
+const PERSIAN_MONTHS: [&str; 12] = [
+    "Farvardin",
+    "Ordibehesht",
+    "Khordad",
+    "Tir",
+    "Mordad",
+    "Shahrivar",
+    "Mehr",
+    "Aban",
+    "Azar",
+    "Dey",
+    "Bahman",
+    "Esfand",
+];
+
+/// Persian years at which the 33-year leap cycle shifts (Borkowski, 1996).
+const PERSIAN_BREAKS: [i64; 20] = [
+    -61, 9, 38, 199, 426, 686, 756, 818, 1111, 1181, 1210, 1635, 2060, 2097, 2192, 2262, 2324,
+    2394, 2456, 3178,
+];
+
+/// The day of March (Gregorian) on which Persian `year` starts. Exact from the first
+/// break to the last, 560 to 3799 CE; beyond them the nearest cycle is carried on.
+fn nowruz(year: i64) -> u32 {
+    let gregorian_year = year + 621;
+    let mut leap_persian = -14;
+    let mut previous = PERSIAN_BREAKS[0];
+    let mut jump = 0;
+    for &next in &PERSIAN_BREAKS[1..] {
+        jump = next - previous;
+        if year < next {
+            break;
+        }
+        leap_persian += jump / 33 * 8 + jump % 33 / 4;
+        previous = next;
+    }
+    let n = year - previous;
+    leap_persian += n / 33 * 8 + (n % 33 + 3) / 4;
+    if jump % 33 == 4 && jump - n == 4 {
+        leap_persian += 1;
+    }
+    let leap_gregorian = gregorian_year / 4 - (gregorian_year / 100 + 1) * 3 / 4 - 150;
+    (20 + leap_persian - leap_gregorian) as u32
+}
+
+/// Borkowski's algorithm as adapted in jalaali-js, counting from 1 Farvardin.
+fn persian(date: NaiveDate) -> CalendarDate {
+    let new_year = |year: i64| {
+        NaiveDate::from_ymd_opt((year + 621) as i32, 3, nowruz(year)).expect("valid Nowruz")
+    };
+    let mut year = i64::from(date.year()) - 621;
+    if date < new_year(year) {
+        year -= 1;
+    }
+    let day_of_year = (date - new_year(year)).num_days();
+    let (month, day) = if day_of_year < 186 {
+        (day_of_year / 31, day_of_year % 31)
+    } else {
+        (6 + (day_of_year - 186) / 30, (day_of_year - 186) % 30)
+    };
+    CalendarDate {
+        year,
+        month: month as u32 + 1,
+        day: day as u32 + 1,
+        month_name: PERSIAN_MONTHS[month as usize],
+        era: None,
+    }
+}
+
+/// Julian day number of fixed day 0.
+const FIXED_TO_JDN: i64 = 1_721_425;
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// Richards' conversion from Julian day numbers, *Explanatory Supplement to the
+/// Astronomical Almanac*, ch. 15.
+fn julian(fixed: i64) -> CalendarDate {
+    let c = fixed + FIXED_TO_JDN + 32_082;
+    let d = (4 * c + 3).div_euclid(1461);
+    let e = c - (1461 * d).div_euclid(4);
+    let m = (5 * e + 2).div_euclid(153);
+    let month = m + 3 - 12 * (m / 10);
+    CalendarDate {
+        year: d - 4800 + m / 10,
+        month: month as u32,
+        day: (e - (153 * m + 2).div_euclid(5) + 1) as u32,
+        month_name: MONTH_NAMES[month as usize - 1],
+        era: None,
+    }
+}
+
+struct JapaneseEra {
+    name: &'static str,
+    initial: char,
+    /// First day, (year, month, day).
+    start: (i32, u32, u32),
+}
+
+/// The modern eras, latest first.
+const JAPANESE_ERAS: [JapaneseEra; 5] = [
+    JapaneseEra {
+        name: "令和",
+        initial: 'R',
+        start: (2019, 5, 1),
+    },
+    JapaneseEra {
+        name: "平成",
+        initial: 'H',
+        start: (1989, 1, 8),
+    },
+    JapaneseEra {
+        name: "昭和",
+        initial: 'S',
+        start: (1926, 12, 25),
+    },
+    JapaneseEra {
+        name: "大正",
+        initial: 'T',
+        start: (1912, 7, 30),
+    },
+    JapaneseEra {
+        name: "明治",
+        initial: 'M',
+        start: (1868, 10, 23),
+    },
+];
+
+const JAPANESE_MONTHS: [&str; 12] = [
+    "1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月",
+];
+
+/// Gregorian months and days, with years counted from the start of the era, the first
+/// being year 1.
+fn japanese(date: NaiveDate) -> CalendarDate {
+    let era = JAPANESE_ERAS.iter().find(|era| {
+        let (year, month, day) = era.start;
+        NaiveDate::from_ymd_opt(year, month, day).is_some_and(|start| date >= start)
+    });
+    let year = i64::from(date.year());
+    CalendarDate {
+        year: era.map_or(year, |era| year - i64::from(era.start.0) + 1),
+        month: date.month(),
+        day: date.day(),
+        month_name: JAPANESE_MONTHS[date.month0() as usize],
+        era: era.map(|era| era.name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn on(system: CalendarSystem, date: &str) -> String {
+        system.format(date.parse().unwrap())
+    }
+
+    #[test]
+    fn converts_to_the_hebrew_calendar() {
+        use CalendarSystem::Hebrew;
+        assert_eq!(on(Hebrew, "2023-11-14"), "1 Kislev 5784");
+        assert_eq!(on(Hebrew, "2023-09-16"), "1 Tishrei 5784");
+        assert_eq!(on(Hebrew, "2023-09-15"), "29 Elul 5783");
+        assert_eq!(on(Hebrew, "2024-03-25"), "15 Adar II 5784");
+        assert_eq!(on(Hebrew, "2024-02-24"), "15 Adar I 5784");
+        assert_eq!(on(Hebrew, "2023-04-06"), "15 Nisan 5783");
+    }
+
+    #[test]
+    fn converts_to_the_islamic_calendar() {
+        use CalendarSystem::Islamic;
+        assert_eq!(on(Islamic, "2023-03-23"), "1 Ramadan 1444");
+        assert_eq!(on(Islamic, "622-07-19"), "1 Muharram 1");
+        let d = Islamic.convert("2023-07-19".parse().unwrap());
+        assert_eq!((d.year, d.month, d.day), (1445, 1, 1));
+    }
+
+    #[test]
+    fn converts_to_the_persian_calendar() {
+        use CalendarSystem::Persian;
+        assert_eq!(on(Persian, "2023-11-14"), "23 Aban 1402");
+        assert_eq!(on(Persian, "2023-03-21"), "1 Farvardin 1402");
+        assert_eq!(on(Persian, "2023-03-20"), "29 Esfand 1401");
+        // 1403 is a leap year, so its Esfand has 30 days and 1404 starts a day later.
+        assert_eq!(on(Persian, "2025-03-20"), "30 Esfand 1403");
+        assert_eq!(on(Persian, "2025-03-21"), "1 Farvardin 1404");
+        assert_eq!(on(Persian, "2023-09-22"), "31 Shahrivar 1402");
+        assert_eq!(on(Persian, "2023-09-23"), "1 Mehr 1402");
+    }
+
+    #[test]
+    fn converts_to_the_julian_calendar() {
+        use CalendarSystem::Julian;
+        assert_eq!(on(Julian, "2023-11-14"), "1 November 2023");
+        // The reform: Thursday 4 October 1582 (Julian) was followed by Friday the 15th.
+        assert_eq!(on(Julian, "1582-10-14"), "4 October 1582");
+        assert_eq!(on(Julian, "1582-10-15"), "5 October 1582");
+        // Orthodox Christmas.
+        assert_eq!(on(Julian, "2024-01-07"), "25 December 2023");
+        // Julian leap years include 1900, so the calendars drift a day apart there.
+        assert_eq!(on(Julian, "1900-03-13"), "29 February 1900");
+        assert_eq!(on(Julian, "-0001-12-30"), "1 January 0");
+    }
+
+    #[test]
+    fn counts_japanese_eras() {
+        use CalendarSystem::{Japanese, JapaneseAscii};
+        assert_eq!(on(Japanese, "2023-11-14"), "令和5年11月14日");
+        assert_eq!(on(JapaneseAscii, "2023-11-14"), "R5.11.14");
+        assert_eq!(on(Japanese, "2019-05-01"), "令和元年5月1日");
+        assert_eq!(on(Japanese, "2019-04-30"), "平成31年4月30日");
+        assert_eq!(on(JapaneseAscii, "1989-01-07"), "S64.1.7");
+        assert_eq!(on(Japanese, "1868-10-22"), "1868年10月22日");
+        assert_eq!(on(JapaneseAscii, "1868-10-22"), "1868.10.22");
+    }
+}
@@ -0,0 +1,56 @@
+//! `--paste`: reading INPUT from the system clipboard through the platform's own tool, so
+//! no clipboard library is linked in.
+
+use std::io;
+use std::process::Command;
+
+/// Overrides the tools below with a shell command whose output is the clipboard.
+pub const COMMAND_VAR: &str = "EPOCH_PASTE_COMMAND";
+
+/// Tried in order until one runs: macOS, Wayland, X11 (two tools), Windows.
+const TOOLS: &[(&str, &[&str])] = &[
+    ("pbpaste", &[]),
+    ("wl-paste", &["--no-newline"]),
+    ("xclip", &["-selection", "clipboard", "-out"]),
+    ("xsel", &["--clipboard", "--output"]),
+    ("powershell", &["-NoProfile", "-Command", "Get-Clipboard"]),
+];
+
+/// The clipboard's text, trimmed.
+pub fn read() -> io::Result<String> {
+    if let Ok(command) = std::env::var(COMMAND_VAR) {
+        return run(Command::new("sh").args(["-c", &command]));
+    }
+    for (tool, args) in TOOLS {
+        match run(Command::new(tool).args(*args)) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            result => return result,
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        format!(
+            "no clipboard tool found (tried pbpaste, wl-paste, xclip, xsel, powershell; set {COMMAND_VAR} to use another)"
+        ),
+    ))
+}
+
+fn run(command: &mut Command) -> io::Result<String> {
+    let out = command.output()?;
+    if !out.status.success() {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        return Err(io::Error::other(format!(
+            "clipboard tool failed: {}",
+            stderr.trim()
+        )));
+    }
+    let text = String::from_utf8(out.stdout)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "clipboard is not text"))?;
+    match text.trim() {
+        "" => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "clipboard is empty",
+        )),
+        text => Ok(text.to_string()),
+    }
+}
@@ -0,0 +1,159 @@
+//! `epoch daemon`: newline-delimited JSON requests on stdin, one JSON response per line
+//! on stdout, so callers can keep a single warm process.
+//!
+//! A request is an object with `input` plus any of the `serve` query parameters
+//! (`input_tz`, `output_tz`, `ts`, `prefer_zone`, `fiscal_start`, `schema`);
+//! `"method": "now"` ignores `input`. An `id` member is echoed back. Requests carrying
+//! `"jsonrpc": "2.0"` take their options from `params` and get JSON-RPC 2.0 envelopes in
+//! return; those without an `id` are notifications and get no response at all.
+
+use serde_json::{Map, Value, json};
+use std::io::{self, BufRead, Write};
+
+use crate::serve;
+
+/// JSON-RPC 2.0 error codes.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Serve requests until stdin closes.
+pub fn run() -> io::Result<()> {
+    let stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+    for line in stdin.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = respond(&line) {
+            writeln!(stdout, "{response}")?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// The response line for one request line, if it gets one.
+pub fn respond(line: &str) -> Option<Value> {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(v) => v,
+        Err(e) => {
+            return Some(reply(
+                None,
+                false,
+                Err((PARSE_ERROR, format!("invalid JSON: {e}"))),
+            ));
+        }
+    };
+    let rpc = request.get("jsonrpc").is_some();
+    let id = request.get("id").cloned();
+    if rpc && id.is_none() {
+        return None;
+    }
+
+    let fields = if rpc {
+        request.get("params").cloned().unwrap_or(json!({}))
+    } else {
+        request.clone()
+    };
+    let result = match (fields.as_object(), request.as_object()) {
+        (Some(fields), Some(_)) => evaluate(request.get("method"), fields),
+        _ => Err((INVALID_REQUEST, "request must be a JSON object".to_string())),
+    };
+    Some(reply(id, rpc, result))
+}
+
+fn evaluate(method: Option<&Value>, fields: &Map<String, Value>) -> Result<Value, (i64, String)> {
+    let input = match method.and_then(Value::as_str).unwrap_or("parse") {
+        "parse" => match fields.get("input") {
+            Some(Value::String(s)) => s.as_str(),
+            Some(Value::Number(n)) => &n.to_string(),
+            _ => return Err((INVALID_PARAMS, "missing `input`".to_string())),
+        },
+        "now" => "now",
+        other => return Err((METHOD_NOT_FOUND, format!("unknown method `{other}`"))),
+    };
+
+    let params: Vec<(String, String)> = fields
+        .iter()
+        .filter(|(k, _)| *k != "input")
+        .filter_map(|(k, v)| {
+            let v = match v {
+                Value::String(s) => s.clone(),
+                Value::Array(items) => items
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join(","),
+                Value::Number(n) => n.to_string(),
+                _ => return None,
+            };
+            Some((k.clone(), v))
+        })
+        .collect();
+
+    serve::parse_request(input, &params).map_err(|msg| (INVALID_PARAMS, msg))
+}
+
+fn reply(id: Option<Value>, rpc: bool, result: Result<Value, (i64, String)>) -> Value {
+    if rpc {
+        let mut obj = json!({ "jsonrpc": "2.0", "id": id });
+        match result {
+            Ok(v) => obj["result"] = v,
+            Err((code, message)) => obj["error"] = json!({ "code": code, "message": message }),
+        }
+        return obj;
+    }
+
+    let mut obj = match result {
+        Ok(v) => v,
+        Err((_, message)) => json!({ "error": message }),
+    };
+    if let Some(id) = id {
+        obj["id"] = id;
+    }
+    obj
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers_plain_requests() {
+        let v = respond(r#"{"id": 7, "input": "1700000000", "output_tz": "Asia/Tokyo"}"#).unwrap();
+        assert_eq!(v["id"], 7);
+        assert_eq!(v["rfc3339"], "2023-11-15T07:13:20+09:00");
+
+        let v = respond(r#"{"input": 1700000000000}"#).unwrap();
+        assert_eq!(v["ts_unit"], "millis");
+
+        let v = respond(r#"{"id": "a", "input": "nope"}"#).unwrap();
+        assert_eq!(v["id"], "a");
+        assert!(v["error"].is_string());
+    }
+
+    #[test]
+    fn answers_json_rpc_requests() {
+        let v = respond(
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "parse", "params": {"input": "1700000000"}}"#,
+        )
+        .unwrap();
+        assert_eq!(v["id"], 1);
+        assert_eq!(v["result"]["unix_seconds"], 1_700_000_000);
+
+        let v = respond(r#"{"jsonrpc": "2.0", "id": 2, "method": "bogus"}"#).unwrap();
+        assert_eq!(v["error"]["code"], METHOD_NOT_FOUND);
+
+        let notification = r#"{"jsonrpc": "2.0", "method": "parse", "params": {"input": "x"}}"#;
+        assert_eq!(respond(notification), None);
+
+        let v = respond("{not json").unwrap();
+        assert_eq!(
+            v["error"],
+            "invalid JSON: key must be a string at line 1 column 2"
+        );
+    }
+}
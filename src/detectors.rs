@@ -0,0 +1,274 @@
+//! Site-specific input formats declared outside the crate (`--detectors`), tried before or
+//! after the built-in ones (`scan` feature, for the regex engine).
+//!
+//! A detector either reads a strftime pattern, optionally from the part of the input a
+//! regex picks out, or hands the input to an external command that prints the instant.
+
+use regex::Regex;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::zone::TzChoice;
+use crate::{EpochParser, Parsed, ParsedAs, Trace, layouts};
+
+/// When a detector is tried relative to the built-in formats.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Position {
+    /// Ahead of every built-in format, so it can claim input they would also read.
+    #[default]
+    Before,
+    /// Only once every built-in format has failed.
+    After,
+}
+
+/// How a detector reads its input.
+#[derive(Clone, Debug)]
+pub enum Reader {
+    /// A strftime pattern, as with `--input-format`. With a regex, only input it matches
+    /// is read, taking the `ts` capture group if there is one and the whole match if not;
+    /// a match that does not fit the pattern is then an error rather than a miss. `tz`
+    /// replaces `--input-tz` for times without an offset.
+    Pattern {
+        regex: Option<Regex>,
+        format: String,
+        tz: Option<TzChoice>,
+    },
+    /// A shell command that gets the input on stdin and prints an instant in any
+    /// built-in format, or nothing (or fails) when the input is not its to read.
+    Command(String),
+}
+
+/// A named custom format.
+#[derive(Clone, Debug)]
+pub struct Detector {
+    pub name: String,
+    pub reader: Reader,
+    pub position: Position,
+}
+
+impl Detector {
+    /// `None` when the input is not this detector's to read.
+    fn read(&self, input: &str, p: &EpochParser) -> Option<Parsed> {
+        match &self.reader {
+            Reader::Pattern { regex, format, tz } => {
+                let text = match regex {
+                    Some(regex) => {
+                        let caps = regex.captures(input)?;
+                        caps.name("ts").or_else(|| caps.get(0))?.as_str()
+                    }
+                    None => input,
+                };
+                let zoned;
+                let p = match tz {
+                    Some(tz) => {
+                        zoned = p.clone().input_tz(*tz);
+                        &zoned
+                    }
+                    None => p,
+                };
+                match layouts::custom(text, format, p) {
+                    Err(_) if regex.is_none() => None,
+                    result => Some(result),
+                }
+            }
+            Reader::Command(command) => {
+                let printed = run(command, input)?;
+                let result = EpochParser::new()
+                    .input_tz(TzChoice::Utc)
+                    .strict(true)
+                    .parse(&printed)
+                    .map(|(dt, _)| {
+                        let parsed_as = ParsedAs::Layout {
+                            name: "command",
+                            offset: None,
+                        };
+                        (dt, parsed_as)
+                    });
+                Some(result)
+            }
+        }
+    }
+}
+
+/// What `command` prints for `input`; `None` if it fails or prints nothing.
+fn run(command: &str, input: &str) -> Option<String> {
+    let mut child = Command::new("sh")
+        .args(["-c", command])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .ok()?;
+    // A command that exits without reading its input closes the pipe; that is its answer.
+    let _ = child.stdin.take()?.write_all(input.as_bytes());
+    let out = child.wait_with_output().ok()?;
+    let printed = String::from_utf8(out.stdout).ok()?;
+    (out.status.success() && !printed.trim().is_empty()).then(|| printed.trim().to_string())
+}
+
+/// The first of `p`'s detectors at `position` that reads `input`.
+pub(crate) fn read(
+    input: &str,
+    p: &EpochParser,
+    position: Position,
+    trace: &mut Trace,
+) -> Option<Parsed> {
+    for detector in p.detectors.iter().filter(|d| d.position == position) {
+        if let Some(result) = detector.read(input, p) {
+            trace.note(|| format!("{} (custom): yes", detector.name));
+            return Some(result);
+        }
+        trace.note(|| format!("{} (custom): no", detector.name));
+    }
+    None
+}
+
+/// Detectors declared in JSON:
+///
+/// ```json
+/// {"detectors": [
+///   {"name": "app", "regex": "^APP\\[(?P<ts>[^\\]]+)\\]", "format": "%d.%m.%Y %H:%M",
+///    "tz": "Europe/Berlin"},
+///   {"name": "legacy", "command": "legacy-ts --decode", "position": "after"}
+/// ]}
+/// ```
+///
+/// Each needs a `name` and either `format` (with optional `regex` and `tz`) or `command`;
+/// `position` is `before` (the default) or `after`.
+#[cfg(feature = "json")]
+pub fn from_json(text: &str) -> Result<Vec<Detector>, String> {
+    use serde_json::Value;
+
+    let config: Value = serde_json::from_str(text).map_err(|e| format!("invalid JSON: {e}"))?;
+    let entries = config
+        .get("detectors")
+        .and_then(Value::as_array)
+        .ok_or("expected an object with a `detectors` array")?;
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let string = |key: &str| match entry.get(key) {
+                None => Ok(None),
+                Some(Value::String(s)) => Ok(Some(s.clone())),
+                Some(_) => Err(format!("detector {}: `{key}` must be a string", i + 1)),
+            };
+            let name = string("name")?.ok_or(format!("detector {}: missing `name`", i + 1))?;
+            let context = |e: String| format!("detector `{name}`: {e}");
+            let position = match string("position")?.as_deref() {
+                None | Some("before") => Position::Before,
+                Some("after") => Position::After,
+                Some(other) => {
+                    return Err(context(format!(
+                        "position must be `before` or `after`, got `{other}`"
+                    )));
+                }
+            };
+            let reader = match (string("format")?, string("command")?) {
+                (Some(format), None) => {
+                    crate::check_strftime(&format).map_err(|e| context(e.to_string()))?;
+                    let regex = string("regex")?
+                        .map(|r| Regex::new(&r).map_err(|e| context(e.to_string())))
+                        .transpose()?;
+                    let tz = string("tz")?
+                        .map(|tz| tz.parse().map_err(context))
+                        .transpose()?;
+                    Reader::Pattern { regex, format, tz }
+                }
+                (None, Some(command)) => Reader::Command(command),
+                _ => return Err(context("needs exactly one of `format` or `command`".into())),
+            };
+            Ok(Detector {
+                name,
+                reader,
+                position,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(name: &str, regex: Option<&str>, format: &str, position: Position) -> Detector {
+        Detector {
+            name: name.to_string(),
+            reader: Reader::Pattern {
+                regex: regex.map(|r| Regex::new(r).unwrap()),
+                format: format.to_string(),
+                tz: Some(TzChoice::Utc),
+            },
+            position,
+        }
+    }
+
+    #[test]
+    fn pattern_detectors_run_before_or_after_the_built_ins() {
+        let p = EpochParser::new().detectors(vec![
+            pattern(
+                "app",
+                Some(r"^APP\[(?P<ts>[^\]]+)\]"),
+                "%d.%m.%Y %H:%M",
+                Position::Before,
+            ),
+            // Would read eight-digit dates, but the built-in date reading goes first.
+            pattern("yyyymmdd", None, "%Y%m%d", Position::After),
+        ]);
+        let (dt, parsed_as) = p.parse("APP[14.11.2023 22:13] started").unwrap();
+        assert_eq!(dt.timestamp(), 1_699_999_980);
+        assert!(matches!(parsed_as, ParsedAs::Layout { name: "custom", .. }));
+        assert!(p.parse("APP[14/11/2023] started").is_err());
+
+        let (_, parsed_as) = p.parse("20231114").unwrap();
+        assert!(matches!(parsed_as, ParsedAs::Layout { name: "date", .. }));
+    }
+
+    #[test]
+    fn command_detectors_print_the_instant() {
+        let command = |script: &str| Detector {
+            name: "cmd".to_string(),
+            reader: Reader::Command(script.to_string()),
+            position: Position::Before,
+        };
+        let p = EpochParser::new().detectors(vec![command(
+            r#"read x; [ "$x" = hello ] && echo 2023-11-14T22:13:20Z"#,
+        )]);
+        let (dt, parsed_as) = p.parse("hello").unwrap();
+        assert_eq!(dt.timestamp(), 1_700_000_000);
+        assert!(matches!(
+            parsed_as,
+            ParsedAs::Layout {
+                name: "command",
+                ..
+            }
+        ));
+        // Printing nothing passes the input on to the built-ins.
+        assert_eq!(p.parse("1700000000").unwrap().0.timestamp(), 1_700_000_000);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn reads_json_declarations() {
+        let detectors = from_json(
+            r#"{"detectors": [
+                {"name": "app", "regex": "^APP", "format": "%Y", "tz": "+02:00"},
+                {"name": "legacy", "command": "cat", "position": "after"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(detectors.len(), 2);
+        assert_eq!(detectors[1].position, Position::After);
+        assert!(matches!(detectors[1].reader, Reader::Command(_)));
+
+        let err = |json| from_json(json).unwrap_err();
+        assert_eq!(
+            err(r#"{"detectors": [{"format": "%Y"}]}"#),
+            "detector 1: missing `name`"
+        );
+        assert!(err(r#"{"detectors": [{"name": "x"}]}"#).contains("exactly one"));
+        assert!(
+            err(r#"{"detectors": [{"name": "x", "format": "%Y", "regex": "("}]}"#).contains("`x`")
+        );
+        assert!(err("[]").contains("`detectors` array"));
+    }
+}
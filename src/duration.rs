@@ -0,0 +1,300 @@
+//! Duration literals: compact `1h30m` / `2d4h` / `90s` / `1w` and ISO 8601 `PT1H30M`.
+
+use chrono::Duration;
+
+const UNITS_HINT: &str = "expected e.g. 90s, 1h30m, 2d4h, 1w or PT1H30M";
+
+/// Parse a duration literal; a leading `-` negates it.
+///
+/// Compact literals are one or more `<N><unit>` pairs with units `w`, `d`, `h`, `m`, `s`
+/// or `ms`. ISO 8601 literals use the `P[nW][nD][T[nH][nM][nS]]` designators; years and
+/// months are rejected because they are not fixed lengths of time. Days are always 24h.
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let (negative, body) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    if body.is_empty() {
+        return Err(format!("invalid duration `{s}`: {UNITS_HINT}"));
+    }
+
+    let d = if body.starts_with(['P', 'p']) {
+        parse_iso(&body[1..]).map_err(|e| format!("invalid ISO 8601 duration `{s}`: {e}"))?
+    } else {
+        parse_compact(body).map_err(|e| format!("invalid duration `{s}`: {e}"))?
+    };
+    Ok(if negative { -d } else { d })
+}
+
+/// How `diff` renders the span between two instants.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum DurationFormat {
+    /// Signed seconds, e.g. `-5400` or `1.500`
+    #[default]
+    Seconds,
+    /// ISO 8601, e.g. `P2DT3H4M5S`
+    Iso,
+    /// Words, e.g. `2 days 3 hours 4 minutes`
+    Human,
+}
+
+/// `1 day`, `2 days`.
+pub fn plural(n: i64, unit: &str) -> String {
+    if n.abs() == 1 {
+        format!("{n} {unit}")
+    } else {
+        format!("{n} {unit}s")
+    }
+}
+
+/// Render `d` in words, largest units first: `2 days 3 hours 4 minutes`.
+///
+/// `max_units` keeps only that many of the largest non-zero units; the rest is dropped,
+/// not rounded. Negative spans are prefixed with `-`.
+pub fn format_human(d: Duration, max_units: Option<usize>) -> String {
+    let sign = if d < Duration::zero() { "-" } else { "" };
+    let millis = d.num_milliseconds().unsigned_abs() as i64;
+    let parts = [
+        (millis / 86_400_000, "day"),
+        (millis % 86_400_000 / 3_600_000, "hour"),
+        (millis % 3_600_000 / 60_000, "minute"),
+        (millis % 60_000 / 1000, "second"),
+        (millis % 1000, "millisecond"),
+    ];
+
+    let words: Vec<String> = parts
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .take(max_units.unwrap_or(usize::MAX))
+        .map(|&(n, unit)| plural(n, unit))
+        .collect();
+    if words.is_empty() {
+        return "0 seconds".to_string();
+    }
+    format!("{sign}{}", words.join(" "))
+}
+
+/// Render `d` as an ISO 8601 duration using days and smaller designators: `P2DT3H4M5S`,
+/// `PT0.5S`, `-PT1H`. Days are 24h, matching what [`parse_duration`] accepts.
+pub fn format_iso(d: Duration) -> String {
+    if d.is_zero() {
+        return "PT0S".to_string();
+    }
+    let sign = if d < Duration::zero() { "-" } else { "" };
+    let millis = d.num_milliseconds().unsigned_abs();
+    let (secs, frac) = (millis / 1000, millis % 1000);
+    let (days, hours, minutes, seconds) = (
+        secs / 86_400,
+        secs % 86_400 / 3600,
+        secs % 3600 / 60,
+        secs % 60,
+    );
+
+    let mut out = format!("{sign}P");
+    if days > 0 {
+        out += &format!("{days}D");
+    }
+    if hours > 0 || minutes > 0 || seconds > 0 || frac > 0 {
+        out.push('T');
+        if hours > 0 {
+            out += &format!("{hours}H");
+        }
+        if minutes > 0 {
+            out += &format!("{minutes}M");
+        }
+        if frac > 0 {
+            let frac = format!("{frac:03}");
+            out += &format!("{seconds}.{}S", frac.trim_end_matches('0'));
+        } else if seconds > 0 {
+            out += &format!("{seconds}S");
+        }
+    }
+    out
+}
+
+/// Split `s` into `(number, designator)` pairs, e.g. `1h30m` -> `[(1, "h"), (30, "m")]`.
+fn components(s: &str) -> Result<Vec<(i64, &str)>, String> {
+    let mut out = Vec::new();
+    let mut rest = s;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if digits == 0 {
+            return Err(format!("expected a number before `{rest}`"));
+        }
+        let n: i64 = rest[..digits]
+            .parse()
+            .map_err(|_| format!("number `{}` is too large", &rest[..digits]))?;
+        rest = &rest[digits..];
+
+        let unit = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if unit == 0 {
+            return Err(format!("missing unit after `{n}`"));
+        }
+        out.push((n, &rest[..unit]));
+        rest = &rest[unit..];
+    }
+    Ok(out)
+}
+
+fn scaled(n: i64, unit_seconds: i64) -> Result<Duration, String> {
+    n.checked_mul(unit_seconds)
+        .and_then(Duration::try_seconds)
+        .ok_or_else(|| "duration out of range".to_string())
+}
+
+fn add(total: Duration, part: Duration) -> Result<Duration, String> {
+    total
+        .checked_add(&part)
+        .ok_or_else(|| "duration out of range".to_string())
+}
+
+fn parse_compact(s: &str) -> Result<Duration, String> {
+    let mut total = Duration::zero();
+    let mut last_rank = usize::MAX;
+    for (n, unit) in components(s)? {
+        let (rank, part) = match unit.to_ascii_lowercase().as_str() {
+            "w" => (5, scaled(n, 7 * 86_400)?),
+            "d" => (4, scaled(n, 86_400)?),
+            "h" => (3, scaled(n, 3600)?),
+            "m" => (2, scaled(n, 60)?),
+            "s" => (1, scaled(n, 1)?),
+            "ms" => (
+                0,
+                Duration::try_milliseconds(n).ok_or("duration out of range")?,
+            ),
+            _ => return Err(format!("unknown unit `{unit}`; {UNITS_HINT}")),
+        };
+        if rank >= last_rank {
+            return Err(format!(
+                "unit `{unit}` is repeated or out of order (largest units first)"
+            ));
+        }
+        last_rank = rank;
+        total = add(total, part)?;
+    }
+    Ok(total)
+}
+
+fn parse_iso(s: &str) -> Result<Duration, String> {
+    let (date, time) = match s.split_once(['T', 't']) {
+        Some((_, "")) => return Err("`T` must be followed by hours, minutes or seconds".into()),
+        Some((date, time)) => (date, Some(time)),
+        None => (date_part_or_empty(s)?, None),
+    };
+    if date.is_empty() && time.is_none() {
+        return Err("no components after `P`".into());
+    }
+
+    let mut total = Duration::zero();
+    let mut last_rank = usize::MAX;
+    for (n, unit) in components(date)? {
+        let (rank, part) = match unit {
+            "W" | "w" => (1, scaled(n, 7 * 86_400)?),
+            "D" | "d" => (0, scaled(n, 86_400)?),
+            "Y" | "y" | "M" | "m" => {
+                return Err("years and months are not fixed lengths; use weeks or days".into());
+            }
+            _ => return Err(format!("unknown designator `{unit}` before `T`")),
+        };
+        if rank >= last_rank {
+            return Err(format!("designator `{unit}` is repeated or out of order"));
+        }
+        last_rank = rank;
+        total = add(total, part)?;
+    }
+
+    let mut last_rank = usize::MAX;
+    for (n, unit) in components(time.unwrap_or(""))? {
+        let (rank, part) = match unit {
+            "H" | "h" => (2, scaled(n, 3600)?),
+            "M" | "m" => (1, scaled(n, 60)?),
+            "S" | "s" => (0, scaled(n, 1)?),
+            _ => return Err(format!("unknown designator `{unit}` after `T`")),
+        };
+        if rank >= last_rank {
+            return Err(format!("designator `{unit}` is repeated or out of order"));
+        }
+        last_rank = rank;
+        total = add(total, part)?;
+    }
+    Ok(total)
+}
+
+/// `P1H` is a common mistake for `PT1H`; point it out instead of a generic error.
+fn date_part_or_empty(s: &str) -> Result<&str, String> {
+    if s.contains(['H', 'h', 'S', 's']) {
+        return Err("hours, minutes and seconds go after `T`, e.g. PT1H30M".into());
+    }
+    Ok(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(s: &str) -> Result<i64, String> {
+        parse_duration(s).map(|d| d.num_seconds())
+    }
+
+    #[test]
+    fn parses_compact_literals() {
+        assert_eq!(secs("90s"), Ok(90));
+        assert_eq!(secs("1h30m"), Ok(5400));
+        assert_eq!(secs("2d4h"), Ok(2 * 86_400 + 4 * 3600));
+        assert_eq!(secs("1w"), Ok(7 * 86_400));
+        assert_eq!(secs("-15m"), Ok(-900));
+        assert_eq!(
+            parse_duration("1s500ms").map(|d| d.num_milliseconds()),
+            Ok(1500)
+        );
+    }
+
+    #[test]
+    fn parses_iso_literals() {
+        assert_eq!(secs("PT1H30M"), Ok(5400));
+        assert_eq!(secs("P2DT3H4M5S"), Ok(2 * 86_400 + 3 * 3600 + 4 * 60 + 5));
+        assert_eq!(secs("P1W"), Ok(7 * 86_400));
+        assert_eq!(secs("-PT90S"), Ok(-90));
+    }
+
+    #[test]
+    fn formats_iso() {
+        let d = Duration::seconds(2 * 86_400 + 3 * 3600 + 4 * 60 + 5);
+        assert_eq!(format_iso(d), "P2DT3H4M5S");
+        assert_eq!(format_iso(Duration::days(3)), "P3D");
+        assert_eq!(format_iso(-Duration::minutes(90)), "-PT1H30M");
+        assert_eq!(format_iso(Duration::milliseconds(1500)), "PT1.5S");
+        assert_eq!(format_iso(Duration::zero()), "PT0S");
+
+        // round-trips through the parser
+        assert_eq!(parse_duration(&format_iso(d)), Ok(d));
+    }
+
+    #[test]
+    fn formats_human() {
+        let d = Duration::seconds(2 * 86_400 + 3 * 3600 + 4 * 60 + 5);
+        assert_eq!(format_human(d, None), "2 days 3 hours 4 minutes 5 seconds");
+        assert_eq!(format_human(d, Some(2)), "2 days 3 hours");
+        assert_eq!(format_human(Duration::minutes(61), None), "1 hour 1 minute");
+        assert_eq!(format_human(-Duration::seconds(90), Some(1)), "-1 minute");
+        assert_eq!(format_human(Duration::zero(), None), "0 seconds");
+    }
+
+    #[test]
+    fn explains_bad_literals() {
+        assert!(secs("").is_err());
+        assert!(secs("5").unwrap_err().contains("missing unit"));
+        assert!(secs("h").unwrap_err().contains("expected a number"));
+        assert!(secs("3y").unwrap_err().contains("unknown unit `y`"));
+        assert!(secs("30m1h").unwrap_err().contains("out of order"));
+        assert!(secs("P1M").unwrap_err().contains("not fixed lengths"));
+        assert!(secs("P1H").unwrap_err().contains("after `T`"));
+        assert!(secs("PT").is_err());
+        assert!(secs("P").is_err());
+    }
+}
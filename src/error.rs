@@ -0,0 +1,58 @@
+//! The library's error type.
+
+use std::error::Error;
+use std::fmt;
+
+/// Why an input could not be turned into an instant.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EpochError {
+    /// The input matched none of the supported formats.
+    UnrecognizedFormat,
+    /// The input has a recognised shape but invalid contents, e.g. week 54 of a year
+    /// or 31 November; the message says which.
+    InvalidInput(String),
+    /// The input names an instant outside the supported range.
+    OutOfRange,
+    /// The wall-clock time is skipped or repeated by a DST transition. `zone` is the
+    /// IANA zone, or `None` for the local zone.
+    AmbiguousLocalTime { zone: Option<String> },
+    /// A zone abbreviation or letter that is not known.
+    UnknownTimezone(String),
+    /// A zone abbreviation used by several regions, with each candidate as
+    /// `region (+HH:MM)`; pick one with `--prefer-zone` / [`crate::EpochParser::prefer_zone`].
+    AmbiguousAbbreviation {
+        abbreviation: String,
+        candidates: Vec<String>,
+    },
+    /// A malformed expression such as `now + 3q`.
+    InvalidExpression(String),
+}
+
+impl fmt::Display for EpochError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EpochError::UnrecognizedFormat => f.write_str("Unrecognized input"),
+            EpochError::InvalidInput(msg) | EpochError::InvalidExpression(msg) => f.write_str(msg),
+            EpochError::OutOfRange => f.write_str("Invalid unix timestamp"),
+            EpochError::AmbiguousLocalTime { zone: Some(zone) } => write!(
+                f,
+                "Ambiguous or non-existent local time in {zone} (DST transition)"
+            ),
+            EpochError::AmbiguousLocalTime { zone: None } => {
+                f.write_str("Ambiguous or non-existent local time (DST transition)")
+            }
+            EpochError::UnknownTimezone(name) => write!(f, "Unknown timezone abbreviation: {name}"),
+            EpochError::AmbiguousAbbreviation {
+                abbreviation,
+                candidates,
+            } => write!(
+                f,
+                "Ambiguous timezone abbreviation {abbreviation}: {}",
+                candidates.join(", ")
+            ),
+        }
+    }
+}
+
+impl Error for EpochError {}
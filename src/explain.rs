@@ -0,0 +1,125 @@
+//! Why an input was read the way it was, and how else it could have been read, as
+//! reported by the `explain` subcommand.
+
+use chrono::{DateTime, Utc};
+
+use crate::zone::TzChoice;
+use crate::{EpochError, EpochParser, ParsedAs, TsUnit};
+
+/// How an input was read, with the readings it was not given.
+#[derive(Clone, Debug)]
+pub struct Explanation {
+    pub utc: DateTime<Utc>,
+    pub parsed_as: ParsedAs,
+    /// The detection steps tried, the unit picked and why, and the zone applied, as
+    /// [`EpochParser::parse_traced`] reports them.
+    pub steps: Vec<String>,
+    pub alternatives: Vec<Alternative>,
+}
+
+/// Another plausible reading of the same input.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Alternative {
+    /// What would have to change, e.g. `as millis` or `in UTC`.
+    pub reading: String,
+    pub utc: DateTime<Utc>,
+}
+
+/// Explain how `parser` reads `input`.
+///
+/// Numeric input is also read in the other units that give a valid instant. Wall-clock
+/// input without its own offset is also read in UTC and in local time, where those differ
+/// from the input zone used.
+pub fn explain(parser: &EpochParser, input: &str) -> Result<Explanation, EpochError> {
+    let (result, steps) = parser.parse_traced(input);
+    let (utc, parsed_as) = result?;
+
+    let mut alternatives = Vec::new();
+    let mut try_reading = |reading: String, alt: &EpochParser| {
+        if let Ok((alt_utc, _)) = alt.parse(input)
+            && alt_utc != utc
+            && alternatives.iter().all(|a: &Alternative| a.utc != alt_utc)
+        {
+            alternatives.push(Alternative {
+                reading,
+                utc: alt_utc,
+            });
+        }
+    };
+    match parsed_as {
+        ParsedAs::Timestamp { unit, .. } => {
+            for other in [
+                TsUnit::Seconds,
+                TsUnit::Millis,
+                TsUnit::Micros,
+                TsUnit::Nanos,
+            ] {
+                if other != unit {
+                    let alt = parser.clone().force_unit(other);
+                    try_reading(format!("as {}", other.name()), &alt);
+                }
+            }
+        }
+        ParsedAs::Expression => {}
+        _ if parsed_as.source_offset().is_none() => {
+            let digits = input.bytes().all(|b| b.is_ascii_digit());
+            if matches!(parsed_as.name(), "compact" | "date") && digits {
+                let alt = parser.clone().compact_digits(false);
+                try_reading("as a timestamp (--no-compact)".to_string(), &alt);
+            }
+            for (name, tz) in [
+                ("in UTC", TzChoice::Utc),
+                ("in local time", TzChoice::Local),
+            ] {
+                try_reading(name.to_string(), &parser.clone().input_tz(tz));
+            }
+        }
+        _ => {}
+    }
+
+    Ok(Explanation {
+        utc,
+        parsed_as,
+        steps,
+        alternatives,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offers_the_other_units() {
+        let p = EpochParser::new().input_tz(TzChoice::Utc);
+        let e = explain(&p, "1700000000000").unwrap();
+        assert_eq!(e.utc.timestamp(), 1_700_000_000);
+        let readings: Vec<_> = e
+            .alternatives
+            .iter()
+            .map(|a| (a.reading.as_str(), a.utc.to_rfc3339()))
+            .collect();
+        assert_eq!(
+            readings,
+            [
+                ("as seconds", "+55840-11-08T22:13:20+00:00".to_string()),
+                ("as micros", "1970-01-20T16:13:20+00:00".to_string()),
+                ("as nanos", "1970-01-01T00:28:20+00:00".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn offers_other_zones_for_wall_clock_input() {
+        let p = EpochParser::new().input_tz("+02:00".parse().unwrap());
+        let e = explain(&p, "2023/11/14 22:13:20").unwrap();
+        assert_eq!(e.alternatives[0].reading, "in UTC");
+        assert_eq!(e.alternatives[0].utc.timestamp(), 1_700_000_000);
+
+        // An input that states its offset has no other reading, and neither has one the
+        // parser cannot read at all.
+        let e = explain(&p, "2023-11-14T22:13:20Z").unwrap();
+        assert!(e.alternatives.is_empty());
+        assert!(explain(&p, "garbage").is_err());
+    }
+}
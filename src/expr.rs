@@ -0,0 +1,174 @@
+//! Time expressions: an instant plus or minus durations, e.g. `now - 90m` or
+//! `(2025/01/01 00:00:00) + 6w`.
+//!
+//! ```text
+//! expr    := operand (("+" | "-") duration)*
+//! operand := "now" | "(" INPUT ")" | INPUT
+//! ```
+//!
+//! A bare INPUT operand ends at the first `+` or `-` with whitespace on both sides, so
+//! numeric offsets such as `-05:00` stay part of the input. Parenthesize inputs to be
+//! explicit; after `now` or `)` operators may also be written without spaces.
+
+use chrono::{DateTime, Utc};
+
+use crate::duration;
+
+#[derive(Debug, PartialEq)]
+pub enum ExprError<E> {
+    /// The input has no operators, parentheses or `now`, so it is not an expression at
+    /// all; callers should report their own parse error instead.
+    NotAnExpression,
+    /// The input is an expression but malformed.
+    Syntax(String),
+    /// An operand failed to resolve.
+    Input(E),
+}
+
+/// Evaluate `s`, resolving operands other than `now` with `resolve`.
+pub fn evaluate<E>(
+    s: &str,
+    now: DateTime<Utc>,
+    resolve: &dyn Fn(&str) -> Result<DateTime<Utc>, E>,
+) -> Result<DateTime<Utc>, ExprError<E>> {
+    let (mut value, mut rest) = operand(s.trim(), now, resolve)?;
+
+    loop {
+        rest = rest.trim_start();
+        let Some(op) = rest.chars().next() else {
+            return Ok(value);
+        };
+        if op != '+' && op != '-' {
+            return Err(ExprError::Syntax(format!(
+                "expected + or - before `{rest}`"
+            )));
+        }
+
+        rest = rest[1..].trim_start();
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let literal = &rest[..end];
+        if literal.is_empty() {
+            return Err(ExprError::Syntax(format!("missing duration after `{op}`")));
+        }
+        let d = duration::parse_duration(literal).map_err(ExprError::Syntax)?;
+
+        let next = if op == '+' {
+            value.checked_add_signed(d)
+        } else {
+            value.checked_sub_signed(d)
+        };
+        value = next.ok_or_else(|| ExprError::Syntax("result out of range".to_string()))?;
+        rest = &rest[end..];
+    }
+}
+
+/// Split off the leading operand, returning its value and the remaining text.
+fn operand<'a, E>(
+    s: &'a str,
+    now: DateTime<Utc>,
+    resolve: &dyn Fn(&str) -> Result<DateTime<Utc>, E>,
+) -> Result<(DateTime<Utc>, &'a str), ExprError<E>> {
+    if let Some(inner) = s.strip_prefix('(') {
+        let close = matching_paren(inner)
+            .ok_or_else(|| ExprError::Syntax("unbalanced parentheses".to_string()))?;
+        let value = resolve(inner[..close].trim()).map_err(ExprError::Input)?;
+        return Ok((value, &inner[close + 1..]));
+    }
+
+    if s.get(..3).is_some_and(|w| w.eq_ignore_ascii_case("now"))
+        && s[3..]
+            .chars()
+            .next()
+            .is_none_or(|c| c.is_whitespace() || c == '+' || c == '-')
+    {
+        return Ok((now, &s[3..]));
+    }
+
+    let op = s
+        .char_indices()
+        .skip(1)
+        .find(|&(i, c)| {
+            (c == '+' || c == '-')
+                && s[..i].ends_with(char::is_whitespace)
+                && s[i + 1..].starts_with(char::is_whitespace)
+        })
+        .map(|(i, _)| i)
+        .ok_or(ExprError::NotAnExpression)?;
+    let value = resolve(s[..op].trim_end()).map_err(ExprError::Input)?;
+    Ok((value, &s[op..]))
+}
+
+/// Index of the `)` closing an already-opened parenthesis in `s`.
+fn matching_paren(s: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Some(i),
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const NOW: i64 = 1_700_000_000;
+
+    /// Resolves unix seconds, and nested expressions like the real parser does.
+    fn resolve(s: &str) -> Result<DateTime<Utc>, String> {
+        match s.parse::<i64>() {
+            Ok(secs) => Ok(Utc.timestamp_opt(secs, 0).unwrap()),
+            Err(_) => match eval(s) {
+                Ok(dt) => Ok(dt),
+                Err(ExprError::Input(e)) => Err(e),
+                Err(_) => Err(format!("bad input `{s}`")),
+            },
+        }
+    }
+
+    fn eval(s: &str) -> Result<DateTime<Utc>, ExprError<String>> {
+        evaluate(s, Utc.timestamp_opt(NOW, 0).unwrap(), &resolve)
+    }
+
+    fn secs(s: &str) -> Result<i64, ExprError<String>> {
+        eval(s).map(|dt| dt.timestamp())
+    }
+
+    #[test]
+    fn adds_and_subtracts_durations() {
+        assert_eq!(secs("1700000000 + 3d - 2h"), Ok(NOW + 3 * 86_400 - 7200));
+        assert_eq!(secs("now - 90m"), Ok(NOW - 5400));
+        assert_eq!(secs("NOW+1h30m"), Ok(NOW + 5400));
+        assert_eq!(secs("now"), Ok(NOW));
+        assert_eq!(secs("-100 + 100s"), Ok(0));
+    }
+
+    #[test]
+    fn parenthesized_operands() {
+        assert_eq!(secs("(1700000000) + 1w"), Ok(NOW + 7 * 86_400));
+        assert_eq!(secs("((now + 1h) - 30m) + PT30M"), Ok(NOW + 3600));
+    }
+
+    #[test]
+    fn plain_inputs_are_not_expressions() {
+        assert_eq!(secs("garbage"), Err(ExprError::NotAnExpression));
+        assert_eq!(
+            secs("2024/01/01 00:00:00 -05:00"),
+            Err(ExprError::NotAnExpression)
+        );
+    }
+
+    #[test]
+    fn reports_malformed_expressions() {
+        assert!(matches!(secs("now + 3x"), Err(ExprError::Syntax(_))));
+        assert!(matches!(secs("now + "), Err(ExprError::Syntax(_))));
+        assert!(matches!(secs("now 3h"), Err(ExprError::Syntax(_))));
+        assert!(matches!(secs("(now + 1h"), Err(ExprError::Syntax(_))));
+        assert!(matches!(secs("nope + 1h"), Err(ExprError::Input(_))));
+    }
+}
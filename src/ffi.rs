@@ -0,0 +1,181 @@
+//! C bindings (`ffi` feature). The header is generated with
+//! `cbindgen --config cbindgen.toml --output include/epoch.h`.
+//!
+//! Strings in results are owned by the library; release every result with
+//! [`epoch_result_free`].
+
+use chrono::DateTime;
+use std::ffi::{CStr, CString, c_char};
+use std::ptr;
+
+use crate::zone::TzChoice;
+
+/// Outcome of an `epoch_*` call.
+#[repr(C)]
+pub struct EpochResult {
+    /// Whether the call succeeded; `error` is set otherwise.
+    pub ok: bool,
+    /// The parsed instant as unix milliseconds (`epoch_parse` only).
+    pub unix_millis: i64,
+    /// The formatted instant (`epoch_format` only), or NULL.
+    pub value: *mut c_char,
+    /// Error message, or NULL.
+    pub error: *mut c_char,
+}
+
+fn to_c(s: String) -> *mut c_char {
+    // interior NULs cannot come out of the parser, but never hand out a truncated string
+    CString::new(s).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+fn success(unix_millis: i64, value: Option<String>) -> EpochResult {
+    EpochResult {
+        ok: true,
+        unix_millis,
+        value: value.map_or(ptr::null_mut(), to_c),
+        error: ptr::null_mut(),
+    }
+}
+
+fn failure(msg: String) -> EpochResult {
+    EpochResult {
+        ok: false,
+        unix_millis: 0,
+        value: ptr::null_mut(),
+        error: to_c(msg),
+    }
+}
+
+/// Borrow an optional C string argument.
+///
+/// # Safety
+/// `s` must be NULL or a valid NUL-terminated string.
+unsafe fn arg<'a>(s: *const c_char, name: &str) -> Result<Option<&'a str>, String> {
+    if s.is_null() {
+        return Ok(None);
+    }
+    // SAFETY: guaranteed by the caller
+    unsafe { CStr::from_ptr(s) }
+        .to_str()
+        .map(Some)
+        .map_err(|_| format!("{name} is not valid UTF-8"))
+}
+
+fn tz(s: Option<&str>, default: TzChoice) -> Result<TzChoice, String> {
+    s.map_or(Ok(default), str::parse)
+}
+
+/// Parse `input` like the CLI does. `input_tz` may be NULL for the local zone.
+///
+/// # Safety
+/// `input` must be a valid NUL-terminated string; `input_tz` must be NULL or one.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn epoch_parse(input: *const c_char, input_tz: *const c_char) -> EpochResult {
+    // SAFETY: guaranteed by the caller
+    let args = unsafe { (arg(input, "input"), arg(input_tz, "input_tz")) };
+    let result = match args {
+        (Ok(Some(input)), Ok(input_tz)) => tz(input_tz, TzChoice::Local).and_then(|input_tz| {
+            crate::parse_input_to_utc(input, input_tz, None, &[]).map_err(|e| e.to_string())
+        }),
+        (Ok(None), _) => Err("input is NULL".to_string()),
+        (Err(e), _) | (_, Err(e)) => Err(e),
+    };
+    match result {
+        Ok((utc, _)) => success(utc.timestamp_millis(), None),
+        Err(msg) => failure(msg),
+    }
+}
+
+/// Format unix milliseconds in `output_tz` (NULL for UTC) with a strftime `format`
+/// (NULL for RFC 3339).
+///
+/// # Safety
+/// `output_tz` and `format` must each be NULL or a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn epoch_format(
+    unix_millis: i64,
+    output_tz: *const c_char,
+    format: *const c_char,
+) -> EpochResult {
+    // SAFETY: guaranteed by the caller
+    let args = unsafe { (arg(output_tz, "output_tz"), arg(format, "format")) };
+    let result = match args {
+        (Ok(output_tz), Ok(format)) => tz(output_tz, TzChoice::Utc).and_then(|output_tz| {
+            if let Some(format) = format {
+                crate::check_strftime(format).map_err(|e| e.to_string())?;
+            }
+            DateTime::from_timestamp_millis(unix_millis)
+                .map(|utc| crate::format_output(utc, output_tz, format))
+                .ok_or_else(|| "timestamp out of range".to_string())
+        }),
+        (Err(e), _) | (_, Err(e)) => Err(e),
+    };
+    match result {
+        Ok(s) => success(unix_millis, Some(s)),
+        Err(msg) => failure(msg),
+    }
+}
+
+/// Release the strings owned by `result`.
+///
+/// # Safety
+/// `result` must come from an `epoch_*` call and not have been freed already.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn epoch_result_free(result: EpochResult) {
+    for s in [result.value, result.error] {
+        if !s.is_null() {
+            // SAFETY: produced by CString::into_raw in this module
+            drop(unsafe { CString::from_raw(s) });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: *mut c_char) -> String {
+        unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn parses_and_formats() {
+        let input = CString::new("2024/01/01 09:00:00").unwrap();
+        let zone = CString::new("Asia/Tokyo").unwrap();
+        let r = unsafe { epoch_parse(input.as_ptr(), zone.as_ptr()) };
+        assert!(r.ok);
+        assert_eq!(r.unix_millis, 1_704_067_200_000);
+        assert!(r.error.is_null());
+        unsafe { epoch_result_free(r) };
+
+        let fmt = CString::new("%Y-%m-%d %H:%M").unwrap();
+        let r = unsafe { epoch_format(1_704_067_200_000, zone.as_ptr(), fmt.as_ptr()) };
+        assert!(r.ok);
+        assert_eq!(text(r.value), "2024-01-01 09:00");
+        unsafe { epoch_result_free(r) };
+
+        let r = unsafe { epoch_format(0, ptr::null(), ptr::null()) };
+        assert_eq!(text(r.value), "1970-01-01T00:00:00+00:00");
+        unsafe { epoch_result_free(r) };
+    }
+
+    #[test]
+    fn reports_errors() {
+        let input = CString::new("nope").unwrap();
+        let r = unsafe { epoch_parse(input.as_ptr(), ptr::null()) };
+        assert!(!r.ok);
+        assert_eq!(text(r.error), "Unrecognized input");
+        unsafe { epoch_result_free(r) };
+
+        let r = unsafe { epoch_parse(ptr::null(), ptr::null()) };
+        assert!(!r.ok);
+        unsafe { epoch_result_free(r) };
+
+        let fmt = CString::new("%Q").unwrap();
+        let r = unsafe { epoch_format(0, ptr::null(), fmt.as_ptr()) };
+        assert!(!r.ok);
+        assert!(r.value.is_null());
+        assert_eq!(text(r.error), "invalid strftime pattern `%Q`");
+        unsafe { epoch_result_free(r) };
+    }
+}
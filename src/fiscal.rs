@@ -0,0 +1,64 @@
+//! Fiscal calendars whose year starts in a month other than January.
+
+use chrono::{Datelike, NaiveDate};
+
+/// Position of a date within a fiscal year.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FiscalDate {
+    /// Fiscal years are named after the calendar year in which they end, so with an
+    /// April start, April 2023 - March 2024 is FY2024.
+    pub year: i32,
+    /// 1-4
+    pub quarter: u32,
+    /// Month within the fiscal year, 1-12.
+    pub period: u32,
+}
+
+/// Locate `date` in a fiscal calendar starting on the first day of `start_month` (1-12).
+pub fn fiscal_date(date: NaiveDate, start_month: u32) -> FiscalDate {
+    let offset = (date.month() + 12 - start_month) % 12;
+    let year = if start_month == 1 || date.month() < start_month {
+        date.year()
+    } else {
+        date.year() + 1
+    };
+    FiscalDate {
+        year,
+        quarter: offset / 3 + 1,
+        period: offset + 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn on(y: i32, m: u32, d: u32, start: u32) -> FiscalDate {
+        fiscal_date(NaiveDate::from_ymd_opt(y, m, d).unwrap(), start)
+    }
+
+    #[test]
+    fn january_start_matches_calendar_year() {
+        let f = on(2023, 11, 14, 1);
+        assert_eq!((f.year, f.quarter, f.period), (2023, 4, 11));
+    }
+
+    #[test]
+    fn april_start() {
+        let f = on(2023, 11, 14, 4);
+        assert_eq!((f.year, f.quarter, f.period), (2024, 3, 8));
+
+        let f = on(2024, 3, 31, 4);
+        assert_eq!((f.year, f.quarter, f.period), (2024, 4, 12));
+
+        let f = on(2024, 4, 1, 4);
+        assert_eq!((f.year, f.quarter, f.period), (2025, 1, 1));
+    }
+
+    #[test]
+    fn october_start() {
+        // US federal FY2024 began on 2023-10-01
+        let f = on(2023, 10, 1, 10);
+        assert_eq!((f.year, f.quarter, f.period), (2024, 1, 1));
+    }
+}
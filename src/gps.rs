@@ -0,0 +1,139 @@
+//! GPS time as receivers report it: a week number (WN) and the seconds into that week
+//! (time of week, TOW), counted from 1980-01-06 without leap seconds.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+use crate::EpochError;
+
+/// Seconds in a GPS week.
+pub const WEEK_SECS: i64 = 7 * 86_400;
+
+/// Legacy receivers send the week number in 10 bits, so it wraps every 1024 weeks.
+pub const ROLLOVER_WEEKS: i64 = 1024;
+
+/// UTC dates from which GPS time ran one more second ahead of UTC.
+const LEAP_SECONDS: &[(i32, u32)] = &[
+    (1981, 7),
+    (1982, 7),
+    (1983, 7),
+    (1985, 7),
+    (1988, 1),
+    (1990, 1),
+    (1991, 1),
+    (1992, 7),
+    (1993, 7),
+    (1994, 7),
+    (1996, 1),
+    (1997, 7),
+    (1999, 1),
+    (2006, 1),
+    (2009, 1),
+    (2012, 7),
+    (2015, 7),
+    (2017, 1),
+];
+
+fn epoch() -> DateTime<Utc> {
+    NaiveDate::from_ymd_opt(1980, 1, 6)
+        .unwrap()
+        .and_time(Default::default())
+        .and_utc()
+}
+
+/// How many seconds GPS time is ahead of UTC at `utc`.
+pub fn leap_seconds(utc: DateTime<Utc>) -> i64 {
+    LEAP_SECONDS
+        .iter()
+        .take_while(|&&(y, m)| {
+            let from = NaiveDate::from_ymd_opt(y, m, 1).unwrap();
+            utc.date_naive() >= from
+        })
+        .count() as i64
+}
+
+/// A week number and time of week: `2291:345600`, `2291:345600.5`.
+///
+/// Week numbers below 1024 are taken as 10-bit ones that have rolled over and are placed
+/// in the latest 1024-week era that does not put the time after `now`.
+pub fn from_week(input: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, EpochError> {
+    let invalid = || EpochError::InvalidInput(format!("expected WEEK:SECONDS, got `{input}`"));
+    let (week, tow) = input.split_once(':').ok_or_else(invalid)?;
+    let week = week.parse::<i64>().map_err(|_| invalid())?;
+    let tow = tow
+        .parse::<f64>()
+        .ok()
+        .filter(|tow| (0.0..WEEK_SECS as f64).contains(tow))
+        .ok_or_else(|| {
+            EpochError::InvalidInput(format!(
+                "time of week must be 0-{}, got `{tow}`",
+                WEEK_SECS - 1
+            ))
+        })?;
+
+    let at = |week: i64| {
+        let gps = Duration::try_weeks(week)?
+            .checked_add(&Duration::nanoseconds((tow * 1e9).round() as i64))?;
+        let gps_as_utc = epoch().checked_add_signed(gps)?;
+        // The offset depends on the UTC time being solved for; settle it in two steps.
+        let first = gps_as_utc - Duration::seconds(leap_seconds(gps_as_utc));
+        Some(gps_as_utc - Duration::seconds(leap_seconds(first)))
+    };
+
+    if !(0..ROLLOVER_WEEKS).contains(&week) {
+        return at(week).ok_or(EpochError::OutOfRange);
+    }
+    (0..)
+        .map(|era| week + era * ROLLOVER_WEEKS)
+        .map_while(at)
+        .take_while(|dt| *dt <= now)
+        .last()
+        .or_else(|| at(week))
+        .ok_or(EpochError::OutOfRange)
+}
+
+/// `utc` as the full (unwrapped) week number and time of week. Weeks before the GPS
+/// epoch are negative.
+pub fn to_week(utc: DateTime<Utc>) -> (i64, Duration) {
+    let gps = utc - epoch() + Duration::seconds(leap_seconds(utc));
+    let week = gps.num_seconds().div_euclid(WEEK_SECS);
+    (week, gps - Duration::weeks(week))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().to_utc()
+    }
+
+    #[test]
+    fn counts_leap_seconds() {
+        assert_eq!(leap_seconds(utc("1980-01-06T00:00:00Z")), 0);
+        assert_eq!(leap_seconds(utc("2016-12-31T23:59:59Z")), 17);
+        assert_eq!(leap_seconds(utc("2023-11-14T22:13:20Z")), 18);
+    }
+
+    #[test]
+    fn converts_week_and_time_of_week() {
+        let now = utc("2024-01-01T00:00:00Z");
+        // Week 2288 began on Sunday 2023-11-12 (GPS); 2d 22:13:38 in is 22:13:20 UTC.
+        let dt = from_week("2288:252818", now).unwrap();
+        assert_eq!(dt, utc("2023-11-14T22:13:20Z"));
+        assert_eq!(to_week(dt), (2288, Duration::seconds(252_818)));
+        assert_eq!(
+            from_week("0:0", utc("1990-01-01T00:00:00Z")).unwrap(),
+            epoch()
+        );
+    }
+
+    #[test]
+    fn resolves_rolled_over_week_numbers() {
+        let now = utc("2024-01-01T00:00:00Z");
+        // 2288 - 2 * 1024: a legacy receiver's week number for the same time.
+        let dt = from_week("240:252818", now).unwrap();
+        assert_eq!(dt, utc("2023-11-14T22:13:20Z"));
+        assert!(from_week("2288:604800", now).is_err());
+        assert!(from_week("2288", now).is_err());
+    }
+}
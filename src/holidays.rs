@@ -0,0 +1,419 @@
+//! Built-in public holiday calendars.
+//!
+//! Each calendar is a list of rules (fixed dates, nth-weekday-of-month, offsets from
+//! Easter) plus the country's rule for holidays falling on a weekend. Only the regular
+//! rules are modelled; one-off holidays (jubilees, moved bank holidays) are not.
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+/// A country's holiday calendar, selected with `--holidays`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Calendar {
+    /// United States federal holidays
+    Us,
+    /// England and Wales bank holidays
+    Gb,
+    /// Romanian public holidays
+    Ro,
+}
+
+#[derive(Copy, Clone, Debug)]
+enum Rule {
+    /// A fixed month and day.
+    Fixed(u32, u32),
+    /// The nth weekday of a month; `-1` is the last one.
+    NthWeekday(u32, Weekday, i8),
+    /// Days after Easter Sunday; `orthodox` selects the Julian computus.
+    Easter { offset: i64, orthodox: bool },
+}
+
+/// What happens when a fixed-date holiday falls on a weekend.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Weekend {
+    /// Nothing; the holiday is simply lost.
+    Ignore,
+    /// Saturday is observed on Friday, Sunday on Monday (US federal).
+    Nearest,
+    /// Observed on the next weekday that is not already a holiday (UK substitute days).
+    Substitute,
+}
+
+struct Holiday {
+    name: &'static str,
+    rule: Rule,
+    /// First year the holiday applies.
+    since: i32,
+}
+
+const fn holiday(name: &'static str, rule: Rule) -> Holiday {
+    Holiday {
+        name,
+        rule,
+        since: i32::MIN,
+    }
+}
+
+const fn holiday_since(name: &'static str, rule: Rule, since: i32) -> Holiday {
+    Holiday { name, rule, since }
+}
+
+const fn easter(offset: i64) -> Rule {
+    Rule::Easter {
+        offset,
+        orthodox: false,
+    }
+}
+
+const fn orthodox_easter(offset: i64) -> Rule {
+    Rule::Easter {
+        offset,
+        orthodox: true,
+    }
+}
+
+const US: &[Holiday] = &[
+    holiday("New Year's Day", Rule::Fixed(1, 1)),
+    holiday_since(
+        "Birthday of Martin Luther King, Jr.",
+        Rule::NthWeekday(1, Weekday::Mon, 3),
+        1986,
+    ),
+    holiday(
+        "Washington's Birthday",
+        Rule::NthWeekday(2, Weekday::Mon, 3),
+    ),
+    holiday("Memorial Day", Rule::NthWeekday(5, Weekday::Mon, -1)),
+    holiday_since(
+        "Juneteenth National Independence Day",
+        Rule::Fixed(6, 19),
+        2021,
+    ),
+    holiday("Independence Day", Rule::Fixed(7, 4)),
+    holiday("Labor Day", Rule::NthWeekday(9, Weekday::Mon, 1)),
+    holiday("Columbus Day", Rule::NthWeekday(10, Weekday::Mon, 2)),
+    holiday("Veterans Day", Rule::Fixed(11, 11)),
+    holiday("Thanksgiving Day", Rule::NthWeekday(11, Weekday::Thu, 4)),
+    holiday("Christmas Day", Rule::Fixed(12, 25)),
+];
+
+const GB: &[Holiday] = &[
+    holiday("New Year's Day", Rule::Fixed(1, 1)),
+    holiday("Good Friday", easter(-2)),
+    holiday("Easter Monday", easter(1)),
+    holiday(
+        "Early May bank holiday",
+        Rule::NthWeekday(5, Weekday::Mon, 1),
+    ),
+    holiday("Spring bank holiday", Rule::NthWeekday(5, Weekday::Mon, -1)),
+    holiday("Summer bank holiday", Rule::NthWeekday(8, Weekday::Mon, -1)),
+    holiday("Christmas Day", Rule::Fixed(12, 25)),
+    holiday("Boxing Day", Rule::Fixed(12, 26)),
+];
+
+const RO: &[Holiday] = &[
+    holiday("Anul Nou", Rule::Fixed(1, 1)),
+    holiday("Anul Nou (a doua zi)", Rule::Fixed(1, 2)),
+    holiday_since("Boboteaza", Rule::Fixed(1, 6), 2024),
+    holiday_since("Sfântul Ioan Botezătorul", Rule::Fixed(1, 7), 2024),
+    holiday_since("Ziua Unirii Principatelor Române", Rule::Fixed(1, 24), 2017),
+    holiday_since("Vinerea Mare", orthodox_easter(-2), 2018),
+    holiday("Paștele", orthodox_easter(0)),
+    holiday("Paștele (a doua zi)", orthodox_easter(1)),
+    holiday("Ziua Muncii", Rule::Fixed(5, 1)),
+    holiday_since("Ziua Copilului", Rule::Fixed(6, 1), 2017),
+    holiday("Rusaliile", orthodox_easter(49)),
+    holiday("Rusaliile (a doua zi)", orthodox_easter(50)),
+    holiday("Adormirea Maicii Domnului", Rule::Fixed(8, 15)),
+    holiday_since("Sfântul Andrei", Rule::Fixed(11, 30), 2012),
+    holiday("Ziua Națională a României", Rule::Fixed(12, 1)),
+    holiday("Crăciunul", Rule::Fixed(12, 25)),
+    holiday("Crăciunul (a doua zi)", Rule::Fixed(12, 26)),
+];
+
+impl Calendar {
+    fn rules(self) -> (&'static [Holiday], Weekend) {
+        match self {
+            Calendar::Us => (US, Weekend::Nearest),
+            Calendar::Gb => (GB, Weekend::Substitute),
+            Calendar::Ro => (RO, Weekend::Ignore),
+        }
+    }
+
+    /// Holidays observed during `year`, in date order.
+    pub fn holidays_in_year(self, year: i32) -> Vec<(NaiveDate, &'static str)> {
+        // Observed dates can cross a year boundary (a Saturday New Year's Day is observed
+        // on the Friday before), so look at the neighbouring years too.
+        let mut days: Vec<(NaiveDate, &'static str)> = (year - 1..=year + 1)
+            .flat_map(|y| self.observed_in(y))
+            .filter(|(date, _)| date.year() == year)
+            .collect();
+        days.sort();
+        days
+    }
+
+    /// The holiday observed on `date`, if any.
+    pub fn holiday_on(self, date: NaiveDate) -> Option<&'static str> {
+        self.holidays_in_year(date.year())
+            .into_iter()
+            .find(|(d, _)| *d == date)
+            .map(|(_, name)| name)
+    }
+
+    /// Observed dates of the holidays whose actual date falls in `year`.
+    fn observed_in(self, year: i32) -> Vec<(NaiveDate, &'static str)> {
+        let (holidays, weekend) = self.rules();
+        let actual: Vec<(NaiveDate, &'static str)> = holidays
+            .iter()
+            .filter(|h| year >= h.since)
+            .filter_map(|h| Some((h.rule.date_in(year)?, h.name)))
+            .collect();
+
+        let is_weekend = |d: NaiveDate| matches!(d.weekday(), Weekday::Sat | Weekday::Sun);
+        let mut taken: Vec<NaiveDate> = actual
+            .iter()
+            .map(|(d, _)| *d)
+            .filter(|d| !is_weekend(*d))
+            .collect();
+
+        let mut observed = Vec::with_capacity(actual.len());
+        for (date, name) in actual {
+            let date = match weekend {
+                _ if !is_weekend(date) => date,
+                Weekend::Ignore => date,
+                Weekend::Nearest => match date.weekday() {
+                    Weekday::Sat => date - Duration::days(1),
+                    _ => date + Duration::days(1),
+                },
+                Weekend::Substitute => {
+                    let mut d = date;
+                    while is_weekend(d) || taken.contains(&d) {
+                        d += Duration::days(1);
+                    }
+                    taken.push(d);
+                    d
+                }
+            };
+            observed.push((date, name));
+        }
+        observed
+    }
+}
+
+impl Rule {
+    fn date_in(self, year: i32) -> Option<NaiveDate> {
+        match self {
+            Rule::Fixed(month, day) => NaiveDate::from_ymd_opt(year, month, day),
+            Rule::NthWeekday(month, weekday, n) if n > 0 => {
+                NaiveDate::from_weekday_of_month_opt(year, month, weekday, n as u8)
+            }
+            Rule::NthWeekday(month, weekday, _) => {
+                let (y, m) = if month == 12 {
+                    (year + 1, 1)
+                } else {
+                    (year, month + 1)
+                };
+                let last = NaiveDate::from_ymd_opt(y, m, 1)?.pred_opt()?;
+                let back = (7 + last.weekday().num_days_from_monday()
+                    - weekday.num_days_from_monday())
+                    % 7;
+                Some(last - Duration::days(i64::from(back)))
+            }
+            Rule::Easter { offset, orthodox } => {
+                easter_in(year, orthodox)?.checked_add_signed(Duration::days(offset))
+            }
+        }
+    }
+}
+
+/// Feasts of the Western churches that move with Easter, in days after Easter Sunday.
+const WESTERN_FEASTS: &[(&str, i64)] = &[
+    ("Ash Wednesday", -46),
+    ("Palm Sunday", -7),
+    ("Maundy Thursday", -3),
+    ("Good Friday", -2),
+    ("Holy Saturday", -1),
+    ("Easter Sunday", 0),
+    ("Easter Monday", 1),
+    ("Ascension Day", 39),
+    ("Pentecost", 49),
+    ("Whit Monday", 50),
+    ("Trinity Sunday", 56),
+    ("Corpus Christi", 60),
+];
+
+/// The same for the Orthodox churches, counted from Orthodox Easter.
+const ORTHODOX_FEASTS: &[(&str, i64)] = &[
+    ("Clean Monday", -48),
+    ("Palm Sunday", -7),
+    ("Holy Thursday", -3),
+    ("Good Friday", -2),
+    ("Holy Saturday", -1),
+    ("Easter Sunday", 0),
+    ("Bright Monday", 1),
+    ("Ascension", 39),
+    ("Pentecost", 49),
+    ("Monday of the Holy Spirit", 50),
+    ("All Saints' Sunday", 56),
+];
+
+/// Easter Sunday by the Julian computus when `orthodox`, else the Gregorian one.
+pub fn easter_in(year: i32, orthodox: bool) -> Option<NaiveDate> {
+    if orthodox {
+        orthodox_easter_sunday(year)
+    } else {
+        easter_sunday(year)
+    }
+}
+
+/// The feasts that move with Easter in `year`, earliest first, with their offsets from
+/// Easter Sunday in days.
+pub fn movable_feasts(year: i32, orthodox: bool) -> Option<Vec<(NaiveDate, &'static str, i64)>> {
+    let sunday = easter_in(year, orthodox)?;
+    let feasts = if orthodox {
+        ORTHODOX_FEASTS
+    } else {
+        WESTERN_FEASTS
+    };
+    feasts
+        .iter()
+        .map(|&(name, offset)| {
+            let date = sunday.checked_add_signed(Duration::days(offset))?;
+            Some((date, name, offset))
+        })
+        .collect()
+}
+
+/// Western (Gregorian) Easter Sunday, via the anonymous Gregorian computus.
+pub fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a = year.rem_euclid(19);
+    let b = year.div_euclid(100);
+    let c = year.rem_euclid(100);
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+/// Orthodox Easter Sunday (Julian computus), as a Gregorian date.
+pub fn orthodox_easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a = year.rem_euclid(4);
+    let b = year.rem_euclid(7);
+    let c = year.rem_euclid(19);
+    let d = (19 * c + 15) % 30;
+    let e = (2 * a + 4 * b - d + 34) % 7;
+    let month = (d + e + 114) / 31;
+    let day = (d + e + 114) % 31 + 1;
+    julian_to_gregorian(year, month as u32, day as u32)
+}
+
+/// Convert a proleptic Julian calendar date to the Gregorian calendar.
+pub fn julian_to_gregorian(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+    if !(1..=12).contains(&month) || day == 0 || day > 31 {
+        return None;
+    }
+    let a = (14 - month as i64) / 12;
+    let y = year as i64 + 4800 - a;
+    let m = month as i64 + 12 * a - 3;
+    let jdn = day as i64 + (153 * m + 2) / 5 + 365 * y + y.div_euclid(4) - 32083;
+    // JDN 1721426 is 0001-01-01 in the proleptic Gregorian calendar.
+    NaiveDate::from_num_days_from_ce_opt(i32::try_from(jdn - 1_721_425).ok()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn computes_easter() {
+        assert_eq!(easter_sunday(2024), Some(ymd(2024, 3, 31)));
+        assert_eq!(easter_sunday(2025), Some(ymd(2025, 4, 20)));
+        assert_eq!(easter_sunday(2026), Some(ymd(2026, 4, 5)));
+        assert_eq!(orthodox_easter_sunday(2024), Some(ymd(2024, 5, 5)));
+        assert_eq!(orthodox_easter_sunday(2025), Some(ymd(2025, 4, 20)));
+        assert_eq!(orthodox_easter_sunday(2026), Some(ymd(2026, 4, 12)));
+    }
+
+    #[test]
+    fn lists_movable_feasts() {
+        let western = movable_feasts(2026, false).unwrap();
+        assert_eq!(western[0], (ymd(2026, 2, 18), "Ash Wednesday", -46));
+        assert!(western.contains(&(ymd(2026, 5, 14), "Ascension Day", 39)));
+        assert!(western.contains(&(ymd(2026, 5, 24), "Pentecost", 49)));
+        assert!(western.windows(2).all(|w| w[0].0 < w[1].0));
+        let orthodox = movable_feasts(2026, true).unwrap();
+        assert_eq!(orthodox[0], (ymd(2026, 2, 23), "Clean Monday", -48));
+        assert!(orthodox.contains(&(ymd(2026, 4, 12), "Easter Sunday", 0)));
+    }
+
+    #[test]
+    fn us_holidays_use_nearest_weekday() {
+        let days = Calendar::Us.holidays_in_year(2021);
+        // New Year's Day 2022 fell on a Saturday: observed Friday 2021-12-31
+        assert!(days.contains(&(ymd(2021, 12, 31), "New Year's Day")));
+        // Independence Day 2021 fell on a Sunday: observed Monday 2021-07-05
+        assert!(days.contains(&(ymd(2021, 7, 5), "Independence Day")));
+        assert_eq!(
+            Calendar::Us.holiday_on(ymd(2024, 11, 28)),
+            Some("Thanksgiving Day")
+        );
+        assert_eq!(
+            Calendar::Us.holiday_on(ymd(2024, 5, 27)),
+            Some("Memorial Day")
+        );
+        assert_eq!(Calendar::Us.holiday_on(ymd(2020, 6, 19)), None);
+    }
+
+    #[test]
+    fn gb_substitute_days_do_not_collide() {
+        // Christmas 2021 was a Saturday and Boxing Day a Sunday
+        assert_eq!(
+            Calendar::Gb.holiday_on(ymd(2021, 12, 27)),
+            Some("Christmas Day")
+        );
+        assert_eq!(
+            Calendar::Gb.holiday_on(ymd(2021, 12, 28)),
+            Some("Boxing Day")
+        );
+        // Christmas 2022 was a Sunday, Boxing Day the Monday
+        assert_eq!(
+            Calendar::Gb.holiday_on(ymd(2022, 12, 26)),
+            Some("Boxing Day")
+        );
+        assert_eq!(
+            Calendar::Gb.holiday_on(ymd(2022, 12, 27)),
+            Some("Christmas Day")
+        );
+        assert_eq!(
+            Calendar::Gb.holiday_on(ymd(2024, 3, 29)),
+            Some("Good Friday")
+        );
+    }
+
+    #[test]
+    fn ro_holidays_follow_orthodox_easter() {
+        assert_eq!(
+            Calendar::Ro.holiday_on(ymd(2024, 5, 6)),
+            Some("Paștele (a doua zi)")
+        );
+        assert_eq!(
+            Calendar::Ro.holiday_on(ymd(2024, 6, 24)),
+            Some("Rusaliile (a doua zi)")
+        );
+        assert_eq!(Calendar::Ro.holiday_on(ymd(2024, 1, 6)), Some("Boboteaza"));
+        assert_eq!(Calendar::Ro.holiday_on(ymd(2023, 1, 6)), None);
+        assert_eq!(Calendar::Ro.holidays_in_year(2024).len(), 17);
+    }
+}
@@ -0,0 +1,278 @@
+//! iCalendar (RFC 5545): writing a single event for `epoch ics`, and reading the times
+//! out of a calendar for `epoch ics-read`.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+
+use crate::zone::TzChoice;
+use crate::{EpochError, naive_to_utc};
+
+/// Layout of UTC DATE-TIME values: `20231114T221320Z`.
+pub const UTC_DATE_TIME: &str = "%Y%m%dT%H%M%SZ";
+
+/// A one-off event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Event {
+    /// Globally unique identifier, e.g. `1700000000-42@example.com`.
+    pub uid: String,
+    pub summary: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// When the event was written (DTSTAMP).
+    pub stamp: DateTime<Utc>,
+}
+
+impl Event {
+    /// A VCALENDAR holding this event as its only VEVENT, with CRLF line endings and long
+    /// lines folded as RFC 5545 requires.
+    pub fn to_ics(&self) -> String {
+        let utc = |dt: DateTime<Utc>| dt.format(UTC_DATE_TIME).to_string();
+        let lines = [
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//epoch//epoch//EN".to_string(),
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}", escape_text(&self.uid)),
+            format!("DTSTAMP:{}", utc(self.stamp)),
+            format!("DTSTART:{}", utc(self.start)),
+            format!("DTEND:{}", utc(self.end)),
+            format!("SUMMARY:{}", escape_text(&self.summary)),
+            "END:VEVENT".to_string(),
+            "END:VCALENDAR".to_string(),
+        ];
+        lines.iter().map(|line| fold(line) + "\r\n").collect()
+    }
+}
+
+/// `text` as a TEXT value: backslash, `;`, `,` and newlines escaped.
+fn escape_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// `line` split into lines of at most 75 octets, continuations starting with a space.
+fn fold(line: &str) -> String {
+    let mut out = String::with_capacity(line.len() + line.len() / 74 * 3);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out
+}
+
+/// A DTSTART, DTEND or RRULE property of a calendar component.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimeProperty {
+    /// Line of the calendar it starts on, counting from 1.
+    pub line: usize,
+    /// `DTSTART`, `DTEND` or `RRULE`.
+    pub name: String,
+    /// The `TZID` parameter, naming the zone of a local time.
+    pub tzid: Option<String>,
+    /// The value as written, e.g. `20231114T221320Z` or `FREQ=WEEKLY;UNTIL=20231231`.
+    pub value: String,
+}
+
+impl TimeProperty {
+    /// The DATE or DATE-TIME the property holds: its value, or an RRULE's `UNTIL`.
+    /// `None` for a rule without an end.
+    pub fn date_time(&self) -> Option<&str> {
+        if self.name != "RRULE" {
+            return Some(&self.value);
+        }
+        self.value
+            .split(';')
+            .find_map(|part| part.strip_prefix("UNTIL="))
+    }
+
+    /// The instant of [`TimeProperty::date_time`]. Times ending in `Z` are UTC, others are
+    /// in the TZID zone or, without one ("floating" times), in `floating`; dates are taken
+    /// at midnight.
+    pub fn to_utc(&self, floating: TzChoice) -> Option<Result<DateTime<Utc>, EpochError>> {
+        let value = self.date_time()?;
+        Some(self.read(value, floating))
+    }
+
+    fn read(&self, value: &str, floating: TzChoice) -> Result<DateTime<Utc>, EpochError> {
+        let invalid = || {
+            EpochError::InvalidInput(format!(
+                "{}: expected a DATE (YYYYMMDD) or DATE-TIME (YYYYMMDDTHHMMSS[Z]), got `{value}`",
+                self.name
+            ))
+        };
+        if let Some(utc) = value.strip_suffix('Z') {
+            let naive =
+                NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").map_err(|_| invalid())?;
+            return Ok(naive.and_utc());
+        }
+        let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+            .or_else(|_| {
+                NaiveDate::parse_from_str(value, "%Y%m%d").map(|d| d.and_time(Default::default()))
+            })
+            .map_err(|_| invalid())?;
+        let zone = match &self.tzid {
+            Some(tzid) => tzid.parse().map_err(|e| {
+                EpochError::InvalidInput(format!("{}: TZID `{tzid}`: {e}", self.name))
+            })?,
+            None => floating,
+        };
+        naive_to_utc(&naive, zone)
+    }
+}
+
+/// Every DTSTART, DTEND and RRULE in `calendar`, in order, after unfolding long lines.
+/// Those describing VTIMEZONE rules rather than events are left out.
+pub fn time_properties(calendar: &str) -> Vec<TimeProperty> {
+    let mut found = Vec::new();
+    let mut in_timezone = 0;
+    for (line, content) in unfold(calendar) {
+        let Some((head, value)) = split_value(&content) else {
+            continue;
+        };
+        let mut params = head.split(';');
+        let name = params.next().unwrap_or_default().to_ascii_uppercase();
+        match name.as_str() {
+            "BEGIN" if value.eq_ignore_ascii_case("VTIMEZONE") => in_timezone += 1,
+            "END" if value.eq_ignore_ascii_case("VTIMEZONE") => in_timezone -= 1,
+            "DTSTART" | "DTEND" | "RRULE" if in_timezone == 0 => {
+                let tzid = params.find_map(|param| {
+                    let (key, value) = param.split_once('=')?;
+                    key.eq_ignore_ascii_case("TZID")
+                        .then(|| value.trim_matches('"').to_string())
+                });
+                found.push(TimeProperty {
+                    line,
+                    name,
+                    tzid,
+                    value: value.trim().to_string(),
+                });
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+/// Content lines with their folded continuations (lines starting with a space or tab)
+/// joined back on, each with the line number it starts on.
+fn unfold(calendar: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    for (i, raw) in calendar.lines().enumerate() {
+        match (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some((_, last))) => last.push_str(rest),
+            _ => lines.push((i + 1, raw.to_string())),
+        }
+    }
+    lines
+}
+
+/// A content line split at the colon ending its name and parameters, skipping colons
+/// inside quoted parameter values.
+fn split_value(line: &str) -> Option<(&str, &str)> {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ':' if !quoted => return Some((&line[..i], &line[i + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(secs, 0).unwrap()
+    }
+
+    #[test]
+    fn writes_a_calendar_with_one_event() {
+        let event = Event {
+            uid: "1700000000-1@epoch".into(),
+            summary: "Deploy, then verify; roll back\nif needed".into(),
+            start: at(1_700_000_000),
+            end: at(1_700_003_600),
+            stamp: at(1_699_999_000),
+        };
+        assert_eq!(
+            event.to_ics(),
+            "BEGIN:VCALENDAR\r\n\
+             VERSION:2.0\r\n\
+             PRODID:-//epoch//epoch//EN\r\n\
+             BEGIN:VEVENT\r\n\
+             UID:1700000000-1@epoch\r\n\
+             DTSTAMP:20231114T215640Z\r\n\
+             DTSTART:20231114T221320Z\r\n\
+             DTEND:20231114T231320Z\r\n\
+             SUMMARY:Deploy\\, then verify\\; roll back\\nif needed\r\n\
+             END:VEVENT\r\n\
+             END:VCALENDAR\r\n"
+        );
+    }
+
+    #[test]
+    fn folds_long_lines() {
+        let line = format!("SUMMARY:{}", "é".repeat(40));
+        let folded = fold(&line);
+        let parts: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(parts.len(), 2);
+        assert!(parts.iter().all(|part| part.len() <= 75));
+        assert_eq!(parts.concat().replacen(" é", "é", 1), line);
+    }
+
+    #[test]
+    fn reads_event_times() {
+        let calendar = "BEGIN:VCALENDAR\r
+BEGIN:VTIMEZONE\r
+TZID:Europe/Paris\r
+BEGIN:STANDARD\r
+DTSTART:19701025T030000\r
+END:STANDARD\r
+END:VTIMEZONE\r
+BEGIN:VEVENT\r
+DTSTART;TZID=\"Europe/Paris\":20231114T231320\r
+DTEND:20231114T\r
+ 231320Z\r
+RRULE:FREQ=WEEKLY;UNTIL=20231231\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let found = time_properties(calendar);
+        let names: Vec<_> = found.iter().map(|p| (p.line, p.name.as_str())).collect();
+        assert_eq!(names, [(9, "DTSTART"), (10, "DTEND"), (12, "RRULE")]);
+        assert_eq!(found[0].tzid.as_deref(), Some("Europe/Paris"));
+        assert_eq!(found[1].value, "20231114T231320Z");
+        assert_eq!(found[2].date_time(), Some("20231231"));
+
+        let utc = |p: &TimeProperty| p.to_utc(TzChoice::Utc).unwrap().unwrap().timestamp();
+        #[cfg(feature = "tz")]
+        assert_eq!(utc(&found[0]), 1_700_000_000);
+        assert_eq!(utc(&found[1]), 1_700_003_600);
+        assert_eq!(utc(&found[2]), 1_703_980_800);
+    }
+
+    #[test]
+    fn rules_without_an_end_have_no_instant() {
+        let found = time_properties("RRULE:FREQ=DAILY;COUNT=3\nDTSTART:2023-11-14\n");
+        assert!(found[0].to_utc(TzChoice::Utc).is_none());
+        assert!(found[1].to_utc(TzChoice::Utc).unwrap().is_err());
+    }
+}
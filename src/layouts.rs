@@ -0,0 +1,1125 @@
+//! Well-known layouts that logs and protocols print timestamps in, each recognized as
+//! input under its own name (reported as `parsed_as`).
+
+use chrono::format::StrftimeItems;
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Months, NaiveDate, NaiveDateTime, NaiveTime,
+    TimeZone, Utc,
+};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::radix::{self, Radix};
+use crate::{Encoding, EpochError, EpochParser, Parsed, ParsedAs, TsUnit, gps};
+
+/// Which year a syslog (RFC 3164) timestamp such as `Nov 14 22:13:20` belongs to, since
+/// the format leaves it out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SyslogYear {
+    /// The latest year that does not put the time more than a day in the future, so
+    /// December lines read in January land in the previous year.
+    #[default]
+    Recent,
+    /// The current year.
+    Current,
+    /// A fixed year.
+    Year(i32),
+}
+
+impl FromStr for SyslogYear {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "recent" => Ok(SyslogYear::Recent),
+            "current" => Ok(SyslogYear::Current),
+            _ => s
+                .parse()
+                .map(SyslogYear::Year)
+                .map_err(|_| format!("expected `recent`, `current` or a year, got `{s}`")),
+        }
+    }
+}
+
+impl fmt::Display for SyslogYear {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyslogYear::Recent => f.write_str("recent"),
+            SyslogYear::Current => f.write_str("current"),
+            SyslogYear::Year(y) => write!(f, "{y}"),
+        }
+    }
+}
+
+/// Where in its span a date-only input (`2023-11-14`, `2023-11`) lands (`--at`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DefaultTime {
+    /// Midnight at the start of the day, or of the month's first day.
+    #[default]
+    Start,
+    /// 23:59:59 on the day, or on the month's last day.
+    End,
+    /// This time on the day, or on the month's first day.
+    At(NaiveTime),
+}
+
+impl DefaultTime {
+    /// The time of day it stands for.
+    pub fn time(self) -> NaiveTime {
+        match self {
+            DefaultTime::Start => NaiveTime::MIN,
+            DefaultTime::End => NaiveTime::from_hms_opt(23, 59, 59).expect("valid time"),
+            DefaultTime::At(time) => time,
+        }
+    }
+
+    /// The instant it picks in the month starting on `first`.
+    fn in_month(self, first: NaiveDate) -> NaiveDateTime {
+        let day = match self {
+            DefaultTime::End => {
+                let next = first
+                    .checked_add_months(Months::new(1))
+                    .unwrap_or(NaiveDate::MAX);
+                next.pred_opt().unwrap_or(first)
+            }
+            _ => first,
+        };
+        day.and_time(self.time())
+    }
+}
+
+impl FromStr for DefaultTime {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "start" => Ok(DefaultTime::Start),
+            "end" => Ok(DefaultTime::End),
+            "noon" => Ok(DefaultTime::At(
+                NaiveTime::from_hms_opt(12, 0, 0).expect("valid time"),
+            )),
+            _ => NaiveTime::parse_from_str(s, "%H:%M:%S")
+                .or_else(|_| NaiveTime::parse_from_str(s, "%H:%M"))
+                .map(DefaultTime::At)
+                .map_err(|_| format!("expected start, end, noon or HH:MM[:SS], got `{s}`")),
+        }
+    }
+}
+
+impl fmt::Display for DefaultTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DefaultTime::Start => f.write_str("start"),
+            DefaultTime::End => f.write_str("end"),
+            DefaultTime::At(time) => write!(f, "{time}"),
+        }
+    }
+}
+
+/// An instant read from a layout that always states its offset.
+fn with_offset(name: &'static str, dt: DateTime<FixedOffset>) -> Parsed {
+    Ok((
+        dt.to_utc(),
+        ParsedAs::Layout {
+            name,
+            offset: Some(*dt.offset()),
+        },
+    ))
+}
+
+/// Apache/Nginx common log format: `14/Nov/2023:22:13:20 +0000`, optionally in the
+/// brackets access logs put around it.
+pub(crate) fn clf(input: &str) -> Option<Parsed> {
+    let inner = input
+        .strip_prefix('[')
+        .and_then(|s| s.strip_suffix(']'))
+        .unwrap_or(input);
+    let dt = DateTime::parse_from_str(inner, "%d/%b/%Y:%H:%M:%S %z").ok()?;
+    Some(with_offset("clf", dt))
+}
+
+/// RFC 3339, which is also what RFC 5424 syslog uses: `2023-11-14T22:13:20.003Z`.
+pub(crate) fn rfc3339(input: &str) -> Option<Parsed> {
+    let dt = DateTime::parse_from_rfc3339(input).ok()?;
+    Some(with_offset("rfc3339", dt))
+}
+
+/// ISO 8601 basic format, as in S3 signatures, SAML assertions and filenames:
+/// `20231114T221320Z`, `20231114T221320.123+0200`. Without an offset the time is read in
+/// `p`'s input zone.
+pub(crate) fn iso8601_basic(input: &str, p: &EpochParser) -> Option<Parsed> {
+    if let Ok(dt) = DateTime::parse_from_str(input, "%Y%m%dT%H%M%S%.f%#z") {
+        return Some(with_offset("iso8601_basic", dt));
+    }
+    let naive = NaiveDateTime::parse_from_str(input, "%Y%m%dT%H%M%S%.f").ok()?;
+    Some(crate::naive_to_utc(&naive, p.input_tz).map(|dt| {
+        (
+            dt,
+            ParsedAs::Layout {
+                name: "iso8601_basic",
+                offset: None,
+            },
+        )
+    }))
+}
+
+/// Years a compact date-time is plausible in without a `--detect-window`.
+const COMPACT_YEARS: (i32, i32) = (1900, 2100);
+
+/// Whether `input` is `len` digits that may be a compact date or date-time rather than a
+/// timestamp: not when that reading is off (`--no-compact`) or a unit is forced.
+fn compact_candidate(input: &str, p: &EpochParser, lengths: &[usize]) -> bool {
+    p.compact
+        && p.unit.is_none()
+        && lengths.contains(&input.len())
+        && input.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Whether `year` is plausible for a compact date: within the `--detect-window`, or
+/// 1900-2100.
+fn plausible_year(year: i32, p: &EpochParser) -> bool {
+    let (from, to) = p.autodetect.window.unwrap_or(COMPACT_YEARS);
+    (from..=to).contains(&year)
+}
+
+/// Digits-only date-times, as in filenames, backups and some databases: `20231114221320`
+/// or, without seconds, `202311142213`, read in `p`'s input zone. Only when the digits
+/// make a valid date-time in a plausible year (the `--detect-window`, or 1900-2100);
+/// anything else is left to the timestamp reading.
+pub(crate) fn compact(input: &str, p: &EpochParser) -> Option<Parsed> {
+    if !compact_candidate(input, p, &[12, 14]) {
+        return None;
+    }
+    let layout = if input.len() == 14 {
+        "%Y%m%d%H%M%S"
+    } else {
+        "%Y%m%d%H%M"
+    };
+    let naive = NaiveDateTime::parse_from_str(input, layout).ok()?;
+    if !plausible_year(naive.year(), p) {
+        return None;
+    }
+    Some(crate::naive_to_utc(&naive, p.input_tz).map(|dt| {
+        (
+            dt,
+            ParsedAs::Layout {
+                name: "compact",
+                offset: None,
+            },
+        )
+    }))
+}
+
+/// A calendar date alone: `2023-11-14`, `2023/11/14` or, under the same conditions as
+/// [`compact`], `20231114`. It stands for `p`'s default time (midnight unless set) on
+/// that day in `p`'s input zone.
+pub(crate) fn date(input: &str, p: &EpochParser) -> Option<Parsed> {
+    let layout = match input.as_bytes() {
+        [_, _, _, _, b'-', _, _, b'-', _, _] => "%Y-%m-%d",
+        [_, _, _, _, b'/', _, _, b'/', _, _] => "%Y/%m/%d",
+        _ if compact_candidate(input, p, &[8]) => "%Y%m%d",
+        _ => return None,
+    };
+    let date = match NaiveDate::parse_from_str(input, layout) {
+        Ok(date) => date,
+        // Digits in the right places, but no such day: `2024-02-30`.
+        Err(_) if layout != "%Y%m%d" && input.bytes().filter(u8::is_ascii_digit).count() == 8 => {
+            return Some(Err(EpochError::InvalidInput(format!(
+                "`{input}` is not a calendar date"
+            ))));
+        }
+        Err(_) => return None,
+    };
+    if layout == "%Y%m%d" && !plausible_year(date.year(), p) {
+        return None;
+    }
+    let naive = date.and_time(p.default_time.time());
+    Some(day_to_utc(naive, p).map(|dt| {
+        (
+            dt,
+            ParsedAs::Layout {
+                name: "date",
+                offset: None,
+            },
+        )
+    }))
+}
+
+/// `naive` on a date-only input's day in `p`'s input zone. At the start of the day, a
+/// midnight that clocks skip or repeat becomes the first instant the day has.
+fn day_to_utc(naive: NaiveDateTime, p: &EpochParser) -> Result<DateTime<Utc>, EpochError> {
+    match p.default_time {
+        DefaultTime::Start => crate::period::first_utc_from(naive, p.input_tz)
+            .map_or_else(|| crate::naive_to_utc(&naive, p.input_tz), Ok),
+        _ => crate::naive_to_utc(&naive, p.input_tz),
+    }
+}
+
+/// A month alone: `2023-11` or `2023/11`. It stands for the start of its first day in
+/// `p`'s input zone, or with a default time of [`DefaultTime::End`], the end of its last.
+pub(crate) fn month(input: &str, p: &EpochParser) -> Option<Parsed> {
+    let (year, month) = match input.as_bytes() {
+        [_, _, _, _, b'-' | b'/', _, _] => (&input[..4], &input[5..]),
+        _ => return None,
+    };
+    if !year
+        .bytes()
+        .chain(month.bytes())
+        .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    let Some(first) = NaiveDate::from_ymd_opt(year.parse().ok()?, month.parse().ok()?, 1) else {
+        // The `YYYY-MM` shape, but no such month: `2023-13`.
+        return Some(Err(EpochError::InvalidInput(format!(
+            "`{input}` is not a calendar month"
+        ))));
+    };
+    let naive = p.default_time.in_month(first);
+    Some(day_to_utc(naive, p).map(|dt| {
+        (
+            dt,
+            ParsedAs::Layout {
+                name: "month",
+                offset: None,
+            },
+        )
+    }))
+}
+
+/// Time-of-day layouts, 24-hour and then 12-hour.
+const TIMES: &[&str] = &[
+    "%H:%M",
+    "%H:%M:%S%.f",
+    "%I:%M%p",
+    "%I:%M %p",
+    "%I:%M:%S%p",
+    "%I:%M:%S %p",
+    "%I%p",
+    "%I %p",
+];
+
+/// A time of day alone: `22:13`, `22:13:20`, `10:13pm`, `10 PM`. It is read on `p`'s
+/// default date, or else on the date it is at `now` in `p`'s input zone.
+pub(crate) fn time(input: &str, p: &EpochParser, now: DateTime<Utc>) -> Option<Parsed> {
+    let time = TIMES.iter().find_map(|layout| {
+        let mut parsed = chrono::format::Parsed::new();
+        chrono::format::parse(&mut parsed, input, StrftimeItems::new(layout)).ok()?;
+        // `5pm` leaves the minute unset.
+        if parsed.minute().is_none() {
+            parsed.set_minute(0).ok()?;
+        }
+        parsed.to_naive_time().ok()
+    })?;
+    let date = p
+        .default_date
+        .unwrap_or_else(|| p.input_tz.naive_local(now).date());
+    Some(
+        crate::naive_to_utc(&date.and_time(time), p.input_tz).map(|dt| {
+            (
+                dt,
+                ParsedAs::Layout {
+                    name: "time",
+                    offset: None,
+                },
+            )
+        }),
+    )
+}
+
+/// HTTP's IMF-fixdate (`Date`, `Expires`, `Last-Modified`): `Tue, 14 Nov 2023 22:13:20 GMT`.
+pub(crate) const HTTP_DATE: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+/// IMF-fixdate always has a four-digit year; chrono's `%Y` would also take `23`, so
+/// obsolete forms are left to [`email`], which knows what two-digit years mean.
+pub(crate) fn http(input: &str) -> Option<Parsed> {
+    let year = input.split(' ').nth(3)?;
+    if year.len() != 4 || !year.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let naive = NaiveDateTime::parse_from_str(input, HTTP_DATE).ok()?;
+    Some(with_offset(
+        "http",
+        Utc.from_utc_datetime(&naive).fixed_offset(),
+    ))
+}
+
+/// An RFC 5322 email date, optionally with the `Date:` header name in front:
+/// `Tue, 14 Nov 2023 17:13 EST`. The obsolete grammar is accepted too: two-digit years,
+/// `UT`/`GMT`/North American zone names, military letters and trailing comments.
+pub(crate) fn email(input: &str) -> Option<Parsed> {
+    let value = match input.get(..5) {
+        Some(name) if name.eq_ignore_ascii_case("date:") => input[5..].trim_start(),
+        _ => input,
+    };
+    let dt = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(with_offset("email", dt))
+}
+
+/// git's date formats: the `git log` default (`Tue Nov 14 22:13:20 2023 +0200`),
+/// `--date=iso` (`2023-11-14 22:13:20 +0200`) and raw (`1700000000 +0200`).
+pub(crate) fn git(input: &str) -> Option<Parsed> {
+    if let Some((secs, offset)) = input.split_once(' ')
+        && let Ok(secs) = secs.parse::<i64>()
+        && let Some(offset) = crate::offset::parse_numeric_offset(offset)
+    {
+        let Some(dt) = DateTime::from_timestamp(secs, 0) else {
+            return Some(Err(EpochError::OutOfRange));
+        };
+        return Some(with_offset("git", dt.with_timezone(&offset)));
+    }
+    ["%a %b %e %H:%M:%S %Y %z", "%Y-%m-%d %H:%M:%S %z"]
+        .iter()
+        .find_map(|layout| DateTime::parse_from_str(input, layout).ok())
+        .map(|dt| with_offset("git", dt))
+}
+
+/// MySQL and Postgres datetime literals: `2023-11-14 22:13:20[.ffffff][+02]`. Without an
+/// offset the time is read in `p`'s input zone.
+pub(crate) fn sql(input: &str, p: &EpochParser) -> Option<Parsed> {
+    if let Ok(dt) = DateTime::parse_from_str(input, "%Y-%m-%d %H:%M:%S%.f%#z") {
+        return Some(with_offset("sql", dt));
+    }
+    let naive = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M:%S%.f").ok()?;
+    Some(crate::naive_to_utc(&naive, p.input_tz).map(|dt| {
+        (
+            dt,
+            ParsedAs::Layout {
+                name: "sql",
+                offset: None,
+            },
+        )
+    }))
+}
+
+/// RFC 3164 syslog: `Nov 14 22:13:20` (day space-padded, no year or zone). The year comes
+/// from `p`'s syslog year policy and the time is read in its input zone.
+pub(crate) fn syslog(input: &str, p: &EpochParser, now: DateTime<Utc>) -> Option<Parsed> {
+    let in_year = |year: i32| {
+        NaiveDateTime::parse_from_str(&format!("{year} {input}"), "%Y %b %e %H:%M:%S").ok()
+    };
+    // Check the layout in a leap year so that Feb 29 is not mistaken for garbage.
+    in_year(2000)?;
+
+    let this_year = p.input_tz.naive_local(now).year();
+    let naive = match p.syslog_year {
+        SyslogYear::Year(year) => in_year(year),
+        SyslogYear::Current => in_year(this_year),
+        SyslogYear::Recent => {
+            let horizon = p.input_tz.naive_local(now) + Duration::days(1);
+            (this_year - 8..=this_year + 1)
+                .rev()
+                .filter_map(in_year)
+                .find(|naive| *naive <= horizon)
+        }
+    };
+    let Some(naive) = naive else {
+        return Some(Err(EpochError::InvalidInput(format!(
+            "{input} does not exist in the chosen syslog year"
+        ))));
+    };
+
+    Some(crate::naive_to_utc(&naive, p.input_tz).map(|dt| {
+        (
+            dt,
+            ParsedAs::Layout {
+                name: "syslog",
+                offset: None,
+            },
+        )
+    }))
+}
+
+/// The field of a `journalctl -o export` entry holding its wall-clock time.
+const JOURNAL_FIELD: &str = "__REALTIME_TIMESTAMP";
+
+/// journald microseconds since the epoch.
+fn journal_micros(micros: &str) -> Parsed {
+    let micros = micros.parse::<i64>().map_err(|_| {
+        EpochError::InvalidInput(format!("expected journald microseconds, got `{micros}`"))
+    })?;
+    let dt = DateTime::from_timestamp_micros(micros).ok_or(EpochError::OutOfRange)?;
+    Ok((
+        dt,
+        ParsedAs::Layout {
+            name: "journal",
+            offset: None,
+        },
+    ))
+}
+
+/// A `__REALTIME_TIMESTAMP=1699999999123456` export field.
+pub(crate) fn journal(input: &str) -> Option<Parsed> {
+    let micros = input.strip_prefix(JOURNAL_FIELD)?.strip_prefix('=')?;
+    Some(journal_micros(micros))
+}
+
+/// A UTC time in exactly `layout`, reported as `name`.
+fn exact_utc(input: &str, layout: &str, name: &'static str) -> Parsed {
+    let naive = NaiveDateTime::parse_from_str(input, layout).map_err(|_| {
+        EpochError::InvalidInput(format!("`{input}` is not a {name} time ({layout})"))
+    })?;
+    with_offset(name, Utc.from_utc_datetime(&naive).fixed_offset())
+}
+
+/// The Julian day number of the Unix epoch.
+const UNIX_EPOCH_JD: f64 = 2_440_587.5;
+
+/// MATLAB's datenum of the Unix epoch.
+const UNIX_EPOCH_DATENUM: f64 = 719_529.0;
+
+const MILLIS_PER_DAY: f64 = 86_400_000.0;
+
+/// An instant given as fractional days since an epoch, rounded to the millisecond like
+/// SQLite does.
+fn from_days(input: &str, epoch_days: f64, name: &'static str) -> Parsed {
+    let days = input
+        .parse::<f64>()
+        .ok()
+        .filter(|d| d.is_finite())
+        .ok_or_else(|| EpochError::InvalidInput(format!("expected {name} days, got `{input}`")))?;
+    let millis = ((days - epoch_days) * MILLIS_PER_DAY).round();
+    if millis.abs() >= i64::MAX as f64 {
+        return Err(EpochError::OutOfRange);
+    }
+    let dt = DateTime::from_timestamp_millis(millis as i64).ok_or(EpochError::OutOfRange)?;
+    Ok((dt, ParsedAs::Layout { name, offset: None }))
+}
+
+fn to_days(dt: DateTime<Utc>, epoch_days: f64) -> String {
+    (epoch_days + dt.timestamp_millis() as f64 / MILLIS_PER_DAY).to_string()
+}
+
+/// Seconds from 1904-01-01 to the Unix epoch.
+const MAC_EPOCH_OFFSET: i64 = 2_082_844_800;
+
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+
+/// Decimal seconds since an epoch `offset` seconds before the Unix one. The fraction is
+/// read digit by digit (up to nanoseconds) rather than through a float.
+fn from_secs(input: &str, offset: i64, name: &'static str) -> Parsed {
+    let invalid = || EpochError::InvalidInput(format!("expected {name} seconds, got `{input}`"));
+    let (whole, fraction) = input.split_once('.').unwrap_or((input, ""));
+    let (negative, digits) = match whole.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, whole),
+    };
+    if digits.is_empty()
+        || fraction.len() > 9
+        || !(digits.bytes().chain(fraction.bytes())).all(|b| b.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+    let secs = digits.parse::<i128>().map_err(|_| EpochError::OutOfRange)?;
+    let nanos = format!("{fraction:0<9}")
+        .parse::<i128>()
+        .map_err(|_| invalid())?;
+    let total = (secs * NANOS_PER_SEC + nanos) * if negative { -1 } else { 1 };
+
+    let secs = i64::try_from(total.div_euclid(NANOS_PER_SEC)).ok();
+    let dt = secs
+        .and_then(|secs| secs.checked_sub(offset))
+        .and_then(|secs| DateTime::from_timestamp(secs, total.rem_euclid(NANOS_PER_SEC) as u32))
+        .ok_or(EpochError::OutOfRange)?;
+    Ok((dt, ParsedAs::Layout { name, offset: None }))
+}
+
+fn to_secs(dt: DateTime<Utc>, offset: i64) -> String {
+    let total = i128::from(dt.timestamp() + offset) * NANOS_PER_SEC
+        + i128::from(dt.timestamp_subsec_nanos());
+    let sign = if total < 0 { "-" } else { "" };
+    let (secs, nanos) = (total.abs() / NANOS_PER_SEC, total.abs() % NANOS_PER_SEC);
+    match nanos {
+        0 => format!("{sign}{secs}"),
+        _ => format!("{sign}{secs}.{nanos:09}")
+            .trim_end_matches('0')
+            .to_string(),
+    }
+}
+
+/// `input` read with a user-supplied strftime pattern (`--input-format`), placing `%y`
+/// years by the parser's century pivot.
+pub(crate) fn custom(input: &str, pattern: &str, p: &EpochParser) -> Parsed {
+    let invalid = || EpochError::InvalidInput(format!("`{input}` does not match `{pattern}`"));
+    let mut parsed = chrono::format::Parsed::new();
+    chrono::format::parse(&mut parsed, input, StrftimeItems::new(pattern))
+        .map_err(|_| invalid())?;
+    if let Some(yy) = parsed.year_mod_100()
+        && parsed.year().is_none()
+        && parsed.year_div_100().is_none()
+    {
+        let century = crate::expand_year(yy, p.century_pivot) / 100;
+        parsed
+            .set_year_div_100(i64::from(century))
+            .map_err(|_| invalid())?;
+    }
+
+    let date = parsed.to_naive_date().map_err(|_| invalid())?;
+    let naive = date.and_time(parsed.to_naive_time().unwrap_or(p.default_time.time()));
+    let name = "custom";
+    match parsed.offset() {
+        Some(secs) => {
+            let offset = FixedOffset::east_opt(secs).ok_or_else(invalid)?;
+            let dt = naive
+                .and_local_timezone(offset)
+                .single()
+                .ok_or_else(invalid)?;
+            with_offset(name, dt)
+        }
+        None => crate::naive_to_utc(&naive, p.input_tz)
+            .map(|dt| (dt, ParsedAs::Layout { name, offset: None })),
+    }
+}
+
+fn from_radix(input: &str, radix: Radix, p: &EpochParser) -> Parsed {
+    let raw = radix::decode(input, radix).ok_or_else(|| {
+        EpochError::InvalidInput(format!("expected a {radix:?} timestamp, got `{input}`"))
+    })?;
+    let unit = p.unit.unwrap_or_else(|| p.autodetect.unit(raw));
+    crate::parse_timestamp_to_utc(raw, Some(unit))
+        .map(|(dt, unit)| (dt, ParsedAs::Timestamp { unit, raw }))
+}
+
+/// `{"seconds": S, "nanos": N}`, keys in either order. As in protobuf, `seconds` is the
+/// floor of the instant, so `nanos` is never negative, and counts up to 999,999,999.
+fn proto_timestamp(input: &str) -> Parsed {
+    let invalid = || {
+        EpochError::InvalidInput(format!(
+            "expected {{\"seconds\": ..., \"nanos\": ...}}, got `{input}`"
+        ))
+    };
+    let body = input
+        .trim()
+        .strip_prefix('{')
+        .and_then(|rest| rest.strip_suffix('}'))
+        .ok_or_else(invalid)?;
+    let (mut seconds, mut nanos) = (None, None);
+    for field in body.split(',').filter(|field| !field.trim().is_empty()) {
+        let (key, value) = field.split_once(':').ok_or_else(invalid)?;
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+        let slot = match key.trim() {
+            "\"seconds\"" => &mut seconds,
+            "\"nanos\"" => &mut nanos,
+            _ => return Err(invalid()),
+        };
+        *slot = Some(value.parse::<i64>().map_err(|_| invalid())?);
+    }
+    let nanos = u32::try_from(nanos.unwrap_or(0))
+        .ok()
+        .filter(|&n| n < 1_000_000_000)
+        .ok_or_else(invalid)?;
+    let dt = DateTime::from_timestamp(seconds.ok_or_else(invalid)?, nanos)
+        .ok_or(EpochError::OutOfRange)?;
+    Ok((
+        dt,
+        ParsedAs::Layout {
+            name: "proto_timestamp",
+            offset: None,
+        },
+    ))
+}
+
+/// Read `input` in an explicitly chosen encoding.
+///
+/// Base36 and base62 codes decode to an integer that is then read like any other, in
+/// the forced unit or the autodetected one.
+pub(crate) fn decode(input: &str, encoding: Encoding, p: &EpochParser) -> Parsed {
+    match encoding {
+        Encoding::Journal => journal(input).unwrap_or_else(|| journal_micros(input)),
+        Encoding::Cloudtrail => exact_utc(input, "%Y-%m-%dT%H:%M:%SZ", "cloudtrail"),
+        Encoding::S3 => exact_utc(input, "%Y-%m-%dT%H:%M:%S%.3fZ", "s3"),
+        Encoding::CloudwatchLogs => {
+            let raw = input.parse::<i64>().map_err(|_| {
+                EpochError::InvalidInput(format!("expected epoch milliseconds, got `{input}`"))
+            })?;
+            crate::parse_timestamp_to_utc(raw, Some(TsUnit::Millis))
+                .map(|(dt, unit)| (dt, ParsedAs::Timestamp { unit, raw }))
+        }
+        Encoding::Julianday => from_days(input, UNIX_EPOCH_JD, "julianday"),
+        Encoding::Matlab => from_days(input, UNIX_EPOCH_DATENUM, "matlab"),
+        Encoding::Mac1904 => from_secs(input, MAC_EPOCH_OFFSET, "mac1904"),
+        Encoding::GpsWeek => gps::from_week(input, Utc::now()).map(|dt| {
+            (
+                dt,
+                ParsedAs::Layout {
+                    name: "gps_week",
+                    offset: None,
+                },
+            )
+        }),
+        Encoding::Base36 => from_radix(input, Radix::Base36, p),
+        Encoding::Base62 => from_radix(input, Radix::Base62, p),
+        Encoding::ProtoTimestamp => proto_timestamp(input),
+    }
+}
+
+/// Render `dt` in `encoding`, the way the system it comes from prints it.
+pub(crate) fn encode(dt: DateTime<Utc>, encoding: Encoding) -> String {
+    match encoding {
+        Encoding::Journal => dt.timestamp_micros().to_string(),
+        Encoding::Cloudtrail => dt.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        Encoding::S3 => dt.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+        Encoding::CloudwatchLogs => dt.timestamp_millis().to_string(),
+        Encoding::Julianday => to_days(dt, UNIX_EPOCH_JD),
+        Encoding::Matlab => to_days(dt, UNIX_EPOCH_DATENUM),
+        Encoding::Mac1904 => to_secs(dt, MAC_EPOCH_OFFSET),
+        Encoding::GpsWeek => {
+            let (week, tow) = gps::to_week(dt);
+            let tow = to_secs(DateTime::UNIX_EPOCH + tow, 0);
+            format!("{week}:{tow}")
+        }
+        Encoding::Base36 => radix::encode(dt.timestamp(), Radix::Base36),
+        Encoding::Base62 => radix::encode(dt.timestamp(), Radix::Base62),
+        Encoding::ProtoTimestamp => format!(
+            "{{\"seconds\": {}, \"nanos\": {}}}",
+            dt.timestamp(),
+            dt.timestamp_subsec_nanos()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The layouts alone, in the order autodetection tries them.
+    fn parse(input: &str) -> Option<Parsed> {
+        let p = EpochParser::new();
+        clf(input)
+            .or_else(|| rfc3339(input))
+            .or_else(|| http(input))
+            .or_else(|| email(input))
+            .or_else(|| git(input))
+            .or_else(|| sql(input, &p))
+            .or_else(|| journal(input))
+            .or_else(|| syslog(input, &p, Utc::now()))
+    }
+
+    fn decode(input: &str, encoding: Encoding) -> Parsed {
+        super::decode(input, encoding, &EpochParser::new())
+    }
+
+    #[test]
+    fn reads_common_log_format() {
+        for input in ["[14/Nov/2023:22:13:20 +0000]", "15/Nov/2023:00:13:20 +0200"] {
+            let (dt, parsed_as) = parse(input).unwrap().unwrap();
+            assert_eq!(dt.timestamp(), 1_700_000_000, "{input}");
+            assert!(matches!(parsed_as, ParsedAs::Layout { name: "clf", .. }));
+        }
+        assert!(parse("14/Nov/2023:22:13:20").is_none());
+    }
+
+    #[test]
+    fn reads_rfc5424_timestamps() {
+        let (dt, _) = parse("2023-11-15T00:13:20.003+02:00").unwrap().unwrap();
+        assert_eq!(dt.timestamp_millis(), 1_700_000_000_003);
+    }
+
+    #[test]
+    fn keeps_rfc3339_nanoseconds() {
+        let (dt, _) = parse("2023-11-14T22:13:20.123456789Z").unwrap().unwrap();
+        assert_eq!(dt.timestamp_nanos_opt(), Some(1_700_000_000_123_456_789));
+    }
+
+    #[test]
+    fn reads_http_dates() {
+        let (dt, parsed_as) = parse("Tue, 14 Nov 2023 22:13:20 GMT").unwrap().unwrap();
+        assert_eq!(dt.timestamp(), 1_700_000_000);
+        assert!(matches!(parsed_as, ParsedAs::Layout { name: "http", .. }));
+        // The weekday has to agree with the date.
+        assert!(parse("Wed, 14 Nov 2023 22:13:20 GMT").is_none());
+    }
+
+    #[test]
+    fn reads_email_dates() {
+        for input in [
+            "Tue, 14 Nov 2023 22:13:20 +0000",
+            "Date: Tue, 14 Nov 2023 17:13:20 EST",
+            "14 Nov 2023 14:13:20 -0800 (PST)",
+            "Tue, 14 Nov 23 22:13:20 UT",
+        ] {
+            let (dt, parsed_as) = parse(input).unwrap().unwrap();
+            assert_eq!(dt.timestamp(), 1_700_000_000, "{input}");
+            assert!(matches!(parsed_as, ParsedAs::Layout { name: "email", .. }));
+        }
+        // Seconds are optional.
+        let (dt, _) = parse("14 Nov 2023 22:13 GMT").unwrap().unwrap();
+        assert_eq!(dt.timestamp(), 1_699_999_980);
+    }
+
+    #[test]
+    fn reads_git_dates() {
+        for input in [
+            "Wed Nov 15 00:13:20 2023 +0200",
+            "2023-11-15 00:13:20 +0200",
+            "1700000000 +0200",
+        ] {
+            let (dt, parsed_as) = parse(input).unwrap().unwrap();
+            assert_eq!(dt.timestamp(), 1_700_000_000, "{input}");
+            assert_eq!(parsed_as.source_offset().unwrap().local_minus_utc(), 7200);
+        }
+    }
+
+    #[test]
+    fn reads_sql_literals() {
+        let p = EpochParser::new().input_tz(crate::zone::TzChoice::Utc);
+        for input in [
+            "2023-11-14 22:13:20",
+            "2023-11-15 00:13:20+02",
+            "2023-11-14 17:13:20.000000-0500",
+        ] {
+            let (dt, parsed_as) = sql(input, &p).unwrap().unwrap();
+            assert_eq!(dt.timestamp(), 1_700_000_000, "{input}");
+            assert!(matches!(parsed_as, ParsedAs::Layout { name: "sql", .. }));
+        }
+        let (dt, _) = sql("2023-11-14 22:13:20.123456", &p).unwrap().unwrap();
+        assert_eq!(dt.timestamp_micros(), 1_700_000_000_123_456);
+    }
+
+    #[test]
+    fn reads_journald_microseconds() {
+        let (dt, parsed_as) = parse("__REALTIME_TIMESTAMP=1700000000123456")
+            .unwrap()
+            .unwrap();
+        assert_eq!(dt.timestamp_micros(), 1_700_000_000_123_456);
+        assert!(matches!(
+            parsed_as,
+            ParsedAs::Layout {
+                name: "journal",
+                ..
+            }
+        ));
+
+        let (dt, _) = decode("1700000000123456", Encoding::Journal).unwrap();
+        assert_eq!(dt.timestamp_micros(), 1_700_000_000_123_456);
+        assert!(decode("MESSAGE=hello", Encoding::Journal).is_err());
+    }
+
+    #[test]
+    fn reads_aws_formats() {
+        let millis = |input, encoding| decode(input, encoding).unwrap().0.timestamp_millis();
+        assert_eq!(
+            millis("2023-11-14T22:13:20Z", Encoding::Cloudtrail),
+            1_700_000_000_000
+        );
+        assert_eq!(
+            millis("2023-11-14T22:13:20.123Z", Encoding::S3),
+            1_700_000_000_123
+        );
+        // Small values stay milliseconds instead of being taken for seconds.
+        assert_eq!(millis("86400000", Encoding::CloudwatchLogs), 86_400_000);
+        assert!(decode("2023-11-14T22:13:20+02:00", Encoding::Cloudtrail).is_err());
+    }
+
+    #[test]
+    fn julian_days_match_sqlite() {
+        // SELECT julianday('2023-11-14 22:13:20') = 2460263.42592593
+        let (dt, _) = decode("2460263.42592593", Encoding::Julianday).unwrap();
+        assert_eq!(dt.timestamp_millis(), 1_700_000_000_000);
+        let (dt, _) = decode("2440587.5", Encoding::Julianday).unwrap();
+        assert_eq!(dt.timestamp(), 0);
+
+        let dt = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let round_trip = decode(&encode(dt, Encoding::Julianday), Encoding::Julianday);
+        assert_eq!(round_trip.unwrap().0, dt);
+        assert!(decode("NaN", Encoding::Julianday).is_err());
+    }
+
+    #[test]
+    fn matlab_datenums() {
+        // datenum(2023, 11, 14, 22, 13, 20) = 739204.925925926
+        let (dt, parsed_as) = decode("739204.925925926", Encoding::Matlab).unwrap();
+        assert_eq!(dt.timestamp_millis(), 1_700_000_000_000);
+        assert!(matches!(parsed_as, ParsedAs::Layout { name: "matlab", .. }));
+        let (dt, _) = decode("1", Encoding::Matlab).unwrap();
+        assert_eq!(dt.to_rfc3339(), "0000-01-01T00:00:00+00:00");
+    }
+
+    #[test]
+    fn mac_1904_seconds() {
+        let (dt, _) = decode("0", Encoding::Mac1904).unwrap();
+        assert_eq!(dt.to_rfc3339(), "1904-01-01T00:00:00+00:00");
+        let (dt, _) = decode("3782844800.25", Encoding::Mac1904).unwrap();
+        assert_eq!(dt.timestamp_millis(), 1_700_000_000_250);
+        assert_eq!(encode(dt, Encoding::Mac1904), "3782844800.25");
+        assert!(decode("3782844800.", Encoding::Mac1904).is_ok());
+        for pre_1904 in ["-1.5", "-0.25"] {
+            let (dt, _) = decode(pre_1904, Encoding::Mac1904).unwrap();
+            assert_eq!(encode(dt, Encoding::Mac1904), pre_1904);
+        }
+        assert!(decode("37828.4480e3", Encoding::Mac1904).is_err());
+    }
+
+    #[test]
+    fn encodes_in_the_source_format() {
+        let dt = DateTime::from_timestamp_millis(1_700_000_000_123).unwrap();
+        for encoding in [
+            Encoding::Journal,
+            Encoding::Cloudtrail,
+            Encoding::S3,
+            Encoding::CloudwatchLogs,
+            Encoding::Base36,
+            Encoding::Base62,
+        ] {
+            let (back, _) = decode(&encode(dt, encoding), encoding).unwrap();
+            assert_eq!(back.timestamp(), dt.timestamp(), "{encoding:?}");
+        }
+        assert_eq!(encode(dt, Encoding::S3), "2023-11-14T22:13:20.123Z");
+    }
+
+    #[test]
+    fn reads_plausible_compact_digits() {
+        let p = EpochParser::new().input_tz(crate::zone::TzChoice::Utc);
+        let read = |input, p: &EpochParser| compact(input, p).map(|r| r.unwrap().0.timestamp());
+        assert_eq!(read("20231114221320", &p), Some(1_700_000_000));
+        assert_eq!(read("202311142213", &p), Some(1_699_999_980));
+        // Not a valid date-time, or not in a plausible year: a timestamp after all.
+        assert_eq!(read("17000000000000", &p), None);
+        assert_eq!(read("20231314221320", &p), None);
+        assert_eq!(read("946684800000", &p), None);
+        assert_eq!(read("2023111422132", &p), None);
+        assert_eq!(
+            read("20231114221320", &p.clone().compact_digits(false)),
+            None
+        );
+        assert_eq!(
+            read("20231114221320", &p.clone().force_unit(TsUnit::Micros)),
+            None
+        );
+        let narrow = p.autodetect(crate::Autodetect {
+            window: Some((2024, 2030)),
+            ..Default::default()
+        });
+        assert_eq!(read("20231114221320", &narrow), None);
+    }
+
+    #[test]
+    fn reads_dates_as_midnight() {
+        let p = EpochParser::new().input_tz("+02:00".parse().unwrap());
+        let read = |input, p: &EpochParser| date(input, p).map(|r| r.unwrap().0.timestamp());
+        for input in ["2023-11-14", "2023/11/14", "20231114"] {
+            assert_eq!(read(input, &p), Some(1_699_912_800), "{input}");
+        }
+        assert!(matches!(
+            date("2023-11-31", &p),
+            Some(Err(EpochError::InvalidInput(_)))
+        ));
+        assert_eq!(read("20231131", &p), None);
+        assert_eq!(read("2023-11-14 22:13", &p), None);
+        // Eight digits stay a timestamp under the same conditions as compact date-times.
+        assert_eq!(read("17000000", &p), None);
+        assert_eq!(read("20231114", &p.clone().compact_digits(false)), None);
+        assert_eq!(
+            read("2023/11/14", &p.clone().compact_digits(false)),
+            Some(1_699_912_800)
+        );
+        let end = p.default_time(DefaultTime::End);
+        assert_eq!(read("2023-11-14", &end), Some(1_699_912_800 + 86_399));
+    }
+
+    #[cfg(feature = "tz")]
+    #[test]
+    fn dates_whose_midnight_is_skipped_start_when_clocks_land() {
+        // America/Santiago goes from 00:00 -04 straight to 01:00 -03 on 2024-09-08.
+        let p = EpochParser::new().input_tz("America/Santiago".parse().unwrap());
+        let (dt, _) = date("2024-09-08", &p).unwrap().unwrap();
+        assert_eq!(dt.to_rfc3339(), "2024-09-08T04:00:00+00:00");
+    }
+
+    #[test]
+    fn reads_months_from_their_start_or_end() {
+        let p = EpochParser::new().input_tz(crate::zone::TzChoice::Utc);
+        let read = |input, p: &EpochParser| month(input, p).map(|r| r.unwrap().0.to_rfc3339());
+        for input in ["2023-11", "2023/11"] {
+            assert_eq!(
+                read(input, &p).as_deref(),
+                Some("2023-11-01T00:00:00+00:00"),
+                "{input}"
+            );
+        }
+        let end = p.clone().default_time(DefaultTime::End);
+        assert_eq!(
+            read("2024-02", &end).as_deref(),
+            Some("2024-02-29T23:59:59+00:00")
+        );
+        assert_eq!(
+            read("2023-12", &end).as_deref(),
+            Some("2023-12-31T23:59:59+00:00")
+        );
+        let noon = p.clone().default_time("noon".parse().unwrap());
+        assert_eq!(
+            read("2023-11", &noon).as_deref(),
+            Some("2023-11-01T12:00:00+00:00")
+        );
+        for input in ["2023-13", "2023/00"] {
+            assert!(
+                matches!(month(input, &p), Some(Err(EpochError::InvalidInput(_)))),
+                "{input}"
+            );
+        }
+        assert_eq!(read("2023-1", &p), None);
+        assert_eq!(read("2023-11-14", &p), None);
+    }
+
+    #[test]
+    fn reads_times_on_today_or_the_default_date() {
+        let p = EpochParser::new().input_tz("-05:00".parse().unwrap());
+        // 02:00 UTC on the 15th is still the 14th five hours west.
+        let now = DateTime::parse_from_rfc3339("2023-11-15T02:00:00Z")
+            .unwrap()
+            .to_utc();
+        let read = |input, p: &EpochParser| time(input, p, now).map(|r| r.unwrap().0.to_rfc3339());
+        for input in ["17:13:20", "5:13:20pm", "05:13:20 PM"] {
+            assert_eq!(
+                read(input, &p).as_deref(),
+                Some("2023-11-14T22:13:20+00:00"),
+                "{input}"
+            );
+        }
+        assert_eq!(
+            read("5pm", &p).as_deref(),
+            Some("2023-11-14T22:00:00+00:00")
+        );
+        assert_eq!(
+            read("17:13", &p).as_deref(),
+            Some("2023-11-14T22:13:00+00:00")
+        );
+        let on = p.clone().default_date("2024-02-29".parse().unwrap());
+        assert_eq!(
+            read("17:13", &on).as_deref(),
+            Some("2024-02-29T22:13:00+00:00")
+        );
+        assert_eq!(read("25:00", &p), None);
+        assert_eq!(read("1700", &p), None);
+    }
+
+    #[test]
+    fn reads_iso8601_basic() {
+        let p = EpochParser::new().input_tz("+01:00".parse().unwrap());
+        let read = |input| iso8601_basic(input, &p).map(|r| r.map(|(dt, a)| (dt.timestamp(), a)));
+        for (input, secs, offset) in [
+            ("20231114T221320Z", 1_700_000_000, Some(0)),
+            ("20231115T001320+0200", 1_700_000_000, Some(7200)),
+            ("20231115T001320+02", 1_700_000_000, Some(7200)),
+            ("20231114T231320", 1_700_000_000, None),
+        ] {
+            let (dt, parsed_as) = read(input).unwrap().unwrap();
+            assert_eq!(dt, secs, "{input}");
+            let offset_secs = parsed_as.source_offset().map(|o| o.local_minus_utc());
+            assert_eq!(offset_secs, offset, "{input}");
+        }
+        let (dt, _) = iso8601_basic("20231114T221320.250Z", &p).unwrap().unwrap();
+        assert_eq!(dt.timestamp_millis(), 1_700_000_000_250);
+        assert!(read("2023-11-14T22:13:20Z").is_none());
+        assert!(read("20231114").is_none());
+    }
+
+    #[test]
+    fn reads_and_writes_proto_timestamps() {
+        let dt = DateTime::from_timestamp(1_700_000_000, 123_000_000).unwrap();
+        let json = encode(dt, Encoding::ProtoTimestamp);
+        assert_eq!(json, r#"{"seconds": 1700000000, "nanos": 123000000}"#);
+        assert_eq!(decode(&json, Encoding::ProtoTimestamp).unwrap().0, dt);
+        let before_1970 = DateTime::from_timestamp_millis(-1_500).unwrap();
+        assert_eq!(
+            encode(before_1970, Encoding::ProtoTimestamp),
+            r#"{"seconds": -2, "nanos": 500000000}"#
+        );
+        for same in [
+            r#"{"nanos":123000000,"seconds":"1700000000"}"#,
+            r#" { "seconds": 1700000000, "nanos": 123000000 } "#,
+        ] {
+            assert_eq!(decode(same, Encoding::ProtoTimestamp).unwrap().0, dt);
+        }
+        let (dt, _) = decode(r#"{"seconds": 5}"#, Encoding::ProtoTimestamp).unwrap();
+        assert_eq!(dt.timestamp_nanos_opt(), Some(5_000_000_000));
+        for bad in [
+            r#"{"nanos": 1}"#,
+            r#"{"seconds": 1, "nanos": -1}"#,
+            r#"{"seconds": 1, "nanos": 1000000000}"#,
+            r#"{"seconds": 1, "millis": 1}"#,
+            "1700000000",
+        ] {
+            assert!(decode(bad, Encoding::ProtoTimestamp).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn reads_radix_codes_in_the_chosen_unit() {
+        let (dt, parsed_as) = decode("s44we8", Encoding::Base36).unwrap();
+        assert_eq!(dt.timestamp(), 1_700_000_000);
+        assert!(matches!(
+            parsed_as,
+            ParsedAs::Timestamp {
+                unit: TsUnit::Seconds,
+                ..
+            }
+        ));
+        let p = EpochParser::new().force_unit(TsUnit::Millis);
+        let (dt, _) = super::decode("1r31eq", Encoding::Base62, &p).unwrap();
+        assert_eq!(dt.timestamp_millis(), 1_700_000_000);
+        assert!(decode("s44we8", Encoding::Base62).is_ok());
+        assert!(decode("s4-4we8", Encoding::Base36).is_err());
+    }
+
+    #[test]
+    fn custom_formats_use_the_century_pivot() {
+        let p = EpochParser::new().input_tz(crate::zone::TzChoice::Utc);
+        let (dt, _) = custom("14/11/23", "%d/%m/%y", &p).unwrap();
+        assert_eq!(dt.to_rfc3339(), "2023-11-14T00:00:00+00:00");
+        let (dt, _) = custom("14/11/69", "%d/%m/%y", &p).unwrap();
+        assert_eq!(dt.year(), 2069);
+
+        let p = p.century_pivot(50);
+        let (dt, _) = custom("14/11/69", "%d/%m/%y", &p).unwrap();
+        assert_eq!(dt.year(), 1969);
+
+        let (dt, parsed_as) = custom("14.11.23 23:13 +0100", "%d.%m.%y %H:%M %z", &p).unwrap();
+        assert_eq!(dt.timestamp(), 1_699_999_980);
+        assert!(parsed_as.source_offset().is_some());
+        assert!(custom("2023-11-14", "%d/%m/%y", &p).is_err());
+    }
+
+    #[test]
+    fn syslog_year_follows_policy() {
+        let p = EpochParser::new().input_tz(crate::zone::TzChoice::Utc);
+        let now = DateTime::parse_from_rfc3339("2024-01-10T00:00:00Z")
+            .unwrap()
+            .to_utc();
+        let year = |input: &str, p: &EpochParser| {
+            let (dt, _) = syslog(input, p, now).unwrap().unwrap();
+            dt.year()
+        };
+
+        // December lines read in January belong to last year; a day of skew is allowed.
+        assert_eq!(year("Dec 31 23:59:59", &p), 2023);
+        assert_eq!(year("Jan 10 12:00:00", &p), 2024);
+        assert_eq!(year("Jan  9 08:00:00", &p), 2024);
+
+        let current = p.clone().syslog_year(SyslogYear::Current);
+        assert_eq!(year("Dec 31 23:59:59", &current), 2024);
+
+        let fixed = p.clone().syslog_year(SyslogYear::Year(2020));
+        assert_eq!(year("Feb 29 00:00:00", &fixed), 2020);
+        assert!(
+            syslog(
+                "Feb 29 00:00:00",
+                &p.syslog_year(SyslogYear::Year(2023)),
+                now
+            )
+            .unwrap()
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn parses_syslog_year_policies() {
+        assert_eq!("recent".parse(), Ok(SyslogYear::Recent));
+        assert_eq!("2019".parse(), Ok(SyslogYear::Year(2019)));
+        assert!("someday".parse::<SyslogYear>().is_err());
+    }
+}
@@ -0,0 +1,1587 @@
+//! Parsing and formatting of unix timestamps and formatted datetimes.
+//!
+//! This is the logic behind the `epoch` CLI (built with the default `cli` feature); it is
+//! also usable as a library, from JavaScript through the `wasm` feature and from C through
+//! the `ffi` feature. [`EpochParser`] and [`Formatter`] are the entry points; the `serde`
+//! feature adds `serde::flexible` for timestamp fields.
+//!
+//! The parser itself only needs chrono. IANA zone names come with the `tz` feature,
+//! [`instant_json`] with `json`, localized month and weekday names
+//! ([`Formatter::locale`]) with `locale`, and the free-text `scan` module (regex) with
+//! `scan`; `cli` turns on `tz`, `json` and `scan`. Embed the parser with
+//! `default-features = false` to leave clap and the rest of the CLI stack out.
+
+use chrono::{
+    DateTime, Datelike, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+    Weekday,
+};
+#[cfg(feature = "json")]
+use serde_json::json;
+
+pub use builder::{EpochParser, Formatter, HTML_TIME_DISPLAY, check_strftime};
+pub use error::EpochError;
+use expr::ExprError;
+pub use layouts::{DefaultTime, SyslogYear};
+use zone::TzChoice;
+
+pub mod age;
+pub mod arith;
+mod builder;
+pub mod calendars;
+#[cfg(feature = "scan")]
+pub mod detectors;
+pub mod duration;
+mod error;
+pub mod explain;
+pub mod expr;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fiscal;
+pub mod gps;
+pub mod holidays;
+pub mod ical;
+mod layouts;
+pub mod milestones;
+pub mod moon;
+pub mod offset;
+pub mod period;
+pub mod presets;
+#[cfg(feature = "json")]
+pub mod query;
+pub mod radix;
+#[cfg(feature = "json")]
+pub mod render;
+pub mod representations;
+#[cfg(feature = "scan")]
+pub mod scan;
+#[cfg(feature = "serde")]
+pub mod serde_helpers;
+#[cfg(feature = "serde")]
+pub use serde_helpers as serde;
+pub mod summary;
+pub mod sun;
+pub mod tokens;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod zone;
+
+/// Layout of formatted datetime input.
+pub const FORMATTED: &str = "%Y/%m/%d %H:%M:%S";
+
+/// Default `--century-pivot`: two-digit years map to 1970-2069, as in POSIX `%y`.
+pub const CENTURY_PIVOT: u8 = 70;
+
+/// The full year for a two-digit one: 20YY below `pivot`, 19YY from it on.
+pub(crate) fn expand_year(yy: i32, pivot: u8) -> i32 {
+    if yy < i32::from(pivot) {
+        2000 + yy
+    } else {
+        1900 + yy
+    }
+}
+
+/// Unit of a numeric timestamp.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum TsUnit {
+    Seconds,
+    Millis,
+    Micros,
+    Nanos,
+}
+
+impl TsUnit {
+    /// How many of this unit make a second.
+    pub fn per_second(self) -> i64 {
+        match self {
+            TsUnit::Seconds => 1,
+            TsUnit::Millis => 1_000,
+            TsUnit::Micros => 1_000_000,
+            TsUnit::Nanos => 1_000_000_000,
+        }
+    }
+
+    /// Name used in JSON (`ts_unit`) and by `--ts`.
+    pub fn name(self) -> &'static str {
+        match self {
+            TsUnit::Seconds => "seconds",
+            TsUnit::Millis => "millis",
+            TsUnit::Micros => "micros",
+            TsUnit::Nanos => "nanos",
+        }
+    }
+}
+
+/// Layout of JSON results (`--schema`). Within a version fields are only ever added.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum SchemaVersion {
+    /// The original fields, kept as the default for existing consumers
+    #[default]
+    #[cfg_attr(feature = "cli", value(name = "1"))]
+    V1,
+    /// Version 1 plus sub-second, offset, calendar (ISO week, quarter) and parse fields
+    #[cfg_attr(feature = "cli", value(name = "2"))]
+    V2,
+}
+
+/// A specific format to read INPUT in instead of autodetecting it (`--from`), or to
+/// render the result in (`--to`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Encoding {
+    /// journald's `__REALTIME_TIMESTAMP`: microseconds since the Unix epoch, bare or as
+    /// the `__REALTIME_TIMESTAMP=...` field of `journalctl -o export`.
+    Journal,
+    /// CloudTrail `eventTime`: `2023-11-14T22:13:20Z`.
+    Cloudtrail,
+    /// S3 `LastModified` as the API and CLI print it: `2023-11-14T22:13:20.000Z`.
+    S3,
+    /// CloudWatch Logs event times and `aws logs --start-time`: epoch milliseconds.
+    CloudwatchLogs,
+    /// SQLite's `julianday()`: fractional days since noon UTC on 24 November 4714 BC.
+    Julianday,
+    /// MATLAB's `datenum`: fractional days since the proleptic year 0, where
+    /// 0000-01-01 is day 1.
+    Matlab,
+    /// Seconds since 1904-01-01 UTC, optionally fractional, as used by HFS+, QuickTime
+    /// atoms and LabVIEW.
+    Mac1904,
+    /// GPS week number and time of week, `2291:345600`; 10-bit week numbers are unwrapped
+    /// to the latest era that is not in the future.
+    GpsWeek,
+    /// An integer timestamp in base36 (`0-9a-z`, any case), as `--radix base36` prints it.
+    Base36,
+    /// An integer timestamp in base62 (`0-9A-Za-z`), as `--radix base62` prints it.
+    Base62,
+    /// A `google.protobuf.Timestamp` as an object, `{"seconds": 1700000000, "nanos": 0}`;
+    /// `seconds` may be quoted, as the proto3 JSON mapping writes int64s, and `nanos` left
+    /// out.
+    ProtoTimestamp,
+}
+
+/// How an input was interpreted.
+#[derive(Clone, Debug)]
+pub enum ParsedAs {
+    Timestamp {
+        unit: TsUnit,
+        raw: i64,
+    },
+    /// `offset` is set when the input carried its own zone designator.
+    Formatted {
+        offset: Option<FixedOffset>,
+    },
+    WeekDate,
+    Dtg {
+        offset: Option<FixedOffset>,
+    },
+    /// An instant plus or minus durations: `now - 90m`
+    Expression,
+    /// A well-known log or protocol layout, such as `clf`; `name` is what JSON reports.
+    Layout {
+        name: &'static str,
+        offset: Option<FixedOffset>,
+    },
+}
+
+impl ParsedAs {
+    /// The name JSON output reports it under, e.g. `timestamp` or `clf`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ParsedAs::Timestamp { .. } => "timestamp",
+            ParsedAs::Formatted { .. } => "formatted",
+            ParsedAs::WeekDate => "week_date",
+            ParsedAs::Dtg { .. } => "dtg",
+            ParsedAs::Expression => "expression",
+            ParsedAs::Layout { name, .. } => name,
+        }
+    }
+
+    /// UTC offset stated by the input itself, if any.
+    pub fn source_offset(&self) -> Option<FixedOffset> {
+        match self {
+            ParsedAs::Formatted { offset }
+            | ParsedAs::Dtg { offset }
+            | ParsedAs::Layout { offset, .. } => *offset,
+            _ => None,
+        }
+    }
+}
+
+/// How numeric input picks its unit when none is forced.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Autodetect {
+    /// Magnitudes from this on are millis, smaller ones seconds (`--detect-threshold`).
+    pub threshold: u64,
+    /// Plausible years, inclusive (`--detect-window`). When exactly one unit (seconds
+    /// to nanos) puts the timestamp in this window it wins; otherwise the threshold
+    /// decides between seconds and millis.
+    pub window: Option<(i32, i32)>,
+}
+
+impl Default for Autodetect {
+    fn default() -> Self {
+        Autodetect {
+            threshold: 1_000_000_000_000,
+            window: None,
+        }
+    }
+}
+
+impl Autodetect {
+    /// The unit `raw` is read in.
+    pub fn unit(&self, raw: i64) -> TsUnit {
+        self.decide(raw).0
+    }
+
+    /// The unit `raw` is read in, plus the window when that alone decided it.
+    pub(crate) fn decide(&self, raw: i64) -> (TsUnit, Option<(i32, i32)>) {
+        if let Some((from, to)) = self.window {
+            let plausible: Vec<TsUnit> = [
+                TsUnit::Seconds,
+                TsUnit::Millis,
+                TsUnit::Micros,
+                TsUnit::Nanos,
+            ]
+            .into_iter()
+            .filter(|&unit| {
+                parse_timestamp_to_utc(raw, Some(unit))
+                    .is_ok_and(|(dt, _)| (from..=to).contains(&dt.year()))
+            })
+            .collect();
+            if let [unit] = plausible[..] {
+                return (unit, Some((from, to)));
+            }
+        }
+        if raw.unsigned_abs() >= self.threshold {
+            (TsUnit::Millis, None)
+        } else {
+            (TsUnit::Seconds, None)
+        }
+    }
+}
+
+/// The outcome of parsing one input.
+pub(crate) type Parsed = Result<(DateTime<Utc>, ParsedAs), EpochError>;
+
+/// A parsed instant plus the UTC offset the input stated for it, if any.
+type Zoned = (DateTime<Utc>, Option<FixedOffset>);
+
+/// Convert a numeric timestamp into a UTC DateTime, using forced or autodetected unit.
+pub(crate) fn parse_timestamp_to_utc(
+    raw: i64,
+    forced: Option<TsUnit>,
+) -> Result<(DateTime<Utc>, TsUnit), EpochError> {
+    let unit = forced.unwrap_or_else(|| Autodetect::default().unit(raw));
+
+    let per_second = unit.per_second();
+    let secs = raw.div_euclid(per_second);
+    let nanos = raw.rem_euclid(per_second) * (1_000_000_000 / per_second);
+
+    let dt = Utc
+        .timestamp_opt(secs, nanos as u32)
+        .single()
+        .ok_or(EpochError::OutOfRange)?;
+
+    Ok((dt, unit))
+}
+
+/// Read an integer written with digit separators, as copied from dashboards and docs:
+/// underscores between any two digits (`1_700_000_000`) or commas between groups of
+/// three (`1,700,000,000`), but not both. `None` for anything else.
+fn parse_grouped(input: &str) -> Option<i64> {
+    let digits = input.strip_prefix('-').unwrap_or(input);
+    let separator = match (digits.contains('_'), digits.contains(',')) {
+        (true, false) => '_',
+        (false, true) => ',',
+        _ => return None,
+    };
+    let groups: Vec<&str> = digits.split(separator).collect();
+    let well_formed = groups.iter().enumerate().all(|(i, group)| {
+        let len_ok = match separator {
+            ',' if i == 0 => (1..=3).contains(&group.len()),
+            ',' => group.len() == 3,
+            _ => !group.is_empty(),
+        };
+        len_ok && group.bytes().all(|b| b.is_ascii_digit())
+    });
+    if !well_formed {
+        return None;
+    }
+    input.replace(separator, "").parse().ok()
+}
+
+/// What `--lenient` leaves of `input` once the junk that comes along when copying values
+/// out of JSON logs and code is gone: surrounding whitespace, quotes (`"`, `'`, `` ` ``)
+/// and brackets (`[]`, `()`, `{}`), and trailing `,` or `;`, however nested. A number
+/// suffixed with `ms` or `s` loses the suffix, returned as the unit it implies.
+pub fn clean_lenient(input: &str) -> (&str, Option<TsUnit>) {
+    const PAIRS: [(char, char); 6] = [
+        ('"', '"'),
+        ('\'', '\''),
+        ('`', '`'),
+        ('[', ']'),
+        ('(', ')'),
+        ('{', '}'),
+    ];
+    let mut s = input;
+    loop {
+        let before = s;
+        s = s.trim().trim_end_matches([',', ';']);
+        for (open, close) in PAIRS {
+            if let Some(inner) = s.strip_prefix(open).and_then(|s| s.strip_suffix(close)) {
+                s = inner;
+            }
+        }
+        if s == before {
+            break;
+        }
+    }
+
+    let numeric = |s: &str| {
+        s.ends_with(|c: char| c.is_ascii_digit())
+            && s.bytes()
+                .all(|b| b.is_ascii_digit() || b"_,.+-eE".contains(&b))
+    };
+    for (suffix, unit) in [("ms", TsUnit::Millis), ("s", TsUnit::Seconds)] {
+        if let Some(number) = s.strip_suffix(suffix)
+            && numeric(number)
+        {
+            return (number, Some(unit));
+        }
+    }
+    (s, None)
+}
+
+/// Integers past this magnitude (2^53) do not all survive a trip through `f64`.
+pub const F64_EXACT_LIMIT: u64 = 1 << 53;
+
+/// Read scientific notation such as `1.7e9` or `1.7E12`, as timestamps come out of
+/// JavaScript and spreadsheets. `None` when `input` is not written that way.
+fn parse_scientific(input: &str) -> Option<Result<f64, EpochError>> {
+    let allowed = |b: u8| b.is_ascii_digit() || b"+-.eE".contains(&b);
+    if !input.contains(['e', 'E']) || !input.bytes().all(allowed) {
+        return None;
+    }
+    let value = input.parse::<f64>().ok()?;
+    // `i64::MAX as f64` rounds up to 2^63, hence the exclusive upper bound.
+    Some(if (i64::MIN as f64..i64::MAX as f64).contains(&value) {
+        Ok(value)
+    } else {
+        Err(EpochError::OutOfRange)
+    })
+}
+
+/// Convert a possibly fractional numeric timestamp into UTC: the whole part picks the
+/// unit as in [`parse_timestamp_to_utc`], and the fraction of a unit is kept to the
+/// nanosecond.
+pub(crate) fn parse_f64_timestamp_to_utc(
+    value: f64,
+    forced: Option<TsUnit>,
+) -> Result<(DateTime<Utc>, TsUnit), EpochError> {
+    // NaN is in no range, so it is out of range too.
+    if !(i64::MIN as f64..i64::MAX as f64).contains(&value) {
+        return Err(EpochError::OutOfRange);
+    }
+    let whole = value.floor();
+    let (dt, unit) = parse_timestamp_to_utc(whole as i64, forced)?;
+    let nanos_per_unit = (1_000_000_000 / unit.per_second()) as f64;
+    let nanos = ((value - whole) * nanos_per_unit).round() as i64;
+    dt.checked_add_signed(chrono::Duration::nanoseconds(nanos))
+        .map(|dt| (dt, unit))
+        .ok_or(EpochError::OutOfRange)
+}
+
+/// Whether `input` is scientific notation past [`F64_EXACT_LIMIT`], so that its last
+/// digits may have been lost before it was ever parsed.
+pub fn loses_precision(input: &str) -> bool {
+    matches!(parse_scientific(input), Some(Ok(value)) if value.abs() > F64_EXACT_LIMIT as f64)
+}
+
+/// Parse either numeric timestamp OR formatted datetime into UTC.
+///
+/// Shorthand for [`EpochParser`] with these settings.
+pub fn parse_input_to_utc(
+    input: &str,
+    input_tz: TzChoice,
+    forced_ts: Option<TsUnit>,
+    prefer_zone: &[String],
+) -> Result<(DateTime<Utc>, ParsedAs), EpochError> {
+    let parser = EpochParser {
+        input_tz,
+        unit: forced_ts,
+        prefer_zone: prefer_zone.to_vec(),
+        ..EpochParser::new()
+    };
+    parser.parse(input)
+}
+
+/// Notes on how an input was read, as [`EpochParser::parse_traced`] returns them. Off for
+/// plain parsing, where no note is even formatted.
+pub(crate) struct Trace(Option<Vec<String>>);
+
+impl Trace {
+    pub(crate) fn off() -> Self {
+        Trace(None)
+    }
+
+    pub(crate) fn on() -> Self {
+        Trace(Some(Vec::new()))
+    }
+
+    pub(crate) fn note(&mut self, note: impl FnOnce() -> String) {
+        if let Some(notes) = &mut self.0 {
+            notes.push(note());
+        }
+    }
+
+    pub(crate) fn into_notes(self) -> Vec<String> {
+        self.0.unwrap_or_default()
+    }
+}
+
+fn parse_with(input: &str, p: &EpochParser, trace: &mut Trace) -> Parsed {
+    // 0) an explicit --from encoding or --input-format replaces autodetection
+    if let Some(encoding) = p.encoding {
+        trace.note(|| format!("reading as --from {encoding:?}, autodetection off"));
+        return layouts::decode(input, encoding, p);
+    }
+    if let Some(pattern) = &p.input_format {
+        trace.note(|| format!("reading with --input-format `{pattern}`, autodetection off"));
+        return layouts::custom(input, pattern, p);
+    }
+
+    // 1) autodetection, in table order, between any custom detectors
+    #[cfg(feature = "scan")]
+    if let Some(result) = detectors::read(input, p, detectors::Position::Before, trace) {
+        return result;
+    }
+    for format in FORMATS {
+        if let Some(result) = (format.detect)(input, p, trace) {
+            trace.note(|| format!("{}: yes", format.name));
+            return result;
+        }
+        trace.note(|| format!("{}: no", format.name));
+    }
+    #[cfg(feature = "scan")]
+    if let Some(result) = detectors::read(input, p, detectors::Position::After, trace) {
+        return result;
+    }
+    Err(EpochError::UnrecognizedFormat)
+}
+
+type Detect = fn(&str, &EpochParser, &mut Trace) -> Option<Parsed>;
+
+/// An input format autodetection recognizes, as `epoch formats` lists it.
+pub struct Format {
+    /// What `-v` calls it; for layouts, also what JSON reports as `parsed_as`.
+    pub name: &'static str,
+    pub description: &'static str,
+    /// An input that this format, and no earlier one, reads.
+    pub example: &'static str,
+    detect: Detect,
+}
+
+/// Every format autodetection tries, in the order it tries them; the first that
+/// recognizes the input decides how it is read, errors included.
+pub const FORMATS: &[Format] = &[
+    Format {
+        name: "compact",
+        description: "YYYYMMDDHHMM[SS] digits, when they make a plausible date-time (--no-compact reads them as a timestamp); read in --input-tz",
+        example: "20231114221320",
+        detect: |input, p, _| layouts::compact(input, p),
+    },
+    Format {
+        name: "date",
+        description: "YYYY-MM-DD, YYYY/MM/DD or YYYYMMDD (under the same conditions as compact): midnight (or --at) in --input-tz",
+        example: "2023-11-14",
+        detect: |input, p, _| layouts::date(input, p),
+    },
+    Format {
+        name: "timestamp",
+        description: "Unix time in seconds or millis (autodetected) or the --ts unit; digit separators and scientific notation allowed",
+        example: "1700000000",
+        detect: detect_timestamp,
+    },
+    Format {
+        name: "month",
+        description: "YYYY-MM or YYYY/MM: the start of the month (or the end, with --at end) in --input-tz",
+        example: "2023-11",
+        detect: |input, p, _| layouts::month(input, p),
+    },
+    Format {
+        name: "time",
+        description: "HH:MM[:SS] or h:MM[:SS]am/pm: that time today (or on --date) in --input-tz",
+        example: "22:13:20",
+        detect: |input, p, _| layouts::time(input, p, Utc::now()),
+    },
+    Format {
+        name: "week_date",
+        description: "ISO 8601 week date, optionally with a time, read in --input-tz",
+        example: "2024-W05-3",
+        detect: |input, p, _| {
+            let naive = parse_iso_week_date(input, p.default_time.time())?;
+            Some(
+                naive
+                    .map_err(EpochError::InvalidInput)
+                    .and_then(|naive| naive_to_utc(&naive, p.input_tz))
+                    .map(|dt| (dt, ParsedAs::WeekDate)),
+            )
+        },
+    },
+    Format {
+        name: "dtg",
+        description: "military date-time group; the zone letter gives the offset",
+        example: "141300Z NOV 23",
+        detect: |input, p, _| {
+            let dtg = parse_dtg(input, p.century_pivot)?;
+            Some(
+                dtg.map_err(EpochError::InvalidInput)
+                    .and_then(|(naive, letter)| military_to_utc(&naive, letter, p.input_tz))
+                    .map(|(dt, offset)| (dt, ParsedAs::Dtg { offset })),
+            )
+        },
+    },
+    Format {
+        name: "clf",
+        description: "Apache/Nginx common log format, optionally bracketed",
+        example: "[14/Nov/2023:22:13:20 +0000]",
+        detect: |input, _, _| layouts::clf(input),
+    },
+    Format {
+        name: "rfc3339",
+        description: "RFC 3339 / RFC 5424 syslog, up to nanoseconds",
+        example: "2023-11-14T22:13:20.123Z",
+        detect: |input, _, _| layouts::rfc3339(input),
+    },
+    Format {
+        name: "iso8601_basic",
+        description: "ISO 8601 basic, without separators; read in --input-tz without an offset",
+        example: "20231114T221320Z",
+        detect: |input, p, _| layouts::iso8601_basic(input, p),
+    },
+    Format {
+        name: "http",
+        description: "HTTP date (IMF-fixdate)",
+        example: "Tue, 14 Nov 2023 22:13:20 GMT",
+        detect: |input, _, _| layouts::http(input),
+    },
+    Format {
+        name: "email",
+        description: "RFC 5322 email date, optionally after `Date:`",
+        example: "Date: Tue, 14 Nov 2023 17:13:20 -0500",
+        detect: |input, _, _| layouts::email(input),
+    },
+    Format {
+        name: "git",
+        description: "git log default, --date=iso and raw dates",
+        example: "Tue Nov 14 22:13:20 2023 +0200",
+        detect: |input, _, _| layouts::git(input),
+    },
+    Format {
+        name: "sql",
+        description: "MySQL/Postgres datetime, read in --input-tz without an offset",
+        example: "2023-11-14 22:13:20.123+02",
+        detect: |input, p, _| layouts::sql(input, p),
+    },
+    Format {
+        name: "journal",
+        description: "journald export field (microseconds)",
+        example: "__REALTIME_TIMESTAMP=1700000000123456",
+        detect: |input, _, _| layouts::journal(input),
+    },
+    Format {
+        name: "syslog",
+        description: "RFC 3164 syslog; the year comes from --syslog-year",
+        example: "Nov 14 22:13:20",
+        detect: |input, p, _| layouts::syslog(input, p, Utc::now()),
+    },
+    Format {
+        name: "formatted_zone",
+        description: "YYYY/MM/DD HH:MM:SS with a zone abbreviation, offset or military letter",
+        example: "2023/11/14 17:13:20 EST",
+        detect: |input, p, _| {
+            let result = parse_formatted_with_zone(input, p.input_tz, &p.prefer_zone)?;
+            Some(result.map(|(dt, offset)| (dt, ParsedAs::Formatted { offset })))
+        },
+    },
+    Format {
+        name: "formatted",
+        description: "YYYY/MM/DD HH:MM:SS, read in --input-tz",
+        example: "2023/11/14 22:13:20",
+        detect: |input, p, _| {
+            let naive = NaiveDateTime::parse_from_str(input, FORMATTED).ok()?;
+            Some(
+                naive_to_utc(&naive, p.input_tz)
+                    .map(|dt| (dt, ParsedAs::Formatted { offset: None })),
+            )
+        },
+    },
+    Format {
+        name: "expression",
+        description: "an instant plus or minus durations; operands are any other format (not in `scan`)",
+        example: "now - 90m",
+        detect: detect_expression,
+    },
+];
+
+/// Numeric input: plain, with digit separators (1_700_000_000, 1,700,000,000) or in
+/// scientific notation (1.7e9).
+fn detect_timestamp(input: &str, p: &EpochParser, trace: &mut Trace) -> Option<Parsed> {
+    // Scientific notation may leave a fraction of a unit on top of `raw`.
+    let (raw, fractional) = match input.parse::<i64>() {
+        Ok(raw) => (raw, None),
+        Err(_) => match parse_grouped(input) {
+            Some(raw) => (raw, None),
+            None => match parse_scientific(input)? {
+                Ok(value) => (value.floor() as i64, Some(value)),
+                Err(e) => return Some(Err(e)),
+            },
+        },
+    };
+    let unit = match p.unit {
+        Some(unit) => {
+            trace.note(|| format!("unit: {} (forced)", unit.name()));
+            unit
+        }
+        None => {
+            let (unit, window) = p.autodetect.decide(raw);
+            trace.note(|| match window {
+                Some((from, to)) => {
+                    format!(
+                        "unit: {} (the only one landing in {from}..{to})",
+                        unit.name()
+                    )
+                }
+                None => format!(
+                    "unit: {} (|{raw}| is {} the detect threshold {})",
+                    unit.name(),
+                    if unit == TsUnit::Millis {
+                        "at or above"
+                    } else {
+                        "below"
+                    },
+                    p.autodetect.threshold
+                ),
+            });
+            unit
+        }
+    };
+    let converted = match fractional {
+        Some(value) => parse_f64_timestamp_to_utc(value, Some(unit)),
+        None => parse_timestamp_to_utc(raw, Some(unit)),
+    };
+    Some(converted.map(|(dt, unit)| (dt, ParsedAs::Timestamp { unit, raw })))
+}
+
+/// `now - 90m`, `(2025/01/01 00:00:00) + 6w`: operands are parsed with `p` itself.
+fn detect_expression(input: &str, p: &EpochParser, trace: &mut Trace) -> Option<Parsed> {
+    if p.strict {
+        trace.note(|| "expression: not tried (strict)".to_string());
+        return None;
+    }
+    let resolve = |s: &str| p.parse(s).map(|(dt, _)| dt);
+    match expr::evaluate(input, Utc::now(), &resolve) {
+        Ok(dt) => Some(Ok((dt, ParsedAs::Expression))),
+        Err(ExprError::NotAnExpression) => None,
+        Err(ExprError::Syntax(msg)) => Some(Err(EpochError::InvalidExpression(msg))),
+        Err(ExprError::Input(e)) => Some(Err(e)),
+    }
+}
+
+/// Parse a formatted datetime followed by a zone designator: an abbreviation (`EST`),
+/// a numeric offset (`+05:30`, `-0700`, `+05`) or a military letter (`Z`).
+///
+/// The designator takes precedence over `--input-tz`. Alongside the instant, returns
+/// the offset the designator stood for (`None` for `J`, which means local time).
+fn parse_formatted_with_zone(
+    input: &str,
+    input_tz: TzChoice,
+    prefer_zone: &[String],
+) -> Option<Result<Zoned, EpochError>> {
+    let naive_before = |s: &str| NaiveDateTime::parse_from_str(s.trim_end(), FORMATTED).ok();
+
+    if let Some((datetime, abbr)) = input.rsplit_once(' ')
+        && offset::looks_like_abbreviation(abbr)
+        && let Some(naive) = naive_before(datetime)
+    {
+        return Some(
+            offset::resolve_abbreviation(abbr, prefer_zone)
+                .map(|off| (fixed_to_utc(&naive, off), Some(off))),
+        );
+    }
+
+    if let Some(i) = input.rfind(['+', '-'])
+        && let Some(off) = offset::parse_numeric_offset(&input[i..])
+        && let Some(naive) = naive_before(&input[..i])
+    {
+        return Some(Ok((fixed_to_utc(&naive, off), Some(off))));
+    }
+
+    if let Some(letter) = input.chars().last().filter(char::is_ascii_alphabetic)
+        && let Some(naive) = naive_before(&input[..input.len() - 1])
+    {
+        return Some(military_to_utc(&naive, letter, input_tz));
+    }
+
+    None
+}
+
+/// Interpret a wall-clock datetime observed at a fixed UTC offset.
+fn fixed_to_utc(naive: &NaiveDateTime, off: FixedOffset) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&(*naive - off))
+}
+
+/// Interpret a wall-clock datetime in the input timezone.
+pub fn naive_to_utc(
+    naive: &NaiveDateTime,
+    input_tz: TzChoice,
+) -> Result<DateTime<Utc>, EpochError> {
+    let utc_dt = match input_tz {
+        TzChoice::Utc => Utc.from_utc_datetime(naive),
+        TzChoice::Fixed(off) => fixed_to_utc(naive, off),
+        #[cfg(feature = "tz")]
+        TzChoice::Named(tz) | TzChoice::PinnedLocal(tz) => tz
+            .from_local_datetime(naive)
+            .single()
+            .ok_or_else(|| EpochError::AmbiguousLocalTime {
+                zone: Some(tz.name().to_string()),
+            })?
+            .with_timezone(&Utc),
+        TzChoice::Local => {
+            let local_dt = Local
+                .from_local_datetime(naive)
+                .single()
+                .ok_or(EpochError::AmbiguousLocalTime { zone: None })?;
+            local_dt.with_timezone(&Utc)
+        }
+    };
+
+    Ok(utc_dt)
+}
+
+/// Interpret a wall-clock datetime tagged with a military zone letter.
+/// `J` means local time, so it defers to the input timezone.
+fn military_to_utc(
+    naive: &NaiveDateTime,
+    letter: char,
+    input_tz: TzChoice,
+) -> Result<Zoned, EpochError> {
+    if letter.eq_ignore_ascii_case(&'J') {
+        return naive_to_utc(naive, input_tz).map(|dt| (dt, None));
+    }
+
+    let off = offset::military_offset(letter)
+        .ok_or_else(|| EpochError::UnknownTimezone(letter.to_string()))?;
+    Ok((fixed_to_utc(naive, off), Some(off)))
+}
+
+/// Parse a military date-time group (DTG): `DDHHMM<zone letter> MON YY`, as in
+/// `141300Z NOV 23`. Spaces are optional and the year may have two or four digits;
+/// two-digit years are placed by `pivot` (see [`CENTURY_PIVOT`]).
+///
+/// Returns `None` when the input does not look like a DTG.
+fn parse_dtg(input: &str, pivot: u8) -> Option<Result<(NaiveDateTime, char), String>> {
+    let compact: String = input.split_whitespace().collect();
+    let b = compact.as_bytes();
+    if !(12..=14).contains(&b.len())
+        || !b[..6].iter().all(u8::is_ascii_digit)
+        || !b[6..10].iter().all(u8::is_ascii_alphabetic)
+        || !b[10..].iter().all(u8::is_ascii_digit)
+    {
+        return None;
+    }
+
+    let letter = b[6] as char;
+    let year = match &compact[10..] {
+        yy if yy.len() == 2 => expand_year(yy.parse().ok()?, pivot),
+        yyyy if yyyy.len() == 4 => yyyy.parse().ok()?,
+        _ => return None,
+    };
+
+    let text = format!(
+        "{} {} {year} {}",
+        &compact[..2],
+        &compact[7..10],
+        &compact[2..6]
+    );
+    Some(
+        NaiveDateTime::parse_from_str(&text, "%d %b %Y %H%M")
+            .map(|naive| (naive, letter))
+            .map_err(|_| format!("Invalid date-time group: {input}")),
+    )
+}
+
+/// Parse an ISO 8601 week date: extended (`2024-W05-3`) or basic (`2024W053`).
+///
+/// The weekday may be omitted (`2024-W05` means that week's Monday), and a time may be
+/// attached after `T` or a space (`HH:MM[:SS]`, or `HHMM[SS]` for the basic form).
+/// Without a time, `default_time` is assumed.
+///
+/// Returns `None` when the input does not look like a week date at all, so the caller
+/// can fall through to other formats.
+fn parse_iso_week_date(
+    input: &str,
+    default_time: NaiveTime,
+) -> Option<Result<NaiveDateTime, String>> {
+    let (date, time) = match input.find(['T', ' ']) {
+        Some(i) => (&input[..i], Some(&input[i + 1..])),
+        None => (input, None),
+    };
+
+    let (year, rest) = date.split_once('W')?;
+    let (year, extended) = match year.strip_suffix('-') {
+        Some(y) => (y, true),
+        None => (year, false),
+    };
+    if year.len() != 4 || !year.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let (week, day) = match (extended, rest.len()) {
+        (true, 2) => (rest, None),
+        (true, 4) if rest.as_bytes()[2] == b'-' => (&rest[..2], Some(&rest[3..])),
+        (false, 2) => (rest, None),
+        (false, 3) => (&rest[..2], Some(&rest[2..])),
+        _ => return None,
+    };
+    if !week
+        .bytes()
+        .chain(day.unwrap_or("").bytes())
+        .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let invalid = || format!("Invalid ISO week date: {input}");
+    let year: i32 = year.parse().ok()?;
+    let week: u32 = week.parse().ok()?;
+    let weekday = match day.map(str::parse::<u8>) {
+        None => Weekday::Mon,
+        Some(Ok(d @ 1..=7)) => Weekday::try_from(d - 1).ok()?,
+        Some(_) => return Some(Err(invalid())),
+    };
+
+    let Some(date) = NaiveDate::from_isoywd_opt(year, week, weekday) else {
+        return Some(Err(invalid()));
+    };
+
+    let time = match time {
+        None => default_time,
+        Some(t) => {
+            let layouts: &[&str] = if extended {
+                &["%H:%M:%S", "%H:%M"]
+            } else {
+                &["%H%M%S", "%H%M"]
+            };
+            match layouts
+                .iter()
+                .find_map(|l| NaiveTime::parse_from_str(t, l).ok())
+            {
+                Some(t) => t,
+                None => return Some(Err(invalid())),
+            }
+        }
+    };
+
+    Some(Ok(date.and_time(time)))
+}
+
+/// Render an instant in `output_tz`: RFC 3339, or `fmt` (strftime) when given. Use a
+/// [`Formatter`] to render many instants with the same pattern.
+///
+/// Panics if `fmt` is not a valid strftime pattern, like [`Formatter::strftime`]; check
+/// untrusted patterns with [`check_strftime`] first.
+pub fn format_output(utc_dt: DateTime<Utc>, output_tz: TzChoice, fmt: Option<&str>) -> String {
+    let formatter = Formatter::new().output_tz(output_tz);
+    match fmt {
+        Some(f) => formatter.strftime(f),
+        None => formatter,
+    }
+    .format(utc_dt)
+}
+
+/// The JSON result for a parsed instant (schema_version 1).
+#[cfg(feature = "json")]
+pub fn instant_json(
+    input: &str,
+    utc_dt: DateTime<Utc>,
+    parsed_as: &ParsedAs,
+    input_tz: TzChoice,
+    output_tz: TzChoice,
+    fiscal_start: Option<u32>,
+) -> serde_json::Value {
+    let ts_unit_str = match parsed_as {
+        ParsedAs::Timestamp { unit, .. } => Some(unit.name()),
+        _ => None,
+    };
+
+    let mut obj = json!({
+        "schema_version": 1,
+        "input": input,
+        "parsed_as": parsed_as.name(),
+        "ts_unit": ts_unit_str,
+        "input_tz": input_tz.to_string(),
+        "source_offset": parsed_as.source_offset().map(|o| o.to_string()),
+        "output_tz": output_tz.to_string(),
+        "local_tz": zone::local_zone_name(),
+        "unix_seconds": utc_dt.timestamp(),
+        "unix_millis": utc_dt.timestamp_millis(),
+        // Null outside 1677-2262, where nanoseconds overflow an i64.
+        "unix_nanos": utc_dt.timestamp_nanos_opt(),
+        "rfc3339": format_output(utc_dt, output_tz, None)
+    });
+
+    if let Some(start) = fiscal_start {
+        let fy = fiscal::fiscal_date(output_tz.naive_local(utc_dt).date(), start);
+        obj["fiscal_year"] = json!(fy.year);
+        obj["fiscal_quarter"] = json!(fy.quarter);
+        obj["fiscal_period"] = json!(fy.period);
+    }
+    obj
+}
+
+/// The JSON result for a parsed instant in schema_version 2: everything in
+/// [`instant_json`] plus sub-second precision, UTC offsets in seconds, the `output_tz`
+/// calendar position (ISO week, weekday, day of year, quarter) and the raw numeric input.
+#[cfg(feature = "json")]
+pub fn instant_json_v2(
+    input: &str,
+    utc_dt: DateTime<Utc>,
+    parsed_as: &ParsedAs,
+    input_tz: TzChoice,
+    output_tz: TzChoice,
+    fiscal_start: Option<u32>,
+) -> serde_json::Value {
+    let mut obj = instant_json(input, utc_dt, parsed_as, input_tz, output_tz, fiscal_start);
+    let local = output_tz.naive_local(utc_dt);
+    let offset_secs = (local - utc_dt.naive_utc()).num_seconds() as i32;
+    let output_offset = FixedOffset::east_opt(offset_secs).expect("zone offsets are under a day");
+    let week = local.iso_week();
+
+    obj["schema_version"] = json!(2);
+    obj["unix_micros"] = json!(utc_dt.timestamp_micros());
+    obj["nanos"] = json!(utc_dt.timestamp_subsec_nanos());
+    obj["ts_raw"] = json!(match parsed_as {
+        ParsedAs::Timestamp { raw, .. } => Some(*raw),
+        _ => None,
+    });
+    obj["source_offset_seconds"] = json!(parsed_as.source_offset().map(|o| o.local_minus_utc()));
+    obj["output_offset"] = json!(output_offset.to_string());
+    obj["output_offset_seconds"] = json!(offset_secs);
+    obj["iso_year"] = json!(week.year());
+    obj["iso_week"] = json!(week.week());
+    obj["weekday"] = json!(local.format("%A").to_string());
+    obj["iso_weekday"] = json!(local.weekday().number_from_monday());
+    obj["day_of_year"] = json!(local.ordinal());
+    obj["quarter"] = json!(local.month0() / 3 + 1);
+    obj["leap_year"] = json!(local.date().leap_year());
+    obj
+}
+
+/// The JSON result for a parsed instant in the given layout.
+#[cfg(feature = "json")]
+pub fn versioned_json(
+    version: SchemaVersion,
+    input: &str,
+    utc_dt: DateTime<Utc>,
+    parsed_as: &ParsedAs,
+    input_tz: TzChoice,
+    output_tz: TzChoice,
+    fiscal_start: Option<u32>,
+) -> serde_json::Value {
+    let build = match version {
+        SchemaVersion::V1 => instant_json,
+        SchemaVersion::V2 => instant_json_v2,
+    };
+    build(input, utc_dt, parsed_as, input_tz, output_tz, fiscal_start)
+}
+
+/// A JSON Schema (draft 2020-12) for [`versioned_json`] results, so consumers of `--json`
+/// and batch NDJSON output can validate them or generate types from them.
+#[cfg(feature = "json")]
+pub fn instant_json_schema(version: SchemaVersion) -> serde_json::Value {
+    let string = |description: &str| json!({ "type": "string", "description": description });
+    let nullable =
+        |ty: &str, description: &str| json!({ "type": [ty, "null"], "description": description });
+    let integer = |description: &str| json!({ "type": "integer", "description": description });
+
+    let mut schema = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "epoch result",
+        "description": "One converted instant: the --json output, and each line of batch \
+                        (NDJSON) output with --json.",
+        "type": "object",
+        "properties": {
+            "schema_version": {
+                "const": 1,
+                "description": "Version of this layout; fields are only added within a version."
+            },
+            "input": string("The input as given."),
+            "parsed_as": string(
+                "How the input was read: timestamp, formatted, week_date, dtg, expression, or \
+                 a layout name such as rfc3339 or clf."
+            ),
+            "ts_unit": {
+                "enum": ["seconds", "millis", "micros", "nanos", null],
+                "description": "Unit of a numeric input; null for other inputs."
+            },
+            "input_tz": string("Zone used for input without an offset (--input-tz)."),
+            "source_offset": nullable(
+                "string",
+                "UTC offset stated by the input itself, e.g. +02:00."
+            ),
+            "output_tz": string("Zone used for rfc3339 and fiscal fields (--output-tz)."),
+            "local_tz": nullable("string", "IANA name of the system zone, if known."),
+            "unix_seconds": integer("Seconds since 1970-01-01T00:00:00Z."),
+            "unix_millis": integer("Milliseconds since 1970-01-01T00:00:00Z."),
+            "unix_nanos": nullable(
+                "integer",
+                "Nanoseconds since 1970-01-01T00:00:00Z; null outside 1677-2262."
+            ),
+            "rfc3339": string("The instant in RFC 3339, in output_tz."),
+            "fiscal_year": integer("Fiscal year it falls in; only with --fiscal-start."),
+            "fiscal_quarter": {
+                "type": "integer",
+                "minimum": 1,
+                "maximum": 4,
+                "description": "Fiscal quarter; only with --fiscal-start."
+            },
+            "fiscal_period": {
+                "type": "integer",
+                "minimum": 1,
+                "maximum": 12,
+                "description": "Fiscal month; only with --fiscal-start."
+            },
+            "week": {
+                "type": "integer",
+                "minimum": 1,
+                "maximum": 54,
+                "description": "Week of the year in output_tz, counted from the week holding \
+                                January 1; only with --week-start."
+            },
+            "week_start": {
+                "enum": ["mon", "tue", "wed", "thu", "fri", "sat", "sun"],
+                "description": "Day the week field's weeks start on; only with --week-start."
+            },
+            "clamped": {
+                "type": "boolean",
+                "description": "Whether the instant was moved into the --clamp-min / \
+                                --clamp-max window; only with either."
+            }
+        },
+        "required": [
+            "schema_version", "input", "parsed_as", "ts_unit", "input_tz", "source_offset",
+            "output_tz", "local_tz", "unix_seconds", "unix_millis", "unix_nanos", "rfc3339"
+        ]
+    });
+    if version == SchemaVersion::V1 {
+        return schema;
+    }
+
+    let v2 = json!({
+        "unix_micros": integer("Microseconds since 1970-01-01T00:00:00Z."),
+        "nanos": {
+            "type": "integer",
+            "minimum": 0,
+            "maximum": 1_999_999_999,
+            "description": "Nanoseconds past unix_seconds (over 999999999 only in a leap second)."
+        },
+        "ts_raw": nullable("integer", "The number a numeric input held, before units."),
+        "source_offset_seconds": nullable("integer", "source_offset in seconds east of UTC."),
+        "output_offset": string("UTC offset of output_tz at the instant, e.g. +05:30."),
+        "output_offset_seconds": integer("output_offset in seconds east of UTC."),
+        "iso_year": integer("ISO 8601 week-numbering year, in output_tz."),
+        "iso_week": {
+            "type": "integer",
+            "minimum": 1,
+            "maximum": 53,
+            "description": "ISO 8601 week, in output_tz."
+        },
+        "weekday": string("English weekday name, in output_tz, e.g. Tuesday."),
+        "iso_weekday": {
+            "type": "integer",
+            "minimum": 1,
+            "maximum": 7,
+            "description": "ISO weekday (Monday is 1), in output_tz."
+        },
+        "day_of_year": {
+            "type": "integer",
+            "minimum": 1,
+            "maximum": 366,
+            "description": "Day of the year, in output_tz."
+        },
+        "quarter": {
+            "type": "integer",
+            "minimum": 1,
+            "maximum": 4,
+            "description": "Calendar quarter, in output_tz."
+        },
+        "leap_year": {
+            "type": "boolean",
+            "description": "Whether the year, in output_tz, has 366 days."
+        }
+    });
+    schema["properties"]["schema_version"]["const"] = json!(2);
+    for (field, property) in v2.as_object().expect("v2 is an object") {
+        schema["properties"][field] = property.clone();
+        schema["required"]
+            .as_array_mut()
+            .expect("required is an array")
+            .push(json!(field));
+    }
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn parses_seconds_timestamp() {
+        let (dt, parsed_as) = parse_input_to_utc("1700000000", TzChoice::Utc, None, &[]).unwrap();
+        assert_eq!(dt.timestamp(), 1_700_000_000);
+
+        match parsed_as {
+            ParsedAs::Timestamp { unit, raw } => {
+                assert_eq!(raw, 1_700_000_000);
+                assert!(matches!(unit, TsUnit::Seconds));
+            }
+            _ => panic!("expected timestamp parse"),
+        }
+    }
+
+    #[test]
+    fn parses_millis_timestamp_autodetect() {
+        let (dt, parsed_as) =
+            parse_input_to_utc("1700000000123", TzChoice::Utc, None, &[]).unwrap();
+        assert_eq!(dt.timestamp(), 1_700_000_000);
+        assert_eq!(dt.timestamp_millis(), 1_700_000_000_123);
+
+        match parsed_as {
+            ParsedAs::Timestamp { unit, .. } => assert!(matches!(unit, TsUnit::Millis)),
+            _ => panic!("expected timestamp parse"),
+        }
+    }
+
+    #[test]
+    fn parses_millis_timestamp_forced() {
+        let (dt, parsed_as) =
+            parse_input_to_utc("1700000000", TzChoice::Utc, Some(TsUnit::Millis), &[]).unwrap();
+        assert_eq!(dt.timestamp_millis(), 1_700_000_000);
+
+        match parsed_as {
+            ParsedAs::Timestamp { unit, .. } => assert!(matches!(unit, TsUnit::Millis)),
+            _ => panic!("expected timestamp parse"),
+        }
+    }
+
+    #[test]
+    fn parses_formatted_datetime_as_utc_when_input_tz_utc() {
+        let (dt, parsed_as) =
+            parse_input_to_utc("2025/12/20 11:10:11", TzChoice::Utc, None, &[]).unwrap();
+
+        let expected = Utc.with_ymd_and_hms(2025, 12, 20, 11, 10, 11).unwrap();
+        assert_eq!(dt, expected);
+
+        assert!(matches!(parsed_as, ParsedAs::Formatted { .. }));
+    }
+
+    #[test]
+    fn rejects_unknown_format() {
+        let err = parse_input_to_utc("20.12.2025 11:10:11", TzChoice::Utc, None, &[]).unwrap_err();
+        assert_eq!(err, EpochError::UnrecognizedFormat);
+    }
+
+    #[test]
+    fn parses_iso_week_date_extended_and_basic() {
+        let expected = Utc.with_ymd_and_hms(2024, 1, 31, 0, 0, 0).unwrap();
+
+        for input in ["2024-W05-3", "2024W053"] {
+            let (dt, parsed_as) = parse_input_to_utc(input, TzChoice::Utc, None, &[]).unwrap();
+            assert_eq!(dt, expected, "{input}");
+            assert!(matches!(parsed_as, ParsedAs::WeekDate));
+        }
+    }
+
+    #[test]
+    fn parses_iso_week_date_with_time_and_week_only() {
+        let (dt, _) = parse_input_to_utc("2024-W05-3T10:20:30", TzChoice::Utc, None, &[]).unwrap();
+        assert_eq!(dt, Utc.with_ymd_and_hms(2024, 1, 31, 10, 20, 30).unwrap());
+
+        let (dt, _) = parse_input_to_utc("2024W053T1020", TzChoice::Utc, None, &[]).unwrap();
+        assert_eq!(dt, Utc.with_ymd_and_hms(2024, 1, 31, 10, 20, 0).unwrap());
+
+        // ISO week 1 of 2025 starts in December 2024
+        let (dt, _) = parse_input_to_utc("2025-W01", TzChoice::Utc, None, &[]).unwrap();
+        assert_eq!(dt, Utc.with_ymd_and_hms(2024, 12, 30, 0, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn rejects_invalid_iso_week_date() {
+        for input in ["2024-W54-1", "2024-W05-8", "2024-W05-3T25:00"] {
+            let err = parse_input_to_utc(input, TzChoice::Utc, None, &[]).unwrap_err();
+            assert!(matches!(err, EpochError::InvalidInput(_)), "{input}");
+            assert!(err.to_string().contains("ISO week date"), "{input}: {err}");
+        }
+    }
+
+    #[test]
+    fn parses_formatted_datetime_with_zone_abbreviation() {
+        let (dt, parsed_as) =
+            parse_input_to_utc("2023/11/14 17:13:20 EST", TzChoice::Local, None, &[]).unwrap();
+        assert_eq!(dt.timestamp(), 1_700_000_000);
+        assert!(matches!(parsed_as, ParsedAs::Formatted { .. }));
+
+        let prefer = vec!["cn".to_string()];
+        let (dt, _) =
+            parse_input_to_utc("2023/11/15 06:13:20 CST", TzChoice::Local, None, &prefer).unwrap();
+        assert_eq!(dt.timestamp(), 1_700_000_000);
+    }
+
+    #[test]
+    fn ambiguous_zone_abbreviation_is_tz_error() {
+        let err =
+            parse_input_to_utc("2023/11/14 22:13:20 CST", TzChoice::Utc, None, &[]).unwrap_err();
+        assert!(matches!(err, EpochError::AmbiguousAbbreviation { .. }));
+    }
+
+    #[test]
+    fn parses_formatted_datetime_with_military_letter() {
+        let (dt, _) =
+            parse_input_to_utc("2023/11/14 22:13:20Z", TzChoice::Local, None, &[]).unwrap();
+        assert_eq!(dt.timestamp(), 1_700_000_000);
+
+        // Bravo is UTC+2
+        let (dt, _) =
+            parse_input_to_utc("2023/11/15 00:13:20 B", TzChoice::Local, None, &[]).unwrap();
+        assert_eq!(dt.timestamp(), 1_700_000_000);
+
+        // Juliet defers to the input timezone
+        let (dt, _) = parse_input_to_utc("2023/11/14 22:13:20J", TzChoice::Utc, None, &[]).unwrap();
+        assert_eq!(dt.timestamp(), 1_700_000_000);
+    }
+
+    #[test]
+    fn parses_formatted_datetime_with_numeric_offset() {
+        for input in [
+            "2023/11/15 03:43:20 +05:30",
+            "2023/11/15 03:43:20 +0530",
+            "2023/11/15 03:43:20+05:30",
+        ] {
+            let (dt, parsed_as) = parse_input_to_utc(input, TzChoice::Local, None, &[]).unwrap();
+            assert_eq!(dt.timestamp(), 1_700_000_000, "{input}");
+            assert_eq!(
+                parsed_as.source_offset(),
+                FixedOffset::east_opt(5 * 3600 + 30 * 60),
+                "{input}"
+            );
+        }
+
+        let (dt, _) =
+            parse_input_to_utc("2023/11/14 15:13:20 -07", TzChoice::Local, None, &[]).unwrap();
+        assert_eq!(dt.timestamp(), 1_700_000_000);
+    }
+
+    #[test]
+    fn parses_dtg() {
+        let expected = Utc.with_ymd_and_hms(2023, 11, 14, 13, 0, 0).unwrap();
+
+        for input in ["141300Z NOV 23", "141300ZNOV23", "141300z nov 2023"] {
+            let (dt, parsed_as) = parse_input_to_utc(input, TzChoice::Local, None, &[]).unwrap();
+            assert_eq!(dt, expected, "{input}");
+            assert!(matches!(parsed_as, ParsedAs::Dtg { .. }));
+        }
+
+        // Quebec is UTC-4
+        let (dt, _) = parse_input_to_utc("140900Q NOV 23", TzChoice::Local, None, &[]).unwrap();
+        assert_eq!(dt, expected);
+    }
+
+    #[test]
+    fn century_pivot_places_two_digit_years() {
+        assert_eq!(expand_year(69, CENTURY_PIVOT), 2069);
+        assert_eq!(expand_year(70, CENTURY_PIVOT), 1970);
+        assert_eq!(expand_year(50, 50), 1950);
+
+        let p = EpochParser::new().input_tz(TzChoice::Utc).century_pivot(20);
+        let (dt, _) = p.parse("141300Z NOV 23").unwrap();
+        assert_eq!(dt.to_rfc3339(), "1923-11-14T13:00:00+00:00");
+    }
+
+    #[test]
+    fn forced_high_precision_units() {
+        let parse = |raw, unit| parse_timestamp_to_utc(raw, Some(unit)).unwrap().0;
+        let dt = parse(1_700_000_000_123_456, TsUnit::Micros);
+        assert_eq!(dt.timestamp_micros(), 1_700_000_000_123_456);
+        let dt = parse(1_700_000_000_123_456_789, TsUnit::Nanos);
+        assert_eq!(dt.timestamp_nanos_opt(), Some(1_700_000_000_123_456_789));
+        // Negative values count back from the epoch, fraction included.
+        assert_eq!(parse(-1_500, TsUnit::Millis).timestamp_millis(), -1_500);
+    }
+
+    #[test]
+    fn digit_separators() {
+        let raw = |s| match parse_input_to_utc(s, TzChoice::Utc, None, &[]) {
+            Ok((_, ParsedAs::Timestamp { raw, .. })) => Some(raw),
+            _ => None,
+        };
+        assert_eq!(raw("1_700_000_000"), Some(1_700_000_000));
+        assert_eq!(raw("1,700,000,000"), Some(1_700_000_000));
+        assert_eq!(raw("-1,000"), Some(-1_000));
+        assert_eq!(raw("17_00_000_000_123"), Some(1_700_000_000_123));
+        for malformed in ["1,70,000", "1,700_000", "_1700", "1700_", "1__700", ",700"] {
+            assert_eq!(parse_grouped(malformed), None, "{malformed}");
+        }
+    }
+
+    #[test]
+    fn lenient_cleanup() {
+        assert_eq!(clean_lenient(r#" "1700000000", "#), ("1700000000", None));
+        assert_eq!(
+            clean_lenient("[`2023/11/14 22:13:20`];"),
+            ("2023/11/14 22:13:20", None)
+        );
+        assert_eq!(
+            clean_lenient("{'1700000000000ms'}"),
+            ("1700000000000", Some(TsUnit::Millis))
+        );
+        assert_eq!(clean_lenient("(1.7e9s),"), ("1.7e9", Some(TsUnit::Seconds)));
+        // Only numbers lose a unit suffix.
+        assert_eq!(clean_lenient("now - 90ms"), ("now - 90ms", None));
+        assert_eq!(clean_lenient("\"unbalanced"), ("\"unbalanced", None));
+
+        let p = EpochParser::new().input_tz(TzChoice::Utc).lenient(true);
+        let (dt, parsed_as) = p.parse("\"1700000000ms\",").unwrap();
+        assert_eq!(dt.timestamp_millis(), 1_700_000_000);
+        assert!(matches!(
+            parsed_as,
+            ParsedAs::Timestamp {
+                unit: TsUnit::Millis,
+                ..
+            }
+        ));
+        assert!(EpochParser::new().parse("\"1700000000\"").is_err());
+    }
+
+    #[test]
+    fn each_format_example_is_read_by_that_format() {
+        let p = EpochParser::new().input_tz(TzChoice::Utc);
+        for (i, format) in FORMATS.iter().enumerate() {
+            let first = FORMATS
+                .iter()
+                .position(|f| (f.detect)(format.example, &p, &mut Trace::off()).is_some());
+            assert_eq!(first, Some(i), "{}", format.name);
+            assert!(p.parse(format.example).is_ok(), "{}", format.name);
+        }
+    }
+
+    #[test]
+    fn traces_the_detection_steps() {
+        let p = EpochParser::new().input_tz(TzChoice::Utc);
+        let (result, notes) = p.parse_traced("2023/11/14 22:13:20");
+        assert!(result.is_ok());
+        assert_eq!(notes.first().map(String::as_str), Some("compact: no"));
+        assert!(notes.contains(&"formatted: yes".to_string()));
+        assert_eq!(
+            notes.last().map(String::as_str),
+            Some("zone: UTC (--input-tz)")
+        );
+
+        let (_, notes) = p.parse_traced("1700000000000");
+        let unit = notes.iter().position(|n| n.starts_with("unit: ")).unwrap();
+        assert!(notes[..unit].iter().all(|n| n.ends_with(": no")));
+        assert_eq!(
+            notes[unit],
+            "unit: millis (|1700000000000| is at or above the detect threshold 1000000000000)"
+        );
+        let (_, notes) = p.clone().strict(true).parse_traced("now");
+        assert!(notes.contains(&"expression: not tried (strict)".to_string()));
+    }
+
+    #[test]
+    fn scientific_notation() {
+        let parse = |s| parse_input_to_utc(s, TzChoice::Utc, None, &[]).unwrap();
+        let (dt, parsed_as) = parse("1.7e9");
+        assert_eq!(dt.timestamp(), 1_700_000_000);
+        assert!(matches!(
+            parsed_as,
+            ParsedAs::Timestamp {
+                unit: TsUnit::Seconds,
+                raw: 1_700_000_000
+            }
+        ));
+        assert_eq!(parse("1.7E12").0.timestamp_millis(), 1_700_000_000_000);
+        assert_eq!(parse("-1e3").0.timestamp(), -1_000);
+        // Fractions of a unit survive rather than rounding to the nearest whole one.
+        assert_eq!(
+            parse("1.7000000005e9").0.to_rfc3339(),
+            "2023-11-14T22:13:20.500+00:00"
+        );
+        assert_eq!(parse("1.5e0").0.timestamp_millis(), 1_500);
+        assert_eq!(parse("-1.5e0").0.timestamp_millis(), -1_500);
+        assert_eq!(
+            parse("1.7000000000005e12").0.timestamp_micros(),
+            1_700_000_000_000_500
+        );
+        assert!(matches!(
+            parse_input_to_utc("1e300", TzChoice::Utc, None, &[]),
+            Err(EpochError::OutOfRange)
+        ));
+
+        assert!(!loses_precision("1.7e12"));
+        assert!(loses_precision("1.7e18"));
+        assert!(!loses_precision("17000000000000000000"));
+    }
+
+    #[test]
+    fn autodetect_threshold_and_window() {
+        let default = Autodetect::default();
+        assert_eq!(default.unit(1_700_000_000), TsUnit::Seconds);
+        assert_eq!(default.unit(1_700_000_000_000), TsUnit::Millis);
+        // Millis from 1990 fall under the default threshold.
+        assert_eq!(default.unit(631_152_000_000), TsUnit::Seconds);
+
+        let lowered = Autodetect {
+            threshold: 100_000_000_000,
+            ..default
+        };
+        assert_eq!(lowered.unit(631_152_000_000), TsUnit::Millis);
+
+        let windowed = Autodetect {
+            window: Some((1980, 2100)),
+            ..default
+        };
+        assert_eq!(windowed.unit(631_152_000_000), TsUnit::Millis);
+        // As seconds this is the year 17814; as millis it is in 1985.
+        assert_eq!(windowed.unit(500_000_000_000), TsUnit::Millis);
+        assert_eq!(windowed.unit(1_700_000_000_000_000), TsUnit::Micros);
+
+        // As seconds and as millis (1970-01-20) both fit: the threshold decides.
+        let wide = Autodetect {
+            window: Some((1970, 2100)),
+            ..default
+        };
+        assert_eq!(wide.unit(1_700_000_000), TsUnit::Seconds);
+    }
+
+    #[test]
+    fn rejects_invalid_dtg() {
+        let err = parse_input_to_utc("311300Z NOV 23", TzChoice::Utc, None, &[]).unwrap_err();
+        assert!(matches!(err, EpochError::InvalidInput(_)));
+    }
+
+    #[test]
+    fn formats_default_rfc3339_utc() {
+        let dt = Utc.with_ymd_and_hms(2025, 12, 20, 11, 10, 11).unwrap();
+        let out = format_output(dt, TzChoice::Utc, None);
+        assert!(out.starts_with("2025-12-20T11:10:11"));
+    }
+
+    #[test]
+    fn formats_fixed_offset_output() {
+        let dt = Utc.with_ymd_and_hms(2023, 11, 14, 22, 13, 20).unwrap();
+        let tz = TzChoice::Fixed(FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap());
+        assert_eq!(format_output(dt, tz, None), "2023-11-15T03:43:20+05:30");
+        assert_eq!(format_output(dt, tz, Some("%H:%M %z")), "03:43 +0530");
+    }
+
+    #[cfg(feature = "tz")]
+    #[test]
+    fn formats_named_zone_output() {
+        let dt = Utc.with_ymd_and_hms(2023, 7, 1, 12, 0, 0).unwrap();
+        let tz: TzChoice = "Europe/Bucharest".parse().unwrap();
+        assert_eq!(format_output(dt, tz, None), "2023-07-01T15:00:00+03:00");
+        assert_eq!(format_output(dt, tz, Some("%H:%M %Z")), "15:00 EEST");
+    }
+
+    #[cfg(feature = "tz")]
+    #[test]
+    fn interprets_formatted_input_in_named_zone() {
+        let tz: TzChoice = "America/New_York".parse().unwrap();
+        let (dt, _) = parse_input_to_utc("2023/11/14 17:13:20", tz, None, &[]).unwrap();
+        assert_eq!(dt.timestamp(), 1_700_000_000);
+
+        // 02:30 does not exist on the spring-forward day
+        let err = parse_input_to_utc("2024/03/10 02:30:00", tz, None, &[]).unwrap_err();
+        assert_eq!(
+            err,
+            EpochError::AmbiguousLocalTime {
+                zone: Some("America/New_York".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn formats_custom_format_utc() {
+        let dt = Utc.with_ymd_and_hms(2025, 12, 20, 11, 10, 11).unwrap();
+        let out = format_output(dt, TzChoice::Utc, Some("%Y/%m/%d %H:%M:%S"));
+        assert_eq!(out, "2025/12/20 11:10:11");
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn schema_describes_every_json_field() {
+        let schema = instant_json_schema(SchemaVersion::V1);
+        let properties = schema["properties"].as_object().unwrap();
+        let dt = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let parsed_as = ParsedAs::Timestamp {
+            unit: TsUnit::Seconds,
+            raw: 1_700_000_000,
+        };
+        let obj = instant_json(
+            "1700000000",
+            dt,
+            &parsed_as,
+            TzChoice::Utc,
+            TzChoice::Utc,
+            Some(4),
+        );
+        for field in obj.as_object().unwrap().keys() {
+            assert!(properties.contains_key(field), "{field}");
+        }
+        for field in schema["required"].as_array().unwrap() {
+            assert!(obj.get(field.as_str().unwrap()).is_some(), "{field}");
+        }
+        assert_eq!(
+            schema["properties"]["schema_version"]["const"],
+            obj["schema_version"]
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn schema_v2_adds_calendar_and_offset_fields() {
+        let dt = Utc.timestamp_opt(1_700_000_000, 5).unwrap();
+        let parsed_as = ParsedAs::Formatted {
+            offset: FixedOffset::east_opt(-5 * 3600),
+        };
+        let tz = TzChoice::Fixed(FixedOffset::east_opt(5 * 3600 + 1800).unwrap());
+        let obj = versioned_json(SchemaVersion::V2, "x", dt, &parsed_as, tz, tz, None);
+        assert_eq!(obj["schema_version"], 2);
+        assert_eq!(obj["nanos"], 5);
+        assert_eq!(obj["ts_raw"], serde_json::Value::Null);
+        assert_eq!(obj["source_offset_seconds"], -18_000);
+        assert_eq!(obj["output_offset"], "+05:30");
+        // 22:13:20 UTC is already Wednesday 03:43:20 at +05:30.
+        assert_eq!(obj["weekday"], "Wednesday");
+        assert_eq!(obj["iso_weekday"], 3);
+        assert_eq!(obj["iso_year"], 2023);
+        assert_eq!(obj["iso_week"], 46);
+        assert_eq!(obj["day_of_year"], 319);
+        assert_eq!(obj["quarter"], 4);
+        assert_eq!(obj["leap_year"], false);
+        // Every v1 field is still there.
+        let v1 = instant_json("x", dt, &parsed_as, tz, tz, None);
+        for field in v1.as_object().unwrap().keys() {
+            assert!(obj.get(field).is_some(), "{field}");
+        }
+
+        let schema = instant_json_schema(SchemaVersion::V2);
+        for field in obj.as_object().unwrap().keys() {
+            assert!(schema["properties"].get(field).is_some(), "{field}");
+        }
+        assert_eq!(schema["properties"]["schema_version"]["const"], 2);
+    }
+}
@@ -1,274 +1,2621 @@
-use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
-use clap::{Parser, ValueEnum};
+use chrono::{DateTime, Datelike, NaiveDate, Utc, Weekday};
+use clap::{Parser, Subcommand};
+use epoch::arith::{self, Amount};
+use epoch::calendars::CalendarSystem;
+use epoch::detectors::{self, Detector};
+use epoch::duration::{self, DurationFormat};
+use epoch::explain;
+use epoch::holidays::{self, Calendar};
+use epoch::ical;
+use epoch::milestones;
+use epoch::moon;
+use epoch::period::{self, Period};
+use epoch::presets;
+use epoch::query::Query;
+use epoch::radix::{self, Radix};
+use epoch::render;
+use epoch::representations;
+use epoch::scan::Scanner;
+use epoch::sun::{self, Crossing, Horizon};
+use epoch::tokens::{self, Dialect};
+use epoch::zone::{self, TzChoice};
+use epoch::{
+    Autodetect, DefaultTime, Encoding, EpochError, EpochParser, Formatter, HTML_TIME_DISPLAY,
+    ParsedAs, SchemaVersion, SyslogYear, TsUnit, age, check_strftime, naive_to_utc, summary,
+};
 use serde_json::json;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
 
+mod batch;
+mod clipboard;
+mod daemon;
+#[cfg(feature = "script")]
+mod script;
+mod serve;
+mod table;
+#[cfg(feature = "tui")]
+mod tui;
+
+/// Exit code for input that could not be parsed.
 const EXIT_PARSE: i32 = 3;
+/// Exit code for timezone problems: unknown or ambiguous zones, DST gaps and overlaps.
 const EXIT_TZ: i32 = 4;
 
-#[derive(Copy, Clone, Debug, ValueEnum)]
-enum TzChoice {
-    Utc,
-    Local,
+fn exit_code(e: &EpochError) -> i32 {
+    match e {
+        EpochError::AmbiguousLocalTime { .. }
+        | EpochError::UnknownTimezone(_)
+        | EpochError::AmbiguousAbbreviation { .. } => EXIT_TZ,
+        _ => EXIT_PARSE,
+    }
+}
+
+/// A parse error as the CLI words it: the library's message plus what helps from here.
+fn message(e: &EpochError) -> String {
+    match e {
+        EpochError::UnrecognizedFormat => format!("{e}; see `epoch formats` for what is read"),
+        EpochError::AmbiguousAbbreviation { .. } => format!("{e}; use --prefer-zone to choose"),
+        _ => e.to_string(),
+    }
+}
+
+#[derive(Parser, Debug)]
+#[command(name = "timeparse")]
+#[command(about = "Parse a unix timestamp or a formatted datetime (YYYY/MM/DD HH:MM:SS).")]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Timestamp (seconds/millis) OR formatted datetime: YYYY/MM/DD HH:MM:SS
+    /// OR ISO week date: 2024-W05-3 / 2024W053.
+    /// `-` reads one INPUT per line from stdin and prints one result per line
+    #[arg(required_unless_present_any = ["input_file", "paste"])]
+    input: Option<String>,
+
+    /// Take INPUT from the system clipboard (pbpaste, wl-paste, xclip, xsel or
+    /// PowerShell; EPOCH_PASTE_COMMAND overrides), e.g. for a hotkey binding
+    #[arg(long, conflicts_with_all = ["input", "input_file"])]
+    paste: bool,
+
+    #[command(flatten)]
+    batch: BatchOpts,
+
+    #[command(flatten)]
+    output: OutputOpts,
+
+    #[command(flatten)]
+    parse: ParseOpts,
+}
+
+/// Options for converting many inputs, one per line (`-` or --input-file).
+#[derive(clap::Args, Debug)]
+struct BatchOpts {
+    /// Convert every line of PATH, streamed rather than loaded into memory
+    #[arg(long, value_name = "PATH", conflicts_with = "input")]
+    input_file: Option<PathBuf>,
+
+    /// Convert lines on N worker threads (0 = one per CPU); output keeps the input order
+    #[arg(long, short = 'j', value_name = "N", default_value_t = 1)]
+    jobs: usize,
+
+    /// Report lines converted (and the share of --input-file read) on stderr
+    #[arg(long)]
+    progress: bool,
+
+    /// After the results, print how many instants were converted and their min, max and
+    /// span to stderr, leaving stdout to the results (a `{"stats": ...}` line with --json)
+    #[arg(long)]
+    stats: bool,
+}
+
+/// Options controlling how INPUT is interpreted.
+#[derive(clap::Args, Debug)]
+struct ParseOpts {
+    /// Timezone used to interpret formatted input (YYYY/MM/DD HH:MM:SS): utc, local, an
+    /// IANA name like Europe/Paris, or a fixed offset like +05:30. Default: local
+    #[arg(long, default_value_t = TzChoice::Local, allow_hyphen_values = true)]
+    input_tz: TzChoice,
+
+    /// Region(s) to prefer when a zone abbreviation in the input is ambiguous,
+    /// e.g. `--prefer-zone in` reads IST as India Standard Time.
+    #[arg(long, value_delimiter = ',')]
+    prefer_zone: Vec<String>,
+
+    /// When INPUT is numeric, force interpretation: seconds, millis, micros or nanos.
+    /// If omitted, seconds vs millis is auto-detected.
+    #[arg(long, value_enum)]
+    ts: Option<TsUnit>,
+
+    /// Autodetected numeric input at or above this magnitude is millis, below it seconds
+    #[arg(long, value_name = "N", default_value_t = Autodetect::default().threshold)]
+    detect_threshold: u64,
+
+    /// Plausible years for autodetection, e.g. 1990..2040: a unit that alone lands in
+    /// the window wins over --detect-threshold
+    #[arg(long, value_name = "FROM..TO", value_parser = year_window)]
+    detect_window: Option<(i32, i32)>,
+
+    /// Year for syslog times that lack one (`Nov 14 22:13:20`): recent (the latest year
+    /// not in the future), current, or a year like 2023.
+    #[arg(long, default_value_t = SyslogYear::Recent)]
+    syslog_year: SyslogYear,
+
+    /// Read INPUT in this format instead of autodetecting it. `journal` takes journald
+    /// microseconds; with `-`, the other fields of `journalctl -o export` are skipped.
+    #[arg(long = "from", value_enum, value_name = "FORMAT")]
+    encoding: Option<Encoding>,
+
+    /// Read INPUT with this strftime pattern instead of autodetecting it, e.g.
+    /// `%d/%m/%y`. Without a time, midnight is used.
+    #[arg(long, value_parser = input_pattern, conflicts_with = "encoding")]
+    input_format: Option<String>,
+
+    /// Two-digit years (`%y`, date-time groups) below this are 20YY, the rest 19YY
+    #[arg(
+        long,
+        default_value_t = epoch::CENTURY_PIVOT,
+        value_parser = clap::value_parser!(u8).range(0..=100)
+    )]
+    century_pivot: u8,
+
+    /// Strip surrounding quotes and brackets, trailing `,`/`;` and `ms`/`s` unit suffixes
+    /// (which then set --ts) from INPUT before parsing, as copied out of JSON and code
+    #[arg(long)]
+    lenient: bool,
+
+    /// Time of day for date-only INPUT (`2023-11-14`, week dates, --input-format without a
+    /// time): start (midnight), end (23:59:59), noon, or HH:MM[:SS]. Year-month INPUT
+    /// (`2023-11`) takes the month's first day, or its last with end
+    #[arg(long, visible_alias = "default-time", value_name = "TIME", default_value_t = DefaultTime::Start)]
+    at: DefaultTime,
+
+    /// Date for time-only INPUT (`22:13`, `10:13pm`), as YYYY-MM-DD. Default: today in
+    /// --input-tz
+    #[arg(long = "date", value_name = "YYYY-MM-DD", value_parser = calendar_date)]
+    on_date: Option<NaiveDate>,
+
+    /// Read 14-, 12- and 8-digit INPUT as a timestamp even when it makes a plausible
+    /// YYYYMMDD[HHMM[SS]] date or date-time (--ts does too)
+    #[arg(long)]
+    no_compact: bool,
+
+    /// Also try the custom formats declared in this JSON file: strftime patterns (with an
+    /// optional regex and zone) or external commands, before or after the built-ins
+    #[arg(long, value_name = "PATH", value_parser = detector_file)]
+    detectors: Option<DetectorFile>,
+
+    /// Explain on stderr how INPUT was read: the detection steps tried, the unit picked
+    /// for numeric input and why, and the zone applied (each line prefixed `line N:` in
+    /// batch mode)
+    #[arg(short, long)]
+    verbose: bool,
+}
+
+impl ParseOpts {
+    fn parser(&self) -> EpochParser {
+        let parser = EpochParser::new()
+            .input_tz(self.input_tz)
+            .autodetect(Autodetect {
+                threshold: self.detect_threshold,
+                window: self.detect_window,
+            })
+            .prefer_zone(&self.prefer_zone)
+            .syslog_year(self.syslog_year)
+            .lenient(self.lenient)
+            .compact_digits(!self.no_compact)
+            .default_time(self.at);
+        let parser = match self.on_date {
+            Some(date) => parser.default_date(date),
+            None => parser,
+        };
+        let parser = match self.encoding {
+            Some(encoding) => parser.encoding(encoding),
+            None => parser,
+        }
+        .century_pivot(self.century_pivot);
+        let parser = match &self.input_format {
+            Some(pattern) => parser.input_format(pattern),
+            None => parser,
+        };
+        let parser = match &self.detectors {
+            Some(DetectorFile(detectors)) => parser.detectors(detectors.clone()),
+            None => parser,
+        };
+        match self.ts {
+            Some(unit) => parser.force_unit(unit),
+            None => parser,
+        }
+    }
+}
+
+/// A `--detect-window` value: `FROM..TO` years, inclusive.
+fn year_window(s: &str) -> Result<(i32, i32), String> {
+    let (from, to) = s
+        .split_once("..")
+        .and_then(|(from, to)| Some((from.parse().ok()?, to.parse().ok()?)))
+        .ok_or_else(|| format!("expected FROM..TO years, e.g. 1990..2040, got `{s}`"))?;
+    if from > to {
+        return Err(format!("{from} is after {to}"));
+    }
+    Ok((from, to))
+}
+
+/// A `--date` value: `YYYY-MM-DD`.
+fn calendar_date(s: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|_| format!("expected a YYYY-MM-DD date, got `{s}`"))
+}
+
+/// A `--clamp-min` / `--clamp-max` bound: any accepted INPUT, read as UTC unless it
+/// states an offset, so the window does not move with the host zone.
+fn bound(s: &str) -> Result<DateTime<Utc>, String> {
+    EpochParser::new()
+        .input_tz(TzChoice::Utc)
+        .parse(s)
+        .map(|(dt, _)| dt)
+        .map_err(|e| e.to_string())
+}
+
+/// The detectors declared in a `--detectors` file.
+#[derive(Clone, Debug)]
+struct DetectorFile(Vec<Detector>);
+
+fn detector_file(path: &str) -> Result<DetectorFile, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+    detectors::from_json(&text)
+        .map(DetectorFile)
+        .map_err(|e| format!("{path}: {e}"))
+}
+
+/// An `--input-format` value: a strftime pattern.
+fn input_pattern(s: &str) -> Result<String, String> {
+    check_strftime(s).map_err(|e| e.to_string())?;
+    Ok(s.to_string())
+}
+
+/// A `--format` value: a preset name, `html-time`, `proto-timestamp` or a strftime
+/// pattern.
+fn strftime_pattern(s: &str) -> Result<String, String> {
+    if presets::find(s).is_some() || s == HTML_TIME || s == PROTO_TIMESTAMP {
+        return Ok(s.to_string());
+    }
+    input_pattern(s)
+}
+
+/// The `--format` value for HTML `<time>` elements.
+const HTML_TIME: &str = "html-time";
+
+/// The `--format` value for `google.protobuf.Timestamp` objects, the same as
+/// `--to proto-timestamp`.
+const PROTO_TIMESTAMP: &str = "proto-timestamp";
+
+/// Options controlling how the resulting instant is printed.
+#[derive(clap::Args, Clone, Debug)]
+struct OutputOpts {
+    /// Output unix seconds only (single line)
+    #[arg(long, conflicts_with_all = ["json", "get"])]
+    unix: bool,
+
+    /// Write --unix output in this base instead of decimal
+    #[arg(long, value_enum, requires = "unix")]
+    radix: Option<Radix>,
+
+    /// Output JSON only (single line)
+    #[arg(long, conflicts_with_all = ["unix", "get"])]
+    json: bool,
+
+    /// Lay results out as a table of input, unix, rfc3339 (or the --format/--to output)
+    /// and tz, aligned or in Markdown; batch runs print one table once every line is read
+    #[arg(
+        long = "output",
+        value_enum,
+        value_name = "STYLE",
+        conflicts_with_all = ["unix", "json", "get", "query", "all"]
+    )]
+    output_style: Option<OutputStyle>,
+
+    /// Draw ASCII borders around --output table
+    #[arg(long, requires = "output_style")]
+    borders: bool,
+
+    /// Output a table of the instant in every representation: epoch units, RFC 3339 in
+    /// UTC and local time, RFC 2822, ISO week and ordinal dates, Julian day, FILETIME and
+    /// more (an object of them with --json)
+    #[arg(long, conflicts_with_all = ["unix", "get", "query", "format", "output_encoding"])]
+    all: bool,
+
+    /// Output the JSON result's fields as TOML, with rfc3339 as a native date-time (batch
+    /// output makes each result a [[results]] table)
+    #[arg(long, conflicts_with_all = ["unix", "json", "get", "query", "all", "output_style"])]
+    toml: bool,
+
+    /// Output the JSON result's fields as an XML document (batch output is one <results>
+    /// document with a <result> element per line)
+    #[arg(
+        long,
+        conflicts_with_all = ["unix", "json", "get", "query", "all", "output_style", "toml"]
+    )]
+    xml: bool,
+
+    /// Output a single field of the JSON result, e.g. `--get unix_millis`
+    #[arg(long, value_name = "FIELD")]
+    get: Option<String>,
+
+    /// Output the part of the JSON result at a jq-style path, e.g. `--query .unix_millis`
+    /// or `--query .rfc3339`; strings are printed bare
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["unix", "json", "get"])]
+    query: Option<Query>,
+
+    /// Layout of JSON results: 1, or 2 for sub-second, offset, ISO week, quarter and
+    /// parse fields on top (see `epoch schema --schema 2`)
+    #[arg(long, value_enum, default_value_t = SchemaVersion::V1)]
+    schema: SchemaVersion,
+
+    /// First month (1-12) of the fiscal year; adds fiscal_year / fiscal_quarter /
+    /// fiscal_period to the JSON result, computed on the --output-tz calendar
+    #[arg(long, value_name = "MONTH", value_parser = clap::value_parser!(u32).range(1..=12))]
+    fiscal_start: Option<u32>,
+
+    /// Custom output format: a strftime pattern or a preset (rfc2822, iso8601,
+    /// iso8601-basic, iso8601-basic-utc, http, sql, unix, ctime, kitchen, aws-logs,
+    /// git-raw), html-time for an HTML <time> element, or proto-timestamp for a
+    /// google.protobuf.Timestamp `{"seconds": ..., "nanos": ...}`. Only applies to string
+    /// outputs (default RFC3339).
+    #[arg(long, value_parser = strftime_pattern)]
+    format: Option<String>,
+
+    /// strftime pattern for the text of --format html-time elements
+    #[arg(long, value_name = "PATTERN", value_parser = input_pattern, default_value = HTML_TIME_DISPLAY)]
+    html_display: String,
+
+    /// Print the instant in this format instead, e.g. julianday (see --from). Only
+    /// applies to string outputs.
+    #[arg(
+        long = "to",
+        value_enum,
+        value_name = "FORMAT",
+        conflicts_with = "format"
+    )]
+    output_encoding: Option<Encoding>,
+
+    /// Print the date (on the --output-tz calendar) in another calendar system instead.
+    /// Only applies to string outputs.
+    #[arg(long, value_enum, conflicts_with_all = ["format", "output_encoding"])]
+    calendar: Option<CalendarSystem>,
+
+    /// Timezone used for formatted output: utc, local, an IANA name like Europe/Paris, or
+    /// a fixed offset like +05:30 or -0700. Default: UTC
+    #[arg(long, default_value_t = TzChoice::Utc, allow_hyphen_values = true)]
+    output_tz: TzChoice,
+
+    /// Move the instant to the start of its minute, hour, day, week (see --week-start),
+    /// month, quarter or year on the --output-tz calendar (local midnight for days and
+    /// longer)
+    #[arg(long, value_enum, value_name = "PERIOD")]
+    start_of: Option<Period>,
+
+    /// Move the instant to the last nanosecond of its minute, hour, day, week (see
+    /// --week-start), month, quarter or year on the --output-tz calendar
+    #[arg(long, value_enum, value_name = "PERIOD", conflicts_with = "start_of")]
+    end_of: Option<Period>,
+
+    /// Day weeks start on for --start-of / --end-of week (default mon, as in ISO 8601);
+    /// also adds week (numbered from the week holding January 1) and week_start to the
+    /// JSON result
+    #[arg(long, value_name = "WEEKDAY", value_parser = arith::parse_weekday)]
+    week_start: Option<Weekday>,
+
+    /// Print this instant instead of any earlier one (any accepted INPUT, read as UTC
+    /// unless it has an offset); adds `clamped` to the JSON result
+    #[arg(long, value_name = "INPUT", value_parser = bound)]
+    clamp_min: Option<DateTime<Utc>>,
+
+    /// Print this instant instead of any later one (read like --clamp-min); adds
+    /// `clamped` to the JSON result
+    #[arg(long, value_name = "INPUT", value_parser = bound)]
+    clamp_max: Option<DateTime<Utc>>,
+
+    /// Print what the Rhai script at PATH makes of each result instead: it sees the JSON
+    /// result (plus `formatted`, the usual output) as `result` and the instant as
+    /// `instant`; in batch mode, inputs it evaluates to `()` for are dropped
+    #[cfg(feature = "script")]
+    #[arg(
+        long,
+        value_name = "PATH",
+        value_parser = script::Script::load,
+        conflicts_with_all = [
+            "unix", "json", "get", "query", "all", "output_style", "toml", "xml"
+        ]
+    )]
+    script: Option<script::Script>,
+}
+
+/// Layouts for `--output`.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputStyle {
+    /// Aligned columns
+    Table,
+    /// A Markdown table, ready to paste into an issue or runbook
+    Markdown,
+}
+
+impl OutputOpts {
+    fn table_style(&self) -> Option<table::Style> {
+        self.output_style.map(|style| match style {
+            OutputStyle::Table if self.borders => table::Style::Bordered,
+            OutputStyle::Table => table::Style::Plain,
+            OutputStyle::Markdown => table::Style::Markdown,
+        })
+    }
+
+    /// Whether --script decides what is printed, and whether anything is.
+    fn scripted(&self) -> bool {
+        #[cfg(feature = "script")]
+        let scripted = self.script.is_some();
+        #[cfg(not(feature = "script"))]
+        let scripted = false;
+        scripted
+    }
+
+    /// `utc` moved as --start-of or --end-of asks.
+    fn snap(&self, utc: DateTime<Utc>) -> DateTime<Utc> {
+        let week_start = self.week_start.unwrap_or(Weekday::Mon);
+        match (self.start_of, self.end_of) {
+            (Some(period), _) => period::period_start(utc, period, self.output_tz, week_start),
+            (_, Some(period)) => period::period_end(utc, period, self.output_tz, week_start),
+            (None, None) => utc,
+        }
+    }
+
+    /// Exit with a usage error unless the --clamp-min / --clamp-max window is in order.
+    fn check_clamp(&self) {
+        if let (Some(min), Some(max)) = (self.clamp_min, self.clamp_max)
+            && min > max
+        {
+            die(2, "Error: --clamp-min is later than --clamp-max");
+        }
+    }
+
+    /// `utc` moved into the --clamp-min / --clamp-max window, and whether it had to be.
+    fn clamp(&self, utc: DateTime<Utc>) -> (DateTime<Utc>, bool) {
+        match (self.clamp_min, self.clamp_max) {
+            (Some(min), _) if utc < min => (min, true),
+            (_, Some(max)) if utc > max => (max, true),
+            _ => (utc, false),
+        }
+    }
+
+    fn formatter(&self) -> Formatter {
+        let formatter = Formatter::new().output_tz(self.output_tz);
+        if let Some(encoding) = self.output_encoding {
+            return formatter.encoding(encoding);
+        }
+        if let Some(calendar) = self.calendar {
+            return formatter.calendar(calendar);
+        }
+        match &self.format {
+            Some(name) if let Some(preset) = presets::find(name) => formatter.preset(preset),
+            Some(name) if name == HTML_TIME => formatter.html_time(&self.html_display),
+            Some(name) if name == PROTO_TIMESTAMP => formatter.encoding(Encoding::ProtoTimestamp),
+            Some(pattern) => formatter.strftime(pattern),
+            None => formatter,
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Timezone database queries
+    #[command(subcommand)]
+    Tz(TzCommand),
+
+    /// Add an amount of time to INPUT
+    Add(ArithArgs),
+
+    /// Subtract an amount of time from INPUT
+    Sub(ArithArgs),
+
+    /// Exit 0 and print the holiday's name if INPUT falls on a public holiday, else exit 1
+    IsHoliday(IsHolidayArgs),
+
+    /// Time from A to B (B - A), optionally checked against a tolerance
+    Diff(DiffArgs),
+
+    /// Print whether A is before, equal to or after B, exiting 0, 1 or 2 to match
+    Compare(CompareArgs),
+
+    /// Exact elapsed years, months and days from a date until today (or TO)
+    Age(AgeArgs),
+
+    /// Profile timestamps read one per line: count, range, median, gap percentiles and rate
+    Summary(SummaryArgs),
+
+    /// Keep the first timestamp (line) in each period, e.g. `--by hour`
+    Uniq(UniqArgs),
+
+    /// Count timestamps per period as `period_start,count` CSV rows, e.g. `--by day`
+    Group(GroupArgs),
+
+    /// Find timestamps anywhere in free text; prints one JSON object per match
+    Scan(ScanArgs),
+
+    /// How INPUT was read (rule, unit, zone, heuristics) and how else it could be read
+    Explain(ExplainArgs),
+
+    /// Print an iCalendar (.ics) event starting at START, for calendar invites
+    Ics(IcsArgs),
+
+    /// Convert the DTSTART, DTEND and RRULE UNTIL times in an .ics file (- for stdin)
+    IcsRead(IcsReadArgs),
+
+    /// Translate a date format pattern between strftime, Go, moment.js and Java
+    FmtConvert(FmtConvertArgs),
+
+    /// List the input formats autodetection tries, in order, with an example of each
+    Formats {
+        /// Output JSON only
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Print a JSON Schema for the JSON output, for validation and code generation
+    Schema {
+        /// Which output to describe
+        #[arg(long, value_enum, default_value_t = SchemaOf::Json)]
+        output: SchemaOf,
+
+        /// Schema version to describe
+        #[arg(long, value_enum, default_value_t = SchemaVersion::V1)]
+        schema: SchemaVersion,
+    },
+
+    /// Serve conversions over HTTP: GET /parse?input=... and GET /now
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        listen: String,
+    },
+
+    /// Answer newline-delimited JSON requests on stdin, one JSON response per line
+    Daemon,
+
+    /// Interactive converter that updates as you type
+    #[cfg(feature = "tui")]
+    Tui(TuiArgs),
+
+    /// When the next clock change (DST transition) happens, and by how much
+    DstNext(DstArgs),
+
+    /// When the previous clock change (DST transition) happened, and by how much
+    DstPrev(DstArgs),
+
+    /// Upcoming round and curious Unix times (1800000000, 2^31, 2222222222) with countdowns
+    Milestones(MilestonesArgs),
+
+    /// The Moon's phase and illuminated fraction at TIME
+    Moon(MoonArgs),
+
+    /// Sunrise, sunset, twilight, solar noon and day length for a place on DATE
+    Sun(SunArgs),
+
+    /// The next WEEKDAY after today (or TIME's date), at --at: `epoch next monday --at 09:00`
+    Next(WeekdayArgs),
+
+    /// The last WEEKDAY before today (or TIME's date), at --at: `epoch prev friday`
+    Prev(WeekdayArgs),
+
+    /// The Nth WEEKDAY of MONTH, at --at: `epoch nth 2 tuesday 2025-03`, `epoch nth -1 sun
+    /// 2024-10` for the last
+    Nth(NthArgs),
+
+    /// Easter Sunday in YEAR (Western, or --orthodox), and with --feasts the holy days
+    /// that move with it
+    Easter(EasterArgs),
+
+    /// How many days MONTH has: `epoch days-in 2024-02` prints 29
+    DaysIn(DaysInArgs),
+
+    /// Answer a yes/no question about INPUT with the exit code: 0 for yes, 1 for no
+    #[command(subcommand)]
+    Is(IsCommand),
+}
+
+#[derive(clap::Args, Debug)]
+struct ArithArgs {
+    /// Starting instant (any accepted INPUT)
+    input: String,
+
+    /// Amount such as 90s, 1h30m, 2d4h, 1w, PT1H30M, or 3bd (business days)
+    #[arg(required_unless_present = "business_days", allow_hyphen_values = true)]
+    amount: Option<Amount>,
+
+    /// Move N business days, skipping weekend days (same as a `<N>bd` amount).
+    /// Days are counted on the --input-tz calendar.
+    #[arg(
+        long,
+        value_name = "N",
+        conflicts_with = "amount",
+        allow_hyphen_values = true
+    )]
+    business_days: Option<i64>,
+
+    /// Days treated as the weekend by business-day arithmetic
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_parser = arith::parse_weekday,
+        default_value = "sat,sun"
+    )]
+    weekend: Vec<Weekday>,
+
+    /// Also skip this country's public holidays in business-day arithmetic
+    #[arg(long, value_enum, ignore_case = true)]
+    holidays: Option<Calendar>,
+
+    #[command(flatten)]
+    output: OutputOpts,
+
+    #[command(flatten)]
+    parse: ParseOpts,
+}
+
+#[derive(clap::Args, Debug)]
+struct IsHolidayArgs {
+    /// Instant to check (any accepted INPUT); its date on the --input-tz calendar is used
+    input: String,
+
+    /// Holiday calendar to consult
+    #[arg(long, value_enum, ignore_case = true)]
+    holidays: Calendar,
+
+    #[command(flatten)]
+    parse: ParseOpts,
+}
+
+#[derive(clap::Args, Debug)]
+struct DiffArgs {
+    /// First instant (any accepted INPUT)
+    a: String,
+
+    /// Second instant (any accepted INPUT)
+    b: String,
+
+    /// Exit 1 unless A and B are at most this far apart, e.g. 90s, 1h30m or PT5M
+    #[arg(long, value_parser = duration::parse_duration)]
+    tolerance: Option<chrono::Duration>,
+
+    /// How to print the difference
+    #[arg(long, value_enum, default_value_t)]
+    duration_format: DurationFormat,
+
+    /// With --duration-format human, show at most N units (largest first)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u16).range(1..))]
+    max_units: Option<u16>,
+
+    /// Output JSON only
+    #[arg(long)]
+    json: bool,
+
+    #[command(flatten)]
+    parse: ParseOpts,
+}
+
+#[derive(clap::Args, Debug)]
+struct CompareArgs {
+    /// First instant (any accepted INPUT)
+    a: String,
+
+    /// Second instant (any accepted INPUT)
+    b: String,
+
+    /// Count A and B as equal when at most this far apart, e.g. 5s, 1h30m or PT5M
+    #[arg(long, value_parser = duration::parse_duration)]
+    tolerance: Option<chrono::Duration>,
+
+    /// Output JSON only
+    #[arg(long)]
+    json: bool,
+
+    #[command(flatten)]
+    parse: ParseOpts,
+}
+
+#[derive(clap::Args, Debug)]
+struct IcsArgs {
+    /// When the event starts (any accepted INPUT)
+    start: String,
+
+    /// How long the event lasts, e.g. 30m, 1h30m or PT2H
+    #[arg(long, value_parser = duration::parse_duration, default_value = "1h")]
+    duration: chrono::Duration,
+
+    /// When the event ends (any accepted INPUT), instead of --duration
+    #[arg(long, conflicts_with = "duration")]
+    end: Option<String>,
+
+    /// Title of the event
+    #[arg(long)]
+    summary: String,
+
+    /// Unique identifier of the event. Default: generated from the current time
+    #[arg(long)]
+    uid: Option<String>,
+
+    #[command(flatten)]
+    parse: ParseOpts,
+}
+
+#[derive(clap::Args, Debug)]
+struct IcsReadArgs {
+    /// Calendar file to read, or - for stdin
+    path: PathBuf,
+
+    #[command(flatten)]
+    output: OutputOpts,
+
+    /// --input-tz applies to "floating" times, which have neither a Z nor a TZID
+    #[command(flatten)]
+    parse: ParseOpts,
+}
+
+#[derive(clap::Args, Debug)]
+struct ExplainArgs {
+    /// Any accepted INPUT
+    input: String,
+
+    /// Output JSON only
+    #[arg(long)]
+    json: bool,
+
+    #[command(flatten)]
+    parse: ParseOpts,
+}
+
+#[derive(clap::Args, Debug)]
+struct FmtConvertArgs {
+    /// The pattern to translate, e.g. "%Y-%m-%d %H:%M:%S"
+    pattern: String,
+
+    /// Language PATTERN is written in
+    #[arg(long, value_enum, default_value = "strftime")]
+    from: Dialect,
+
+    /// Language to write it in
+    #[arg(long, value_enum)]
+    to: Dialect,
+}
+
+/// Where subcommands that read many instants take them from.
+#[derive(clap::Args, Debug)]
+struct StreamArgs {
+    /// Read instants (any accepted INPUT), one per line, from PATH instead of stdin
+    #[arg(long, value_name = "PATH")]
+    input_file: Option<PathBuf>,
+
+    #[command(flatten)]
+    parse: ParseOpts,
+}
+
+#[derive(clap::Args, Debug)]
+struct SummaryArgs {
+    /// Output JSON only
+    #[arg(long)]
+    json: bool,
+
+    /// Timezone for the reported instants. Default: UTC
+    #[arg(long, default_value_t = TzChoice::Utc, allow_hyphen_values = true)]
+    output_tz: TzChoice,
+
+    #[command(flatten)]
+    stream: StreamArgs,
+}
+
+#[derive(clap::Args, Debug)]
+struct UniqArgs {
+    /// Period that makes two timestamps duplicates
+    #[arg(long, value_enum)]
+    by: Period,
+
+    /// Print each period's start (RFC 3339) instead of its first line
+    #[arg(long)]
+    starts: bool,
+
+    /// Day weeks start on for --by week
+    #[arg(long, value_name = "WEEKDAY", value_parser = arith::parse_weekday, default_value = "mon")]
+    week_start: Weekday,
+
+    /// Timezone whose calendar defines the periods (and --starts output). Default: UTC
+    #[arg(long, default_value_t = TzChoice::Utc, allow_hyphen_values = true)]
+    output_tz: TzChoice,
+
+    #[command(flatten)]
+    stream: StreamArgs,
+}
+
+#[derive(clap::Args, Debug)]
+struct GroupArgs {
+    /// Period to count per
+    #[arg(long, value_enum)]
+    by: Period,
+
+    /// Day weeks start on for --by week
+    #[arg(long, value_name = "WEEKDAY", value_parser = arith::parse_weekday, default_value = "mon")]
+    week_start: Weekday,
+
+    /// Timezone whose calendar defines the periods and their printed starts. Default: UTC
+    #[arg(long, default_value_t = TzChoice::Utc, allow_hyphen_values = true)]
+    output_tz: TzChoice,
+
+    /// Output a JSON array of {period_start, count} instead of CSV
+    #[arg(long)]
+    json: bool,
+
+    #[command(flatten)]
+    stream: StreamArgs,
+}
+
+#[derive(clap::Args, Debug)]
+struct ScanArgs {
+    /// Timezone for the `rfc3339` field of each match. Default: UTC
+    #[arg(long, default_value_t = TzChoice::Utc, allow_hyphen_values = true)]
+    output_tz: TzChoice,
+
+    #[command(flatten)]
+    stream: StreamArgs,
+}
+
+#[derive(clap::Args, Debug)]
+struct AgeArgs {
+    /// Start date: YYYY-MM-DD, YYYY/MM/DD, or any accepted INPUT (its date on the
+    /// --input-tz calendar is used)
+    from: String,
+
+    /// End date, in the same forms as FROM. Default: today
+    to: Option<String>,
+
+    /// Output JSON only
+    #[arg(long)]
+    json: bool,
+
+    #[command(flatten)]
+    parse: ParseOpts,
+}
+
+#[cfg(feature = "tui")]
+#[derive(clap::Args, Debug)]
+struct TuiArgs {
+    /// Zones to show the instant in
+    #[arg(
+        long = "zone",
+        value_delimiter = ',',
+        default_value = "UTC,local,America/New_York,Europe/London,Asia/Tokyo",
+        allow_hyphen_values = true
+    )]
+    zones: Vec<TzChoice>,
+
+    #[command(flatten)]
+    parse: ParseOpts,
+}
+
+#[derive(clap::Args, Debug)]
+struct DstArgs {
+    /// IANA zone name (or `local`)
+    #[arg(long, default_value = "local")]
+    tz: String,
+
+    /// Reference instant (any accepted INPUT; formatted input is read in the zone).
+    /// Default: now
+    time: Option<String>,
+
+    /// Output JSON only
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct MilestonesArgs {
+    /// How many milestones to list
+    #[arg(long, default_value_t = 10)]
+    count: usize,
+
+    /// Count down from this instant (any accepted INPUT). Default: now
+    time: Option<String>,
+
+    /// Timezone the dates are shown in. Default: UTC
+    #[arg(long, default_value_t = TzChoice::Utc, allow_hyphen_values = true)]
+    output_tz: TzChoice,
+
+    /// Output JSON only
+    #[arg(long)]
+    json: bool,
+
+    #[command(flatten)]
+    parse: ParseOpts,
+}
+
+#[derive(clap::Args, Debug)]
+struct MoonArgs {
+    /// Any accepted INPUT. Default: now
+    time: Option<String>,
+
+    /// Timezone the time is shown in. Default: UTC
+    #[arg(long, default_value_t = TzChoice::Utc, allow_hyphen_values = true)]
+    output_tz: TzChoice,
+
+    /// Output JSON only
+    #[arg(long)]
+    json: bool,
+
+    #[command(flatten)]
+    parse: ParseOpts,
+}
+
+#[derive(clap::Args, Debug)]
+struct WeekdayArgs {
+    /// mon, tue, ... sun, or the full name
+    #[arg(value_parser = arith::parse_weekday)]
+    weekday: Weekday,
+
+    /// Count from this instant's date (any accepted INPUT). Default: now
+    time: Option<String>,
+
+    /// Zone whose calendar and clock the day and --at time are on. Default: --input-tz
+    #[arg(long, allow_hyphen_values = true)]
+    tz: Option<TzChoice>,
+
+    #[command(flatten)]
+    output: OutputOpts,
+
+    #[command(flatten)]
+    parse: ParseOpts,
+}
+
+#[derive(clap::Args, Debug)]
+struct NthArgs {
+    /// 1 to 5, or -1 to -5 counting from the end of the month
+    #[arg(
+        allow_hyphen_values = true,
+        value_parser = clap::value_parser!(i8).range(-5..=5)
+    )]
+    n: i8,
+
+    /// mon, tue, ... sun, or the full name
+    #[arg(value_parser = arith::parse_weekday)]
+    weekday: Weekday,
+
+    /// YYYY-MM
+    #[arg(value_parser = year_month)]
+    month: (i32, u32),
+
+    /// Zone whose calendar and clock the day and --at time are on. Default: --input-tz
+    #[arg(long, allow_hyphen_values = true)]
+    tz: Option<TzChoice>,
+
+    #[command(flatten)]
+    output: OutputOpts,
+
+    #[command(flatten)]
+    parse: ParseOpts,
+}
+
+/// A `YYYY-MM` (or `YYYY/MM`) month.
+fn year_month(s: &str) -> Result<(i32, u32), String> {
+    NaiveDate::parse_from_str(&format!("{s}-01"), "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(&format!("{s}/01"), "%Y/%m/%d"))
+        .map(|date| (date.year(), date.month()))
+        .map_err(|_| format!("expected a YYYY-MM month, got `{s}`"))
+}
+
+#[derive(clap::Args, Debug)]
+struct EasterArgs {
+    /// Year. Default: the current year
+    year: Option<i32>,
+
+    /// Use the Julian computus of the Orthodox churches (dates are still Gregorian)
+    #[arg(long)]
+    orthodox: bool,
+
+    /// Also list the movable feasts: Ash Wednesday, Good Friday, Ascension, Pentecost, ...
+    #[arg(long)]
+    feasts: bool,
+
+    /// Output JSON only
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct DaysInArgs {
+    /// YYYY-MM, or any accepted INPUT for its month on the --input-tz calendar. Default:
+    /// now
+    month: Option<String>,
+
+    /// Print the month's last date (YYYY-MM-DD) instead of the count
+    #[arg(long)]
+    last: bool,
+
+    /// Output JSON only
+    #[arg(long, conflicts_with = "last")]
+    json: bool,
+
+    #[command(flatten)]
+    parse: ParseOpts,
+}
+
+#[derive(clap::Args, Debug)]
+struct SunArgs {
+    /// YYYY-MM-DD, or the --input-tz calendar date of any accepted INPUT. Default: today
+    date: Option<String>,
+
+    /// Latitude in degrees, north positive, e.g. 44.43
+    #[arg(long, value_parser = latitude, allow_hyphen_values = true)]
+    lat: f64,
+
+    /// Longitude in degrees, east positive, e.g. 26.10 (west is negative: -73.99)
+    #[arg(long, value_parser = longitude, allow_hyphen_values = true)]
+    lon: f64,
+
+    /// Timezone the times are shown in: utc, local, an IANA name or a fixed offset.
+    /// Default: UTC
+    #[arg(long, default_value_t = TzChoice::Utc, allow_hyphen_values = true)]
+    output_tz: TzChoice,
+
+    /// Output JSON only
+    #[arg(long)]
+    json: bool,
+
+    #[command(flatten)]
+    parse: ParseOpts,
+}
+
+fn degrees(s: &str, limit: f64) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(deg) if deg.abs() <= limit => Ok(deg),
+        _ => Err(format!("expected degrees from -{limit} to {limit}")),
+    }
+}
+
+/// A `--lat` value.
+fn latitude(s: &str) -> Result<f64, String> {
+    degrees(s, 90.0)
+}
+
+/// A `--lon` value.
+fn longitude(s: &str) -> Result<f64, String> {
+    degrees(s, 180.0)
+}
+
+#[derive(Subcommand, Debug)]
+enum TzCommand {
+    /// List IANA zone names usable with --input-tz / --output-tz
+    List {
+        /// Only list zones whose name contains PATTERN (case-insensitive)
+        pattern: Option<String>,
+
+        /// Also show each zone's current UTC offset and abbreviation
+        #[arg(short, long)]
+        long: bool,
+    },
+
+    /// Show a zone's offset, abbreviation and DST status, plus the surrounding transitions
+    Info {
+        /// IANA zone name (or `local`)
+        zone: String,
+
+        /// Instant to inspect (any accepted INPUT; formatted input is read in ZONE).
+        /// Default: now
+        time: Option<String>,
+
+        /// Output JSON only
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum IsCommand {
+    /// Whether INPUT's date falls on the weekend
+    Weekend(IsDayArgs),
+
+    /// Whether INPUT's date is a working day: not on the weekend, nor a --holidays holiday
+    BusinessDay {
+        #[command(flatten)]
+        day: IsDayArgs,
+
+        /// Also count this country's public holidays as days off
+        #[arg(long, value_enum, ignore_case = true)]
+        holidays: Option<Calendar>,
+    },
+
+    /// Whether a year has 366 days
+    LeapYear {
+        /// A year of up to four digits, or any accepted INPUT for its year on the
+        /// --input-tz calendar. Default: now
+        year: Option<String>,
+
+        /// Output JSON only (the exit code still answers)
+        #[arg(long)]
+        json: bool,
+
+        /// Also print the answer and why, e.g. `yes (2024 has 366 days)`
+        #[arg(long, conflicts_with = "json")]
+        print: bool,
+
+        #[command(flatten)]
+        parse: ParseOpts,
+    },
+}
+
+#[derive(clap::Args, Debug)]
+struct IsDayArgs {
+    /// Instant to check (any accepted INPUT); its date on the --input-tz calendar is used.
+    /// Default: now
+    input: Option<String>,
+
+    /// Days that make the weekend
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_parser = arith::parse_weekday,
+        default_value = "sat,sun"
+    )]
+    weekend: Vec<Weekday>,
+
+    /// Also print the answer and why, e.g. `no (Saturday)`
+    #[arg(long)]
+    print: bool,
+
+    #[command(flatten)]
+    parse: ParseOpts,
+}
+
+fn die(code: i32, msg: impl AsRef<str>) -> ! {
+    eprintln!("{}", msg.as_ref());
+    std::process::exit(code);
+}
+
+fn transition_json(t: &zone::Transition) -> serde_json::Value {
+    json!({
+        "at": t.at.to_rfc3339(),
+        "unix_seconds": t.at.timestamp(),
+        "change_seconds": t.change().num_seconds(),
+        "from_offset": t.before.utc_offset.to_string(),
+        "from_abbreviation": t.before.abbreviation,
+        "to_offset": t.after.utc_offset.to_string(),
+        "to_abbreviation": t.after.abbreviation,
+    })
+}
+
+fn transition_line(t: &zone::Transition) -> String {
+    format!(
+        "{}  {} {} -> {} {}",
+        t.at.to_rfc3339(),
+        t.before.utc_offset,
+        t.before.abbreviation,
+        t.after.utc_offset,
+        t.after.abbreviation
+    )
+}
+
+/// Describe a clock change, e.g. `clocks go back 1h`.
+fn describe_change(change: chrono::Duration) -> String {
+    let secs = change.num_seconds();
+    let (h, m) = (secs.abs() / 3600, secs.abs() % 3600 / 60);
+    let amount = match (h, m) {
+        (0, m) => format!("{m}m"),
+        (h, 0) => format!("{h}h"),
+        (h, m) => format!("{h}h{m:02}m"),
+    };
+    match secs {
+        0 => "offset unchanged".to_string(),
+        s if s > 0 => format!("clocks go forward {amount}"),
+        _ => format!("clocks go back {amount}"),
+    }
+}
+
+/// Resolve a zone and reference instant given on the command line, exiting on error.
+fn zone_and_time(zone: &str, time: Option<&str>) -> (chrono_tz::Tz, DateTime<Utc>) {
+    let tz = zone::resolve_iana(zone).unwrap_or_else(|e| die(EXIT_TZ, format!("Error: {e}")));
+    let at = match time {
+        Some(time) => match EpochParser::new().input_tz(TzChoice::Named(tz)).parse(time) {
+            Ok((dt, _)) => dt,
+            Err(e) => die(exit_code(&e), format!("Error: {}", message(&e))),
+        },
+        None => Utc::now(),
+    };
+    (tz, at)
+}
+
+fn run_dst(args: DstArgs, forward: bool) {
+    let (tz, at) = zone_and_time(&args.tz, args.time.as_deref());
+    let transition = if forward {
+        zone::next_transition(tz, at)
+    } else {
+        zone::prev_transition(tz, at)
+    };
+    let Some(t) = transition else {
+        die(1, format!("No clock changes found for {}", tz.name()));
+    };
+
+    if args.json {
+        let mut obj = transition_json(&t);
+        obj["zone"] = json!(tz.name());
+        println!("{}", serde_json::to_string_pretty(&obj).unwrap());
+        return;
+    }
+
+    println!(
+        "{}  {}  ({} {} -> {} {})",
+        t.at.with_timezone(&tz).to_rfc3339(),
+        describe_change(t.change()),
+        t.before.utc_offset,
+        t.before.abbreviation,
+        t.after.utc_offset,
+        t.after.abbreviation
+    );
+}
+
+fn run_tz(command: TzCommand) {
+    match command {
+        TzCommand::List { pattern, long } => {
+            let now = Utc::now();
+            for tz in zone::list_zones(pattern.as_deref()) {
+                if long {
+                    let local = now.with_timezone(&tz);
+                    println!(
+                        "{:<32} {} {}",
+                        tz.name(),
+                        local.format("%:z"),
+                        local.format("%Z")
+                    );
+                } else {
+                    println!("{}", tz.name());
+                }
+            }
+        }
+        TzCommand::Info { zone, time, json } => {
+            let (tz, at) = zone_and_time(&zone, time.as_deref());
+
+            let offset = zone::offset_at(tz, at);
+            let prev = zone::prev_transition(tz, at);
+            let next = zone::next_transition(tz, at);
+
+            if json {
+                let obj = json!({
+                    "zone": tz.name(),
+                    "time": at.with_timezone(&tz).to_rfc3339(),
+                    "unix_seconds": at.timestamp(),
+                    "utc_offset": offset.utc_offset.to_string(),
+                    "abbreviation": offset.abbreviation,
+                    "dst": offset.is_dst(),
+                    "dst_offset_seconds": offset.dst_offset.num_seconds(),
+                    "previous_transition": prev.as_ref().map(transition_json),
+                    "next_transition": next.as_ref().map(transition_json),
+                });
+                println!("{}", serde_json::to_string_pretty(&obj).unwrap());
+                return;
+            }
+
+            let none = || "none".to_string();
+            println!("Zone:          {}", tz.name());
+            println!("Time:          {}", at.with_timezone(&tz).to_rfc3339());
+            println!("UTC offset:    {}", offset.utc_offset);
+            println!("Abbreviation:  {}", offset.abbreviation);
+            println!(
+                "DST:           {}",
+                if offset.is_dst() { "yes" } else { "no" }
+            );
+            println!(
+                "Previous:      {}",
+                prev.as_ref().map_or_else(none, transition_line)
+            );
+            println!(
+                "Next:          {}",
+                next.as_ref().map_or_else(none, transition_line)
+            );
+        }
+    }
+}
+
+/// The warning for INPUT in scientific notation too large for `f64` to hold exactly.
+fn precision_warning(input: &str, opts: &ParseOpts) -> Option<String> {
+    let cleaned = if opts.lenient {
+        epoch::clean_lenient(input).0
+    } else {
+        input
+    };
+    epoch::loses_precision(cleaned).then(|| {
+        format!(
+            "Warning: {input} is beyond 2^53; digits past the 16th may have been lost in floating point"
+        )
+    })
+}
+
+/// Parse INPUT according to the parse options, exiting on error; warns first when INPUT
+/// is scientific notation too large for `f64` to hold exactly.
+fn parse_or_die(input: &str, opts: &ParseOpts) -> (DateTime<Utc>, ParsedAs) {
+    if let Some(warning) = precision_warning(input, opts) {
+        eprintln!("{warning}");
+    }
+    let result = if opts.verbose {
+        let (result, notes) = opts.parser().parse_traced(input);
+        for note in notes {
+            eprintln!("trace: {note}");
+        }
+        result
+    } else {
+        opts.parser().parse(input)
+    };
+    match result {
+        Ok(v) => v,
+        Err(e) => die(exit_code(&e), format!("Error: {}", message(&e))),
+    }
+}
+
+/// Render one field of a JSON result for `--get`: strings bare, everything else as JSON.
+/// Exits with a usage error when the field does not exist.
+fn get_field(obj: &serde_json::Value, field: &str) -> String {
+    match obj.get(field) {
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(v) => v.to_string(),
+        None => {
+            let fields: Vec<&str> = obj
+                .as_object()
+                .map(|o| o.keys().map(String::as_str).collect())
+                .unwrap_or_default();
+            die(
+                2,
+                format!(
+                    "Error: unknown field `{field}`; available: {}",
+                    fields.join(", ")
+                ),
+            )
+        }
+    }
+}
+
+/// The JSON result for an instant in the layout `out` asks for.
+fn result_json(
+    input: &str,
+    utc_dt: DateTime<Utc>,
+    parsed_as: &ParsedAs,
+    input_tz: TzChoice,
+    out: &OutputOpts,
+) -> serde_json::Value {
+    let mut obj = epoch::versioned_json(
+        out.schema,
+        input,
+        utc_dt,
+        parsed_as,
+        input_tz,
+        out.output_tz,
+        out.fiscal_start,
+    );
+    if let Some(week_start) = out.week_start {
+        let date = out.output_tz.naive_local(utc_dt).date();
+        obj["week"] = json!(period::week_of_year(date, week_start));
+        obj["week_start"] = json!(week_start.to_string().to_lowercase());
+    }
+    obj
+}
+
+/// Render the value at `query` in a JSON result: strings bare, everything else as JSON
+/// (pretty unless `compact`). Exits with a usage error when there is nothing there.
+fn query_value(obj: &serde_json::Value, query: &Query, compact: bool) -> String {
+    match query.select(obj) {
+        Ok(serde_json::Value::String(s)) => s.clone(),
+        Ok(v) if compact => v.to_string(),
+        Ok(v) => serde_json::to_string_pretty(v).unwrap(),
+        Err(e) => die(2, format!("Error: {e}")),
+    }
+}
+
+/// Render an instant according to the output options; `formatter` is `out.formatter()`,
+/// built once by callers rendering many instants. JSON is pretty-printed unless `compact`
+/// (batch output keeps one result per line).
+fn render_instant(
+    input: &str,
+    utc_dt: DateTime<Utc>,
+    parsed_as: &ParsedAs,
+    input_tz: TzChoice,
+    out: &OutputOpts,
+    formatter: &Formatter,
+    compact: bool,
+) -> String {
+    let (utc_dt, clamped) = out.clamp(out.snap(utc_dt));
+    let result_json = || {
+        let mut obj = result_json(input, utc_dt, parsed_as, input_tz, out);
+        if out.clamp_min.is_some() || out.clamp_max.is_some() {
+            obj["clamped"] = json!(clamped);
+        }
+        obj
+    };
+    if out.unix {
+        return match out.radix {
+            Some(r) => radix::encode(utc_dt.timestamp(), r),
+            None => utc_dt.timestamp().to_string(),
+        };
+    }
+
+    if out.output_style.is_some() {
+        return table_row(input, utc_dt, out, formatter);
+    }
+
+    if out.all {
+        let rows = representations::all(utc_dt, out.output_tz);
+        if out.json {
+            let obj: serde_json::Map<_, _> = rows
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), json!(value)))
+                .collect();
+            let obj = serde_json::Value::Object(obj);
+            return if compact {
+                obj.to_string()
+            } else {
+                serde_json::to_string_pretty(&obj).unwrap()
+            };
+        }
+        let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+        return rows
+            .iter()
+            .map(|(name, value)| format!("{name:<width$}  {value}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+
+    #[cfg(feature = "script")]
+    if let Some(script) = &out.script {
+        let mut obj = result_json();
+        obj["formatted"] = json!(formatter.format(utc_dt));
+        return script
+            .run(utc_dt, &obj)
+            .unwrap_or_else(|e| die(2, format!("Error: {e}")));
+    }
+
+    if out.json || out.get.is_some() || out.query.is_some() || out.toml || out.xml {
+        let obj = result_json();
+        if out.toml || out.xml {
+            let obj = obj.as_object().expect("results are objects");
+            return if out.toml {
+                render::toml(obj, compact.then_some("results"))
+            } else {
+                render::xml(obj, compact)
+            };
+        }
+        if let Some(query) = &out.query {
+            return query_value(&obj, query, compact);
+        }
+        return match &out.get {
+            Some(field) => get_field(&obj, field),
+            None if compact => obj.to_string(),
+            None => serde_json::to_string_pretty(&obj).unwrap(),
+        };
+    }
+
+    // Default: single-line string output (RFC3339 unless --format provided)
+    formatter.format(utc_dt)
+}
+
+/// The cells of an `--output` table row, tab-separated so that batch output can carry
+/// them line by line until [`print_table`] lays them out.
+fn table_row(
+    input: &str,
+    utc_dt: DateTime<Utc>,
+    out: &OutputOpts,
+    formatter: &Formatter,
+) -> String {
+    [
+        input.replace('\t', " "),
+        utc_dt.timestamp().to_string(),
+        formatter.format(utc_dt),
+        out.output_tz.to_string(),
+    ]
+    .join("\t")
+}
+
+/// Print rows from [`table_row`], one per line of `rows`, as an `--output` table.
+fn print_table(rows: &str, out: &OutputOpts) {
+    let style = out.table_style().expect("--output is set");
+    let custom = out.format.is_some() || out.output_encoding.is_some();
+    let header = [
+        "input",
+        "unix",
+        if custom { "output" } else { "rfc3339" },
+        "tz",
+    ];
+    let rows: Vec<Vec<String>> = rows
+        .lines()
+        .map(|row| row.split('\t').map(String::from).collect())
+        .collect();
+    println!("{}", table::render(&header, &rows, style));
 }
 
-impl TzChoice {
-    fn as_str(&self) -> &'static str {
-        match self {
-            TzChoice::Utc => "UTC",
-            TzChoice::Local => "local",
+/// Print an instant according to the output options.
+fn print_instant(
+    input: &str,
+    utc_dt: DateTime<Utc>,
+    parsed_as: &ParsedAs,
+    input_tz: TzChoice,
+    out: &OutputOpts,
+) {
+    let rendered = render_instant(
+        input,
+        utc_dt,
+        parsed_as,
+        input_tz,
+        out,
+        &out.formatter(),
+        false,
+    );
+    if out.output_style.is_some() {
+        print_table(&rendered, out);
+    } else {
+        println!("{rendered}");
+    }
+}
+
+/// Convert stdin (`epoch -`) or --input-file line by line.
+fn run_batch(opts: &BatchOpts, parse: &ParseOpts, output: &OutputOpts) {
+    // Resolve `local` once rather than going through the system zone for every line.
+    let parser = parse.parser().input_tz(parse.input_tz.pin_local());
+    let output = &OutputOpts {
+        output_tz: output.output_tz.pin_local(),
+        ..output.clone()
+    };
+    let formatter = output.formatter();
+    let stats = batch::Stats::default();
+    let convert = |line: &str, notes: &mut Vec<String>| {
+        if parse.encoding == Some(Encoding::Journal) && is_other_journal_field(line) {
+            return Ok(None);
         }
+        notes.extend(precision_warning(line, parse));
+        let result = if parse.verbose {
+            let (result, trace) = parser.parse_traced(line);
+            notes.extend(trace.into_iter().map(|note| format!("trace: {note}")));
+            result
+        } else {
+            parser.parse(line)
+        };
+        let (utc_dt, parsed_as) =
+            result.map_err(|e| (exit_code(&e), format!("Error: {}", message(&e))))?;
+        stats.record(utc_dt);
+        let rendered = render_instant(
+            line,
+            utc_dt,
+            &parsed_as,
+            parse.input_tz,
+            output,
+            &formatter,
+            true,
+        );
+        Ok((!output.scripted() || !rendered.is_empty()).then_some(rendered))
+    };
+
+    let (source, size) = open_input(opts.input_file.as_deref());
+    let progress = batch::Progress::new(size);
+    let input = BufReader::with_capacity(1 << 20, progress.track(source));
+
+    let progress = opts.progress.then_some(&progress);
+    let failed = if output.output_style.is_some() {
+        // Column widths depend on every row, so the table waits for the last one.
+        let mut rows = Vec::new();
+        let failed = batch::run(input, &mut rows, opts.jobs, progress, &convert);
+        let failed = failed.unwrap_or_else(|e| die(2, format!("Error: {e}")));
+        print_table(&String::from_utf8_lossy(&rows), output);
+        failed
+    } else {
+        let mut stdout = io::BufWriter::new(io::stdout().lock());
+        // Batch --xml is one document: the declaration once, every <result> under one root.
+        let (head, tail) = if output.xml {
+            (
+                format!("{}\n<results>\n", render::XML_DECLARATION),
+                "</results>\n",
+            )
+        } else {
+            (String::new(), "")
+        };
+        let written = stdout
+            .write_all(head.as_bytes())
+            .and_then(|()| batch::run(input, &mut stdout, opts.jobs, progress, &convert))
+            .and_then(|failed| {
+                stdout.write_all(tail.as_bytes())?;
+                stdout.flush()?;
+                Ok(failed)
+            });
+        written.unwrap_or_else(|e| die(2, format!("Error: {e}")))
+    };
+
+    if opts.stats {
+        print_stats(&stats, output);
+    }
+    if let Some(code) = failed {
+        std::process::exit(code);
     }
 }
 
-#[derive(Copy, Clone, Debug, ValueEnum)]
-enum TsUnit {
-    Seconds,
-    Millis,
+/// A `journalctl -o export` field other than the entry's timestamp, such as `MESSAGE=..`.
+fn is_other_journal_field(line: &str) -> bool {
+    line.split_once('=')
+        .is_some_and(|(key, _)| key != "__REALTIME_TIMESTAMP")
 }
 
-#[derive(Parser, Debug)]
-#[command(name = "timeparse")]
-#[command(about = "Parse a unix timestamp or a formatted datetime (YYYY/MM/DD HH:MM:SS).")]
-struct Args {
-    /// Timestamp (seconds/millis) OR formatted datetime: YYYY/MM/DD HH:MM:SS
-    input: String,
+/// The `--stats` summary on stderr; instants are shown as RFC 3339 in --output-tz.
+fn print_stats(stats: &batch::Stats, output: &OutputOpts) {
+    let rfc3339 = Formatter::new().output_tz(output.output_tz);
+    let range = stats.range();
+    let span = range.map(|(min, max)| max - min);
 
-    /// Output unix seconds only (single line)
-    #[arg(long, conflicts_with_all = ["json"])]
-    unix: bool,
+    if output.json {
+        let obj = json!({ "stats": {
+            "count": stats.count(),
+            "min": range.map(|(min, _)| rfc3339.format(min)),
+            "max": range.map(|(_, max)| rfc3339.format(max)),
+            "span_seconds": span.map(|d| d.num_seconds()),
+        }});
+        eprintln!("{obj}");
+        return;
+    }
 
-    /// Output JSON only (single line)
-    #[arg(long, conflicts_with_all = ["unix"])]
-    json: bool,
+    let none = || "-".to_string();
+    eprintln!("count: {}", stats.count());
+    eprintln!(
+        "min:   {}",
+        range.map_or_else(none, |(min, _)| rfc3339.format(min))
+    );
+    eprintln!(
+        "max:   {}",
+        range.map_or_else(none, |(_, max)| rfc3339.format(max))
+    );
+    eprintln!(
+        "span:  {}",
+        span.map_or_else(none, |d| duration::format_human(d, None))
+    );
+}
 
-    /// Custom output format (strftime). Only applies to string outputs (default RFC3339).
-    #[arg(long)]
-    format: Option<String>,
+fn run_arith(args: ArithArgs, subtract: bool) {
+    let (utc_dt, parsed_as) = parse_or_die(&args.input, &args.parse);
 
-    /// Timezone used to interpret formatted input (YYYY/MM/DD HH:MM:SS). Default: local
-    #[arg(long, value_enum, default_value_t = TzChoice::Local)]
-    input_tz: TzChoice,
+    let amount = args
+        .business_days
+        .map(Amount::BusinessDays)
+        .or(args.amount)
+        .expect("clap requires an amount");
+    let amount = if subtract { amount.negate() } else { amount };
 
-    /// Timezone used for formatted output. Default: UTC
-    #[arg(long, value_enum, default_value_t = TzChoice::Utc)]
-    output_tz: TzChoice,
+    let result = match amount {
+        Amount::Fixed(d) => utc_dt
+            .checked_add_signed(d)
+            .ok_or("result out of range".to_string()),
+        Amount::BusinessDays(n) => {
+            let tz = args.parse.input_tz;
+            let local = tz.naive_local(utc_dt);
+            arith::add_business_days(local.date(), n, &args.weekend, args.holidays).and_then(
+                |date| naive_to_utc(&date.and_time(local.time()), tz).map_err(|e| e.to_string()),
+            )
+        }
+    };
 
-    /// When INPUT is numeric, force interpretation: seconds or millis.
-    /// If omitted, seconds vs millis is auto-detected.
-    #[arg(long, value_enum)]
-    ts: Option<TsUnit>,
+    match result {
+        Ok(dt) => print_instant(
+            &args.input,
+            dt,
+            &parsed_as,
+            args.parse.input_tz,
+            &args.output,
+        ),
+        Err(msg) => die(EXIT_PARSE, format!("Error: {msg}")),
+    }
+}
+
+fn run_is(command: IsCommand) {
+    match command {
+        IsCommand::Weekend(day) => run_is_day(&day, None, false),
+        IsCommand::BusinessDay { day, holidays } => run_is_day(&day, holidays, true),
+        IsCommand::LeapYear {
+            year,
+            json,
+            print,
+            parse,
+        } => run_is_leap_year(year.as_deref(), json, print, &parse),
+    }
 }
 
-#[derive(Debug)]
-enum ParsedAs {
-    Timestamp { unit: TsUnit, raw: i64 },
-    Formatted,
+/// Print `yes (why)` or `no (why)` when asked, and exit 1 for no.
+fn answer(yes: bool, why: &str, print: bool) {
+    if print {
+        println!("{} ({why})", if yes { "yes" } else { "no" });
+    }
+    if !yes {
+        std::process::exit(1);
+    }
 }
 
-fn die(code: i32, msg: impl AsRef<str>) -> ! {
-    eprintln!("{}", msg.as_ref());
-    std::process::exit(code);
+/// `is weekend`, or `is business-day` when `business`.
+fn run_is_day(day: &IsDayArgs, holidays: Option<Calendar>, business: bool) {
+    let at = match &day.input {
+        Some(input) => parse_or_die(input, &day.parse).0,
+        None => Utc::now(),
+    };
+    let date = day.parse.input_tz.naive_local(at).date();
+    let weekday = date.format("%A").to_string();
+    let weekend = day.weekend.contains(&date.weekday());
+    if !business {
+        return answer(weekend, &weekday, day.print);
+    }
+    match holidays.and_then(|calendar| calendar.holiday_on(date)) {
+        _ if weekend => answer(false, &format!("{weekday}, weekend"), day.print),
+        Some(name) => answer(false, name, day.print),
+        None => answer(true, &weekday, day.print),
+    }
 }
 
-/// Convert a numeric timestamp into a UTC DateTime, using forced or autodetected unit.
-fn parse_timestamp_to_utc(
-    raw: i64,
-    forced: Option<TsUnit>,
-) -> Result<(DateTime<Utc>, TsUnit), String> {
-    let unit = forced.unwrap_or_else(|| {
-        if raw.abs() >= 1_000_000_000_000 {
-            TsUnit::Millis
-        } else {
-            TsUnit::Seconds
+fn run_is_leap_year(year: Option<&str>, json: bool, print: bool, parse: &ParseOpts) {
+    let year = match year {
+        Some(year) if year.len() <= 4 && year.bytes().all(|b| b.is_ascii_digit()) => {
+            year.parse().expect("four digits")
         }
+        Some(input) => parse
+            .input_tz
+            .naive_local(parse_or_die(input, parse).0)
+            .year(),
+        None => parse.input_tz.naive_local(Utc::now()).year(),
+    };
+    let leap = NaiveDate::from_yo_opt(year, 366).is_some();
+    let days = if leap { 366 } else { 365 };
+    if json {
+        let obj = json!({"year": year, "leap_year": leap, "days": days});
+        println!("{}", serde_json::to_string_pretty(&obj).unwrap());
+    }
+    answer(leap, &format!("{year} has {days} days"), print);
+}
+
+fn run_is_holiday(args: IsHolidayArgs) {
+    let (utc_dt, _) = parse_or_die(&args.input, &args.parse);
+    let date = args.parse.input_tz.naive_local(utc_dt).date();
+
+    match args.holidays.holiday_on(date) {
+        Some(name) => println!("{name}"),
+        None => std::process::exit(1),
+    }
+}
+
+/// Seconds with millisecond precision, dropping the fraction when it is zero.
+fn fmt_seconds(d: chrono::Duration) -> String {
+    let millis = d.num_milliseconds();
+    if millis % 1000 == 0 {
+        (millis / 1000).to_string()
+    } else {
+        format!("{:.3}", millis as f64 / 1000.0)
+    }
+}
+
+fn run_ics(args: IcsArgs) {
+    let (start, _) = parse_or_die(&args.start, &args.parse);
+    let end = match &args.end {
+        Some(end) => parse_or_die(end, &args.parse).0,
+        None => start + args.duration,
+    };
+    if end < start {
+        die(2, "Error: the event ends before it starts");
+    }
+    let stamp = Utc::now();
+    let uid = args.uid.unwrap_or_else(|| {
+        let nanos = stamp.timestamp_nanos_opt().unwrap_or_default();
+        format!("{nanos}-{}@epoch", std::process::id())
     });
+    let event = ical::Event {
+        uid,
+        summary: args.summary,
+        start,
+        end,
+        stamp,
+    };
+    print!("{}", event.to_ics());
+}
+
+fn run_ics_read(args: IcsReadArgs) {
+    let path = (args.path.as_os_str() != "-").then_some(args.path.as_path());
+    let mut calendar = String::new();
+    if let Err(e) = open_input(path).0.read_to_string(&mut calendar) {
+        die(2, format!("Error: {e}"));
+    }
+    let out = &args.output;
+    let formatter = out.formatter();
+    let parsed_as = ParsedAs::Layout {
+        name: "ics",
+        offset: None,
+    };
 
-    let (secs, nanos) = match unit {
-        TsUnit::Seconds => (raw, 0u32),
-        TsUnit::Millis => {
-            let secs = raw / 1000;
-            let ms = (raw % 1000).abs() as u32;
-            (secs, ms * 1_000_000)
+    let mut rows = String::new();
+    let mut failed = None;
+    for property in ical::time_properties(&calendar) {
+        let label = match property.name.as_str() {
+            "RRULE" => "RRULE UNTIL",
+            name => name,
+        };
+        let utc_dt = match property.to_utc(args.parse.input_tz) {
+            Some(Ok(utc_dt)) => utc_dt,
+            Some(Err(e)) => {
+                eprintln!("line {}: Error: {}", property.line, message(&e));
+                failed.get_or_insert(exit_code(&e));
+                continue;
+            }
+            // A rule without an end: nothing to convert, but worth seeing.
+            None if out.json => {
+                let obj = json!({ "property": property.name, "line": property.line, "rrule": property.value });
+                println!("{obj}");
+                continue;
+            }
+            None => {
+                println!("{}: {} (no UNTIL)", property.name, property.value);
+                continue;
+            }
+        };
+        let value = property.date_time().unwrap_or_default();
+        if out.json {
+            let mut obj = result_json(value, utc_dt, &parsed_as, args.parse.input_tz, out);
+            obj["property"] = json!(property.name);
+            obj["line"] = json!(property.line);
+            obj["tzid"] = json!(property.tzid);
+            if property.name == "RRULE" {
+                obj["rrule"] = json!(property.value);
+            }
+            println!("{obj}");
+            continue;
         }
+        let rendered = render_instant(
+            value,
+            utc_dt,
+            &parsed_as,
+            args.parse.input_tz,
+            out,
+            &formatter,
+            true,
+        );
+        if out.output_style.is_some() {
+            rows.push_str(&rendered);
+            rows.push('\n');
+        } else {
+            println!("{label}: {rendered}");
+        }
+    }
+    if out.output_style.is_some() {
+        print_table(&rows, out);
+    }
+    if let Some(code) = failed {
+        std::process::exit(code);
+    }
+}
+
+fn run_diff(args: DiffArgs) {
+    let (a, _) = parse_or_die(&args.a, &args.parse);
+    let (b, _) = parse_or_die(&args.b, &args.parse);
+    let diff = b - a;
+    let within = args.tolerance.map(|t| diff.abs() <= t.abs());
+    let formatted = match args.duration_format {
+        DurationFormat::Seconds => fmt_seconds(diff),
+        DurationFormat::Iso => duration::format_iso(diff),
+        DurationFormat::Human => duration::format_human(diff, args.max_units.map(usize::from)),
     };
 
-    let dt = Utc
-        .timestamp_opt(secs, nanos)
-        .single()
-        .ok_or_else(|| "Invalid unix timestamp".to_string())?;
+    if args.json {
+        let obj = json!({
+            "a": a.to_rfc3339(),
+            "b": b.to_rfc3339(),
+            "seconds": diff.num_seconds(),
+            "millis": diff.num_milliseconds(),
+            "iso": duration::format_iso(diff),
+            "human": duration::format_human(diff, args.max_units.map(usize::from)),
+            "tolerance_seconds": args.tolerance.map(|t| t.abs().num_seconds()),
+            "within_tolerance": within,
+        });
+        println!("{}", serde_json::to_string_pretty(&obj).unwrap());
+    } else {
+        println!("{formatted}");
+    }
 
-    Ok((dt, unit))
+    if within == Some(false) {
+        std::process::exit(1);
+    }
 }
 
-/// Parse either numeric timestamp OR formatted datetime into UTC.
-fn parse_input_to_utc(
-    input: &str,
-    input_tz: TzChoice,
-    forced_ts: Option<TsUnit>,
-) -> Result<(DateTime<Utc>, ParsedAs), (i32, String)> {
-    // 1) numeric timestamp
-    if let Ok(raw) = input.parse::<i64>() {
-        return parse_timestamp_to_utc(raw, forced_ts)
-            .map(|(dt, unit)| (dt, ParsedAs::Timestamp { unit, raw }))
-            .map_err(|e| (EXIT_PARSE, e));
+/// Exits 0 (before), 1 (equal) or 2 (after). Unreadable input exits 3 as usual; a usage
+/// error also exits 2, but prints nothing on stdout.
+fn run_compare(args: CompareArgs) {
+    let (a, _) = parse_or_die(&args.a, &args.parse);
+    let (b, _) = parse_or_die(&args.b, &args.parse);
+    let diff = b - a;
+    let tolerance = args
+        .tolerance
+        .map_or_else(chrono::Duration::zero, |t| t.abs());
+    let (answer, code) = if diff.abs() <= tolerance {
+        ("equal", 1)
+    } else if diff > chrono::Duration::zero() {
+        ("before", 0)
+    } else {
+        ("after", 2)
+    };
+
+    if args.json {
+        let obj = json!({
+            "a": a.to_rfc3339(),
+            "b": b.to_rfc3339(),
+            "result": answer,
+            "seconds": diff.num_seconds(),
+            "tolerance_seconds": args.tolerance.map(|t| t.abs().num_seconds()),
+        });
+        println!("{}", serde_json::to_string_pretty(&obj).unwrap());
+    } else {
+        println!("{answer}");
     }
+    std::process::exit(code);
+}
 
-    // 2) formatted datetime: YYYY/MM/DD HH:MM:SS
-    let naive = NaiveDateTime::parse_from_str(input, "%Y/%m/%d %H:%M:%S").map_err(|_| {
-        (
-            EXIT_PARSE,
-            "Expected format: YYYY/MM/DD HH:MM:SS".to_string(),
-        )
-    })?;
-
-    let utc_dt = match input_tz {
-        TzChoice::Utc => Utc.from_utc_datetime(&naive),
-        TzChoice::Local => {
-            let local_dt = Local.from_local_datetime(&naive).single().ok_or_else(|| {
-                (
-                    EXIT_TZ,
-                    "Ambiguous or non-existent local time (DST transition)".to_string(),
-                )
-            })?;
-            local_dt.with_timezone(&Utc)
-        }
+fn run_explain(args: ExplainArgs) {
+    let e = explain::explain(&args.parse.parser(), &args.input)
+        .unwrap_or_else(|e| die(exit_code(&e), format!("Error: {}", message(&e))));
+    let unit = match e.parsed_as {
+        ParsedAs::Timestamp { unit, .. } => Some(unit.name()),
+        _ => None,
     };
+    let zone = match e.parsed_as {
+        ParsedAs::Timestamp { .. } => None,
+        _ => Some(
+            e.parsed_as
+                .source_offset()
+                .map_or_else(|| args.parse.input_tz.to_string(), |o| o.to_string()),
+        ),
+    };
+
+    if args.json {
+        let alternatives: Vec<_> = e
+            .alternatives
+            .iter()
+            .map(|a| json!({ "reading": a.reading, "rfc3339": a.utc.to_rfc3339() }))
+            .collect();
+        let obj = json!({
+            "input": args.input,
+            "parsed_as": e.parsed_as.name(),
+            "ts_unit": unit,
+            "zone": zone,
+            "rfc3339": e.utc.to_rfc3339(),
+            "unix_seconds": e.utc.timestamp(),
+            "steps": e.steps,
+            "alternatives": alternatives,
+        });
+        println!("{}", serde_json::to_string_pretty(&obj).unwrap());
+        return;
+    }
 
-    Ok((utc_dt, ParsedAs::Formatted))
+    let mut reading = e.parsed_as.name().to_string();
+    if let Some(unit) = unit {
+        reading += &format!(" in {unit}");
+    }
+    if let Some(zone) = &zone {
+        reading += &format!(", zone {zone}");
+    }
+    println!("`{}` was read as {reading}:", args.input);
+    println!("  {}", e.utc.to_rfc3339());
+    println!("How:");
+    for step in &e.steps {
+        println!("  {step}");
+    }
+    if !e.alternatives.is_empty() {
+        println!("Other readings:");
+        for a in &e.alternatives {
+            println!("  {} it would be {}", a.reading, a.utc.to_rfc3339());
+        }
+    }
 }
 
-fn format_output(utc_dt: DateTime<Utc>, output_tz: TzChoice, fmt: Option<&str>) -> String {
-    match (output_tz, fmt) {
-        (TzChoice::Utc, Some(f)) => utc_dt.format(f).to_string(),
-        (TzChoice::Local, Some(f)) => utc_dt.with_timezone(&Local).format(f).to_string(),
-        (TzChoice::Utc, None) => utc_dt.to_rfc3339(),
-        (TzChoice::Local, None) => utc_dt.with_timezone(&Local).to_rfc3339(),
+fn run_fmt_convert(args: FmtConvertArgs) {
+    match tokens::convert(&args.pattern, args.from, args.to) {
+        Ok(pattern) => println!("{pattern}"),
+        Err(e) => die(2, format!("Error: {e}")),
     }
 }
 
-fn main() {
-    let args = Args::parse();
+fn run_formats(json: bool) {
+    if json {
+        let formats: Vec<_> = epoch::FORMATS
+            .iter()
+            .map(|f| json!({ "name": f.name, "description": f.description, "example": f.example }))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&formats).unwrap());
+        return;
+    }
+    let width = |field: fn(&epoch::Format) -> &str| {
+        epoch::FORMATS
+            .iter()
+            .map(|f| field(f).len())
+            .max()
+            .unwrap_or(0)
+    };
+    let (name_width, example_width) = (width(|f| f.name), width(|f| f.example));
+    for f in epoch::FORMATS {
+        println!(
+            "{:<name_width$}  {:<example_width$}  {}",
+            f.name, f.example, f.description
+        );
+    }
+}
 
-    let (utc_dt, parsed_as) = match parse_input_to_utc(&args.input, args.input_tz, args.ts) {
-        Ok(v) => v,
-        Err((code, msg)) => die(code, format!("Error: {msg}")),
+fn run_milestones(args: MilestonesArgs) {
+    let from = match &args.time {
+        Some(time) => parse_or_die(time, &args.parse).0,
+        None => Utc::now(),
+    };
+    let formatter = Formatter::new().output_tz(args.output_tz);
+    let rows: Vec<_> = milestones::upcoming(from, args.count)
+        .into_iter()
+        .filter_map(|m| Some((m.at()?, m)))
+        .collect();
+
+    if args.json {
+        let rows: Vec<_> = rows
+            .iter()
+            .map(|(at, m)| {
+                json!({
+                    "unix_seconds": m.unix_seconds,
+                    "rfc3339": formatter.format(*at),
+                    "reason": m.reason,
+                    "seconds_left": (*at - from).num_seconds(),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&rows).unwrap());
+        return;
+    }
+    for (at, m) in &rows {
+        println!(
+            "{:<11}  {}  in {:<20}  {}",
+            m.unix_seconds,
+            formatter.format(*at),
+            duration::format_human(*at - from, Some(2)),
+            m.reason
+        );
+    }
+}
+
+fn run_weekday(args: WeekdayArgs, forward: bool) {
+    let tz = args.tz.unwrap_or(args.parse.input_tz);
+    let from = match &args.time {
+        Some(time) => parse_or_die(time, &args.parse).0,
+        None => Utc::now(),
+    };
+    let today = tz.naive_local(from).date();
+    let (date, word) = if forward {
+        (arith::next_weekday(today, args.weekday), "next")
+    } else {
+        (arith::prev_weekday(today, args.weekday), "prev")
+    };
+    let at = naive_to_utc(&date.and_time(args.parse.at.time()), tz)
+        .unwrap_or_else(|e| die(exit_code(&e), format!("Error: {}", message(&e))));
+    let input = format!("{word} {}", args.weekday);
+    print_instant(&input, at, &ParsedAs::Expression, tz, &args.output);
+}
+
+fn run_nth(args: NthArgs) {
+    let tz = args.tz.unwrap_or(args.parse.input_tz);
+    let (year, month) = args.month;
+    if args.n == 0 {
+        die(2, "Error: N counts from 1 (or back from -1)");
+    }
+    let Some(date) = arith::nth_weekday(year, month, args.weekday, args.n) else {
+        // Every month has at least four of each weekday, so only a fifth can be missing.
+        let which = if args.n > 0 { "5th" } else { "5th-last" };
+        die(
+            1,
+            format!("{year}-{month:02} has no {which} {}", args.weekday),
+        );
+    };
+    let at = naive_to_utc(&date.and_time(args.parse.at.time()), tz)
+        .unwrap_or_else(|e| die(exit_code(&e), format!("Error: {}", message(&e))));
+    let input = format!("nth {} {} {year}-{month:02}", args.n, args.weekday);
+    print_instant(&input, at, &ParsedAs::Expression, tz, &args.output);
+}
+
+fn run_days_in(args: DaysInArgs) {
+    let (year, month) = match &args.month {
+        Some(month) => year_month(month).unwrap_or_else(|_| {
+            let local = args
+                .parse
+                .input_tz
+                .naive_local(parse_or_die(month, &args.parse).0);
+            (local.year(), local.month())
+        }),
+        None => {
+            let local = args.parse.input_tz.naive_local(Utc::now());
+            (local.year(), local.month())
+        }
     };
+    let first = NaiveDate::from_ymd_opt(year, month, 1)
+        .unwrap_or_else(|| die(2, format!("Error: {year}-{month:02} is out of range")));
+    let last = first
+        .checked_add_months(chrono::Months::new(1))
+        .and_then(|next| next.pred_opt())
+        .unwrap_or_else(|| die(2, format!("Error: {year}-{month:02} is out of range")));
+    let days = last.day();
+
+    if args.json {
+        let obj = json!({
+            "month": first.format("%Y-%m").to_string(),
+            "days": days,
+            "first_date": first.to_string(),
+            "last_date": last.to_string(),
+        });
+        println!("{}", serde_json::to_string_pretty(&obj).unwrap());
+    } else if args.last {
+        println!("{last}");
+    } else {
+        println!("{days}");
+    }
+}
+
+fn run_easter(args: EasterArgs) {
+    let year = args.year.unwrap_or_else(|| Utc::now().year());
+    let feasts = holidays::movable_feasts(year, args.orthodox)
+        .unwrap_or_else(|| die(2, format!("Error: year {year} is out of range")));
+    let sunday = holidays::easter_in(year, args.orthodox).expect("feasts include Easter");
+
+    if args.json {
+        let mut obj = json!({
+            "year": year,
+            "computus": if args.orthodox { "julian" } else { "gregorian" },
+            "easter": sunday.to_string(),
+        });
+        if args.feasts {
+            obj["feasts"] = feasts
+                .iter()
+                .map(|(date, name, offset)| {
+                    json!({"name": name, "date": date.to_string(), "days_from_easter": offset})
+                })
+                .collect();
+        }
+        println!("{}", serde_json::to_string_pretty(&obj).unwrap());
+        return;
+    }
+
+    if !args.feasts {
+        println!("{sunday}");
+        return;
+    }
+    for (date, name, _) in feasts {
+        println!("{date}  {}  {name}", date.format("%a"));
+    }
+}
 
-    // Always compute canonical unix outputs from UTC
-    let unix_seconds = utc_dt.timestamp();
-    let unix_millis = utc_dt.timestamp_millis();
+fn run_moon(args: MoonArgs) {
+    let at = match &args.time {
+        Some(time) => parse_or_die(time, &args.parse).0,
+        None => Utc::now(),
+    };
+    let moon = moon::phase_at(at);
+    let time = Formatter::new().output_tz(args.output_tz).format(at);
+    let round = |x: f64, places: i32| (x * 10f64.powi(places)).round() / 10f64.powi(places);
 
-    if args.unix {
-        println!("{unix_seconds}");
+    if args.json {
+        let obj = json!({
+            "time": time,
+            "unix_seconds": at.timestamp(),
+            "phase": moon.phase.name(),
+            "illumination": round(moon.illumination, 4),
+            "waxing": moon.waxing(),
+            "age_days": round(moon.age_days, 2),
+            "elongation_degrees": round(moon.elongation, 2),
+        });
+        println!("{}", serde_json::to_string_pretty(&obj).unwrap());
         return;
     }
+    println!(
+        "{time}  {}, {:.1}% illuminated, {:.1} days since new moon",
+        moon.phase.name(),
+        moon.illumination * 100.0,
+        moon.age_days
+    );
+}
+
+fn run_sun(args: SunArgs) {
+    let date = match &args.date {
+        Some(date) => date_or_die(date, &args.parse),
+        None => args.parse.input_tz.naive_local(Utc::now()).date(),
+    };
+    let day = sun::solar_day(date, args.lat, args.lon);
+    let formatter = Formatter::new().output_tz(args.output_tz);
+    let events = [
+        (
+            Horizon::Astronomical,
+            "astronomical_dawn",
+            "astronomical_dusk",
+        ),
+        (Horizon::Nautical, "nautical_dawn", "nautical_dusk"),
+        (Horizon::Civil, "civil_dawn", "civil_dusk"),
+        (Horizon::Sunrise, "sunrise", "sunset"),
+    ];
+    // Each event's time, or why there is none; mornings in order, then evenings.
+    let mut dawns = Vec::new();
+    let mut dusks = Vec::new();
+    for (horizon, dawn, dusk) in events {
+        let (rise, set) = match day.crossing(horizon) {
+            Crossing::Between { rise, set } => (Ok(rise), Ok(set)),
+            Crossing::AlwaysAbove => (Err("sun above all day"), Err("sun above all day")),
+            Crossing::AlwaysBelow => (Err("sun below all day"), Err("sun below all day")),
+        };
+        dawns.push((dawn, rise));
+        dusks.push((dusk, set));
+    }
+    let rows: Vec<_> = dawns
+        .into_iter()
+        .chain([("solar_noon", Ok(day.noon()))])
+        .chain(dusks.into_iter().rev())
+        .collect();
+    let day_length = day.day_length();
+    let polar = match day.crossing(Horizon::Sunrise) {
+        Crossing::Between { .. } => None,
+        Crossing::AlwaysAbove => Some("day"),
+        Crossing::AlwaysBelow => Some("night"),
+    };
 
     if args.json {
-        let (parsed_as_str, ts_unit_str) = match parsed_as {
-            ParsedAs::Timestamp { unit, .. } => (
-                "timestamp",
-                Some(match unit {
-                    TsUnit::Seconds => "seconds",
-                    TsUnit::Millis => "millis",
-                }),
-            ),
-            ParsedAs::Formatted => ("formatted", None),
+        let mut obj = json!({
+            "date": date.to_string(),
+            "latitude": args.lat,
+            "longitude": args.lon,
+            "day_length_seconds": day_length.num_seconds(),
+            "day_length": duration::format_human(day_length, None),
+            "polar": polar,
+        });
+        for (name, time) in &rows {
+            obj[*name] = json!(time.ok().map(|t| formatter.format(t)));
+        }
+        println!("{}", serde_json::to_string_pretty(&obj).unwrap());
+        return;
+    }
+    for (name, time) in &rows {
+        let time = match time {
+            Ok(time) => formatter.format(*time),
+            Err(why) => format!("none ({why})"),
         };
+        println!("{:<18} {time}", name.replace('_', " "));
+    }
+    let day_length = duration::format_human(day_length, None);
+    match polar {
+        Some(polar) => println!("{:<18} {day_length} (polar {polar})", "day length"),
+        None => println!("{:<18} {day_length}", "day length"),
+    }
+}
+
+/// Outputs `epoch schema` can describe.
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum SchemaOf {
+    /// The --json result (and each NDJSON line of batch output)
+    Json,
+}
+
+fn run_schema(output: SchemaOf, version: SchemaVersion) {
+    let schema = match output {
+        SchemaOf::Json => epoch::instant_json_schema(version),
+    };
+    println!("{}", serde_json::to_string_pretty(&schema).unwrap());
+}
+
+/// A bare `YYYY-MM-DD` / `YYYY/MM/DD` date, or the --input-tz calendar date of any INPUT.
+fn date_or_die(input: &str, opts: &ParseOpts) -> NaiveDate {
+    if let Some(date) = ["%Y-%m-%d", "%Y/%m/%d"]
+        .iter()
+        .find_map(|fmt| NaiveDate::parse_from_str(input, fmt).ok())
+    {
+        return date;
+    }
+    let (utc_dt, _) = parse_or_die(input, opts);
+    opts.input_tz.naive_local(utc_dt).date()
+}
 
-        let rfc3339_out = format_output(utc_dt, args.output_tz, None);
+fn run_age(args: AgeArgs) {
+    let from = date_or_die(&args.from, &args.parse);
+    let to = match &args.to {
+        Some(to) => date_or_die(to, &args.parse),
+        None => args.parse.input_tz.naive_local(Utc::now()).date(),
+    };
+    let age = age::age_between(from, to);
 
+    if args.json {
         let obj = json!({
-            "schema_version": 1,
-            "input": args.input,
-            "parsed_as": parsed_as_str,
-            "ts_unit": ts_unit_str,
-            "input_tz": args.input_tz.as_str(),
-            "output_tz": args.output_tz.as_str(),
-            "unix_seconds": unix_seconds,
-            "unix_millis": unix_millis,
-            "rfc3339": rfc3339_out
+            "from": from.to_string(),
+            "to": to.to_string(),
+            "years": age.years,
+            "months": age.months,
+            "days": age.days,
+            "total_days": (to - from).num_days(),
         });
-
         println!("{}", serde_json::to_string_pretty(&obj).unwrap());
         return;
     }
 
-    // Default: single-line string output (RFC3339 unless --format provided)
-    let out = format_output(utc_dt, args.output_tz, args.format.as_deref());
-    println!("{out}");
+    println!(
+        "{}, {}, {}",
+        duration::plural(age.years, "year"),
+        duration::plural(age.months, "month"),
+        duration::plural(age.days, "day")
+    );
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use chrono::TimeZone;
+/// Open --input-file, or stdin without one.
+fn open_input(path: Option<&Path>) -> (Box<dyn Read>, Option<u64>) {
+    match path {
+        Some(path) => match File::open(path) {
+            Ok(file) => {
+                let size = file.metadata().ok().map(|m| m.len());
+                (Box::new(file), size)
+            }
+            Err(e) => die(2, format!("Error: {}: {e}", path.display())),
+        },
+        None => (Box::new(io::stdin().lock()), None),
+    }
+}
 
-    #[test]
-    fn parses_seconds_timestamp() {
-        let (dt, parsed_as) = parse_input_to_utc("1700000000", TzChoice::Utc, None).unwrap();
-        assert_eq!(dt.timestamp(), 1_700_000_000);
-
-        match parsed_as {
-            ParsedAs::Timestamp { unit, raw } => {
-                assert_eq!(raw, 1_700_000_000);
-                assert!(matches!(unit, TsUnit::Seconds));
+/// Parse every non-blank line of the stream, handing each line and its instant to `f` as
+/// they are read. Lines that fail are reported on stderr and skipped; returns the exit
+/// code of the first failure.
+fn each_instant(args: &StreamArgs, mut f: impl FnMut(&str, DateTime<Utc>)) -> Option<i32> {
+    let parser = args
+        .parse
+        .parser()
+        .input_tz(args.parse.input_tz.pin_local());
+    let (source, _) = open_input(args.input_file.as_deref());
+    let mut failed = None;
+    for (i, line) in BufReader::new(source).lines().enumerate() {
+        let line = line.unwrap_or_else(|e| die(2, format!("Error: {e}")));
+        let journal = args.parse.encoding == Some(Encoding::Journal);
+        if line.trim().is_empty() || journal && is_other_journal_field(line.trim()) {
+            continue;
+        }
+        match parser.parse(line.trim()) {
+            Ok((dt, _)) => f(line.trim(), dt),
+            Err(e) => {
+                eprintln!("line {}: Error: {}", i + 1, message(&e));
+                failed.get_or_insert(exit_code(&e));
             }
-            _ => panic!("expected timestamp parse"),
         }
     }
+    failed
+}
 
-    #[test]
-    fn parses_millis_timestamp_autodetect() {
-        let (dt, parsed_as) = parse_input_to_utc("1700000000123", TzChoice::Utc, None).unwrap();
-        assert_eq!(dt.timestamp(), 1_700_000_000);
-        assert_eq!(dt.timestamp_millis(), 1_700_000_000_123);
-
-        match parsed_as {
-            ParsedAs::Timestamp { unit, .. } => assert!(matches!(unit, TsUnit::Millis)),
-            _ => panic!("expected timestamp parse"),
+fn run_summary(args: SummaryArgs) {
+    let mut instants = Vec::new();
+    let failed = each_instant(&args.stream, |_, dt| instants.push(dt));
+    let rfc3339 = Formatter::new().output_tz(args.output_tz);
+    let summary = summary::summarize(&mut instants);
+    let secs = |d: Option<chrono::Duration>| d.map(|d| d.num_milliseconds() as f64 / 1000.0);
+
+    if args.json {
+        let obj = match &summary {
+            Some(s) => json!({
+                "count": s.count,
+                "earliest": rfc3339.format(s.earliest),
+                "latest": rfc3339.format(s.latest),
+                "median": rfc3339.format(s.median),
+                "gap_p90_seconds": secs(s.gap_p90),
+                "gap_p99_seconds": secs(s.gap_p99),
+                "events_per_minute": s.events_per_minute,
+            }),
+            None => json!({ "count": 0 }),
+        };
+        println!("{}", serde_json::to_string_pretty(&obj).unwrap());
+    } else if let Some(s) = &summary {
+        let gap = |d: Option<chrono::Duration>| {
+            d.map_or("-".to_string(), |d| format!("{}s", fmt_seconds(d)))
+        };
+        println!("count:       {}", s.count);
+        println!("earliest:    {}", rfc3339.format(s.earliest));
+        println!("latest:      {}", rfc3339.format(s.latest));
+        println!("median:      {}", rfc3339.format(s.median));
+        println!("gap p90:     {}", gap(s.gap_p90));
+        println!("gap p99:     {}", gap(s.gap_p99));
+        println!(
+            "per minute:  {}",
+            s.events_per_minute
+                .map_or("-".to_string(), |r| format!("{r:.2}"))
+        );
+    } else {
+        println!("count:       0");
+    }
+
+    if let Some(code) = failed {
+        std::process::exit(code);
+    }
+}
+
+fn run_uniq(args: UniqArgs) {
+    let tz = args.output_tz.pin_local();
+    let rfc3339 = Formatter::new().output_tz(args.output_tz);
+    let mut seen = HashSet::new();
+    let mut stdout = io::BufWriter::new(io::stdout().lock());
+    let failed = each_instant(&args.stream, |line, dt| {
+        let start = period::period_start(dt, args.by, tz, args.week_start);
+        if seen.insert(start) {
+            let shown = if args.starts {
+                rfc3339.format(start)
+            } else {
+                line.to_string()
+            };
+            writeln!(stdout, "{shown}").unwrap_or_else(|e| die(2, format!("Error: {e}")));
         }
+    });
+    drop(stdout);
+
+    if let Some(code) = failed {
+        std::process::exit(code);
     }
+}
 
-    #[test]
-    fn parses_millis_timestamp_forced() {
-        let (dt, parsed_as) =
-            parse_input_to_utc("1700000000", TzChoice::Utc, Some(TsUnit::Millis)).unwrap();
-        assert_eq!(dt.timestamp_millis(), 1_700_000_000);
-
-        match parsed_as {
-            ParsedAs::Timestamp { unit, .. } => assert!(matches!(unit, TsUnit::Millis)),
-            _ => panic!("expected timestamp parse"),
+fn run_group(args: GroupArgs) {
+    let tz = args.output_tz.pin_local();
+    let rfc3339 = Formatter::new().output_tz(args.output_tz);
+    let mut counts: BTreeMap<DateTime<Utc>, u64> = BTreeMap::new();
+    let failed = each_instant(&args.stream, |_, dt| {
+        *counts
+            .entry(period::period_start(dt, args.by, tz, args.week_start))
+            .or_default() += 1;
+    });
+
+    if args.json {
+        let rows: Vec<_> = counts
+            .iter()
+            .map(|(start, count)| json!({ "period_start": rfc3339.format(*start), "count": count }))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&rows).unwrap());
+    } else {
+        println!("period_start,count");
+        for (start, count) in &counts {
+            println!("{},{count}", rfc3339.format(*start));
         }
     }
 
-    #[test]
-    fn parses_formatted_datetime_as_utc_when_input_tz_utc() {
-        let (dt, parsed_as) =
-            parse_input_to_utc("2025/12/20 11:10:11", TzChoice::Utc, None).unwrap();
+    if let Some(code) = failed {
+        std::process::exit(code);
+    }
+}
 
-        let expected = Utc.with_ymd_and_hms(2025, 12, 20, 11, 10, 11).unwrap();
-        assert_eq!(dt, expected);
+/// One NDJSON object per timestamp found; `start`/`end` are byte offsets in the line.
+fn run_scan(args: ScanArgs) {
+    let parse = &args.stream.parse;
+    let scanner = Scanner::new(parse.parser().input_tz(parse.input_tz.pin_local()));
+    let rfc3339 = Formatter::new().output_tz(args.output_tz);
+    let (source, _) = open_input(args.stream.input_file.as_deref());
 
-        assert!(matches!(parsed_as, ParsedAs::Formatted));
+    let mut stdout = io::BufWriter::new(io::stdout().lock());
+    for (i, line) in BufReader::new(source).lines().enumerate() {
+        let line = line.unwrap_or_else(|e| die(2, format!("Error: {e}")));
+        for found in scanner.scan(&line) {
+            let obj = json!({
+                "line": i + 1,
+                "start": found.start,
+                "end": found.end,
+                "match": found.text,
+                "kind": found.kind,
+                "unix_seconds": found.utc.timestamp(),
+                "unix_millis": found.utc.timestamp_millis(),
+                "unix_nanos": found.utc.timestamp_nanos_opt(),
+                "rfc3339": rfc3339.format(found.utc),
+            });
+            writeln!(stdout, "{obj}").unwrap_or_else(|e| die(2, format!("Error: {e}")));
+        }
     }
+}
 
-    #[test]
-    fn rejects_unknown_format() {
-        let err = parse_input_to_utc("2025-12-20 11:10:11", TzChoice::Utc, None).unwrap_err();
-        assert_eq!(err.0, EXIT_PARSE);
+#[cfg(feature = "tui")]
+fn run_tui(args: TuiArgs) {
+    let parse = |s: &str| {
+        args.parse
+            .parser()
+            .parse(s)
+            .map(|(dt, _)| dt)
+            .map_err(|e| e.to_string())
+    };
+    if let Err(e) = tui::run(&args.zones, &parse) {
+        die(2, format!("Error: {e}"));
     }
+}
 
-    #[test]
-    fn formats_default_rfc3339_utc() {
-        let dt = Utc.with_ymd_and_hms(2025, 12, 20, 11, 10, 11).unwrap();
-        let out = format_output(dt, TzChoice::Utc, None);
-        assert!(out.starts_with("2025-12-20T11:10:11"));
+fn main() {
+    let args = Args::parse();
+    let output = match &args.command {
+        None => Some(&args.output),
+        Some(Command::Add(args) | Command::Sub(args)) => Some(&args.output),
+        Some(Command::IcsRead(args)) => Some(&args.output),
+        Some(Command::Next(args) | Command::Prev(args)) => Some(&args.output),
+        Some(Command::Nth(args)) => Some(&args.output),
+        Some(_) => None,
+    };
+    if let Some(output) = output {
+        output.check_clamp();
+    }
+
+    if let Some(command) = args.command {
+        match command {
+            Command::Tz(command) => run_tz(command),
+            Command::Add(args) => run_arith(args, false),
+            Command::Sub(args) => run_arith(args, true),
+            Command::IsHoliday(args) => run_is_holiday(args),
+            Command::Diff(args) => run_diff(args),
+            Command::Compare(args) => run_compare(args),
+            Command::Ics(args) => run_ics(args),
+            Command::IcsRead(args) => run_ics_read(args),
+            Command::Age(args) => run_age(args),
+            Command::Summary(args) => run_summary(args),
+            Command::Uniq(args) => run_uniq(args),
+            Command::Group(args) => run_group(args),
+            Command::Scan(args) => run_scan(args),
+            Command::Explain(args) => run_explain(args),
+            Command::FmtConvert(args) => run_fmt_convert(args),
+            Command::Formats { json } => run_formats(json),
+            Command::Schema { output, schema } => run_schema(output, schema),
+            #[cfg(feature = "tui")]
+            Command::Tui(args) => run_tui(args),
+            Command::Daemon => {
+                if let Err(e) = daemon::run() {
+                    die(2, format!("Error: {e}"));
+                }
+            }
+            Command::Serve { listen } => {
+                if let Err(e) = serve::run(&listen) {
+                    die(2, format!("Error: {e}"));
+                }
+            }
+            Command::DstNext(args) => run_dst(args, true),
+            Command::DstPrev(args) => run_dst(args, false),
+            Command::Milestones(args) => run_milestones(args),
+            Command::Moon(args) => run_moon(args),
+            Command::Sun(args) => run_sun(args),
+            Command::Next(args) => run_weekday(args, true),
+            Command::Prev(args) => run_weekday(args, false),
+            Command::Nth(args) => run_nth(args),
+            Command::Easter(args) => run_easter(args),
+            Command::DaysIn(args) => run_days_in(args),
+            Command::Is(command) => run_is(command),
+        }
+        return;
+    }
+    if args.batch.input_file.is_some() || args.input.as_deref() == Some("-") {
+        run_batch(&args.batch, &args.parse, &args.output);
+        return;
     }
+    let input = match args.input {
+        Some(input) => input,
+        None => clipboard::read().unwrap_or_else(|e| die(2, format!("Error: {e}"))),
+    };
+
+    let (utc_dt, parsed_as) = parse_or_die(&input, &args.parse);
+    print_instant(
+        &input,
+        utc_dt,
+        &parsed_as,
+        args.parse.input_tz,
+        &args.output,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     #[test]
-    fn formats_custom_format_utc() {
-        let dt = Utc.with_ymd_and_hms(2025, 12, 20, 11, 10, 11).unwrap();
-        let out = format_output(dt, TzChoice::Utc, Some("%Y/%m/%d %H:%M:%S"));
-        assert_eq!(out, "2025/12/20 11:10:11");
+    fn describes_clock_changes() {
+        use chrono::Duration;
+        assert_eq!(describe_change(Duration::hours(1)), "clocks go forward 1h");
+        assert_eq!(
+            describe_change(Duration::minutes(-30)),
+            "clocks go back 30m"
+        );
+        assert_eq!(
+            describe_change(Duration::minutes(90)),
+            "clocks go forward 1h30m"
+        );
+        assert_eq!(describe_change(Duration::zero()), "offset unchanged");
     }
 }
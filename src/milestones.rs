@@ -0,0 +1,116 @@
+//! Round and curious Unix timestamps worth a countdown: `1800000000`, `2^31`, `2222222222`.
+
+use chrono::{DateTime, Utc};
+
+/// A notable Unix time.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Milestone {
+    pub unix_seconds: i64,
+    /// Why it is notable, e.g. `2^31: signed 32-bit time_t overflows (Y2038)`.
+    pub reason: String,
+}
+
+impl Milestone {
+    pub fn at(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(self.unix_seconds, 0)
+    }
+}
+
+/// Every value the crate knows to be notable, up to eleven digits (the year 5138).
+fn candidates() -> Vec<(i64, String)> {
+    let mut found = Vec::new();
+    for n in 1..=999 {
+        let value = n * 100_000_000;
+        let reason = if n % 10 == 0 {
+            format!("{} billion", n / 10)
+        } else {
+            format!("{}.{} billion", n / 10, n % 10)
+        };
+        found.push((value, reason));
+    }
+    for (power, reason) in [
+        (30, "2^30"),
+        (31, "2^31: signed 32-bit time_t overflows (Y2038)"),
+        (32, "2^32: unsigned 32-bit time overflows (Y2106)"),
+        (33, "2^33"),
+        (34, "2^34"),
+        (35, "2^35"),
+        (36, "2^36"),
+    ] {
+        found.push((1 << power, reason.to_string()));
+    }
+    for digits in [10, 11] {
+        for d in 1..=9 {
+            let value = d.to_string().repeat(digits).parse().expect("repdigit");
+            found.push((value, "repdigit".to_string()));
+        }
+    }
+    found.push((1_234_567_890, "ascending digits".to_string()));
+    found.push((9_876_543_210, "descending digits".to_string()));
+    found
+}
+
+/// The first `count` milestones after `after`, earliest first.
+pub fn upcoming(after: DateTime<Utc>, count: usize) -> Vec<Milestone> {
+    let mut found = candidates();
+    found.retain(|(value, _)| *value > after.timestamp());
+    found.sort_by_key(|(value, _)| *value);
+    found
+        .into_iter()
+        .take(count)
+        .map(|(unix_seconds, reason)| Milestone {
+            unix_seconds,
+            reason,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_upcoming_values_in_order() {
+        let after = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let found = upcoming(after, 6);
+        let values: Vec<i64> = found.iter().map(|m| m.unix_seconds).collect();
+        assert_eq!(
+            values,
+            [
+                1_800_000_000,
+                1_900_000_000,
+                2_000_000_000,
+                2_100_000_000,
+                2_147_483_648,
+                2_200_000_000
+            ]
+        );
+        assert_eq!(found[0].reason, "1.8 billion");
+        assert_eq!(found[2].reason, "2 billion");
+        assert_eq!(
+            found[0].at().unwrap().to_rfc3339(),
+            "2027-01-15T08:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn includes_the_32_bit_limits() {
+        let after = DateTime::from_timestamp(2_100_000_000, 0).unwrap();
+        let found = upcoming(after, 3);
+        assert_eq!(found[0].unix_seconds, 2_147_483_648);
+        assert!(found[0].reason.contains("Y2038"));
+        assert_eq!(
+            (found[2].unix_seconds, &*found[2].reason),
+            (2_222_222_222, "repdigit")
+        );
+        assert_eq!(upcoming(after, 0), []);
+    }
+
+    #[test]
+    fn counts_from_any_instant() {
+        let after = DateTime::from_timestamp(1_200_000_000, 0).unwrap();
+        assert_eq!(upcoming(after, 1)[0].reason, "ascending digits");
+        let after = DateTime::from_timestamp(99_950_000_000, 0).unwrap();
+        assert_eq!(upcoming(after, 5).len(), 1);
+    }
+}
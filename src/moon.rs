@@ -0,0 +1,135 @@
+//! The Moon's phase and illuminated fraction at an instant, for `epoch moon`.
+//!
+//! Uses the low-precision series of Meeus, *Astronomical Algorithms* ch. 48, good to
+//! well under a percent of illumination, which is plenty for naming the phase.
+
+use chrono::{DateTime, Utc};
+
+/// Mean length of a lunation, new moon to new moon, in days.
+pub const SYNODIC_MONTH_DAYS: f64 = 29.530_588_853;
+
+/// Julian day of the Unix epoch.
+const UNIX_EPOCH_JD: f64 = 2_440_587.5;
+
+/// Julian day of J2000.0, 2000-01-01T12:00:00 (TT, which the phase cannot tell from UTC).
+const J2000_JD: f64 = 2_451_545.0;
+
+/// The eight traditional phases, each spanning 45° of elongation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    NewMoon,
+    WaxingCrescent,
+    FirstQuarter,
+    WaxingGibbous,
+    FullMoon,
+    WaningGibbous,
+    LastQuarter,
+    WaningCrescent,
+}
+
+impl Phase {
+    const ALL: [Phase; 8] = [
+        Phase::NewMoon,
+        Phase::WaxingCrescent,
+        Phase::FirstQuarter,
+        Phase::WaxingGibbous,
+        Phase::FullMoon,
+        Phase::WaningGibbous,
+        Phase::LastQuarter,
+        Phase::WaningCrescent,
+    ];
+
+    /// `Waxing Crescent`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::NewMoon => "New Moon",
+            Phase::WaxingCrescent => "Waxing Crescent",
+            Phase::FirstQuarter => "First Quarter",
+            Phase::WaxingGibbous => "Waxing Gibbous",
+            Phase::FullMoon => "Full Moon",
+            Phase::WaningGibbous => "Waning Gibbous",
+            Phase::LastQuarter => "Last Quarter",
+            Phase::WaningCrescent => "Waning Crescent",
+        }
+    }
+}
+
+/// The Moon as seen at one instant.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MoonPhase {
+    pub phase: Phase,
+    /// Fraction of the disc lit, 0 (new) to 1 (full).
+    pub illumination: f64,
+    /// Angle from the Sun to the Moon along the ecliptic, 0-360°: 0 at new moon, 180 at
+    /// full, below 180 while waxing.
+    pub elongation: f64,
+    /// Days since the last new moon, 0 to about 29.5, going by the mean lunation.
+    pub age_days: f64,
+}
+
+impl MoonPhase {
+    pub fn waxing(&self) -> bool {
+        self.elongation < 180.0
+    }
+}
+
+/// The phase of the Moon at `dt`.
+pub fn phase_at(dt: DateTime<Utc>) -> MoonPhase {
+    let jd = dt.timestamp_millis() as f64 / 86_400_000.0 + UNIX_EPOCH_JD;
+    let t = (jd - J2000_JD) / 36_525.0;
+    // Mean elongation, and the Sun's and Moon's mean anomalies (Meeus 47.2-47.4).
+    let d = 297.850_192_1 + 445_267.111_403_4 * t;
+    let m = 357.529_109_2 + 35_999.050_290_9 * t;
+    let m_moon = 134.963_396_4 + 477_198.867_505_5 * t;
+    let sin = |deg: f64| deg.to_radians().sin();
+    // Phase angle, Sun-Moon-Earth (Meeus 48.4).
+    let phase_angle = 180.0 - d - 6.289 * sin(m_moon) + 2.100 * sin(m)
+        - 1.274 * sin(2.0 * d - m_moon)
+        - 0.658 * sin(2.0 * d)
+        - 0.214 * sin(2.0 * m_moon)
+        - 0.110 * sin(d);
+    let elongation = (180.0 - phase_angle).rem_euclid(360.0);
+    let sector = (elongation / 45.0).round() as usize % 8;
+    MoonPhase {
+        phase: Phase::ALL[sector],
+        illumination: (1.0 + phase_angle.to_radians().cos()) / 2.0,
+        elongation,
+        age_days: elongation / 360.0 * SYNODIC_MONTH_DAYS,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(rfc3339: &str) -> MoonPhase {
+        phase_at(DateTime::parse_from_rfc3339(rfc3339).unwrap().to_utc())
+    }
+
+    #[test]
+    fn names_the_principal_phases() {
+        let new = at("2024-04-08T18:21:00Z");
+        assert_eq!(new.phase, Phase::NewMoon);
+        assert!(new.illumination < 0.001, "{new:?}");
+        assert!(new.age_days < 0.2 || new.age_days > 29.3, "{new:?}");
+
+        let full = at("2023-11-27T09:16:00Z");
+        assert_eq!(full.phase, Phase::FullMoon);
+        assert!(full.illumination > 0.999, "{full:?}");
+
+        let quarter = at("2023-11-20T10:50:00Z");
+        assert_eq!(quarter.phase, Phase::FirstQuarter);
+        assert!((quarter.illumination - 0.5).abs() < 0.01, "{quarter:?}");
+        assert!(quarter.waxing());
+    }
+
+    #[test]
+    fn tells_waxing_from_waning() {
+        let crescent = at("2023-11-16T00:00:00Z");
+        assert_eq!(crescent.phase, Phase::WaxingCrescent);
+        let waning = at("2023-12-01T00:00:00Z");
+        assert_eq!(waning.phase, Phase::WaningGibbous);
+        assert!(!waning.waxing());
+        assert_eq!(at("2023-12-05T05:49:00Z").phase, Phase::LastQuarter);
+    }
+}
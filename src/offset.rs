@@ -0,0 +1,224 @@
+//! Explicit zone designators attached to formatted input, resolved to fixed UTC offsets.
+
+use chrono::FixedOffset;
+
+use crate::EpochError;
+
+/// A zone abbreviation and the UTC offset it stands for.
+struct Abbreviation {
+    abbr: &'static str,
+    /// Offset east of UTC, in minutes.
+    offset_minutes: i32,
+    /// Region code used by `--prefer-zone` to pick between clashing abbreviations.
+    region: &'static str,
+}
+
+const fn abbr(abbr: &'static str, offset_minutes: i32, region: &'static str) -> Abbreviation {
+    Abbreviation {
+        abbr,
+        offset_minutes,
+        region,
+    }
+}
+
+/// Commonly seen zone abbreviations. Several abbreviations are used by more than one
+/// region (CST, IST, BST, AST); those need `--prefer-zone` to be resolved.
+const ABBREVIATIONS: &[Abbreviation] = &[
+    abbr("UTC", 0, "utc"),
+    abbr("GMT", 0, "utc"),
+    abbr("UT", 0, "utc"),
+    // North America
+    abbr("EST", -5 * 60, "us"),
+    abbr("EDT", -4 * 60, "us"),
+    abbr("CST", -6 * 60, "us"),
+    abbr("CDT", -5 * 60, "us"),
+    abbr("MST", -7 * 60, "us"),
+    abbr("MDT", -6 * 60, "us"),
+    abbr("PST", -8 * 60, "us"),
+    abbr("PDT", -7 * 60, "us"),
+    abbr("AKST", -9 * 60, "us"),
+    abbr("AKDT", -8 * 60, "us"),
+    abbr("HST", -10 * 60, "us"),
+    abbr("AST", -4 * 60, "ca"),
+    abbr("ADT", -3 * 60, "ca"),
+    abbr("NST", -(3 * 60 + 30), "ca"),
+    abbr("NDT", -(2 * 60 + 30), "ca"),
+    abbr("CST", -5 * 60, "cu"),
+    // South America
+    abbr("BRT", -3 * 60, "br"),
+    abbr("ART", -3 * 60, "ar"),
+    // Europe
+    abbr("WET", 0, "eu"),
+    abbr("WEST", 60, "eu"),
+    abbr("BST", 60, "gb"),
+    abbr("IST", 60, "ie"),
+    abbr("CET", 60, "eu"),
+    abbr("CEST", 2 * 60, "eu"),
+    abbr("EET", 2 * 60, "eu"),
+    abbr("EEST", 3 * 60, "eu"),
+    abbr("MSK", 3 * 60, "ru"),
+    // Africa
+    abbr("WAT", 60, "af"),
+    abbr("CAT", 2 * 60, "af"),
+    abbr("SAST", 2 * 60, "za"),
+    abbr("EAT", 3 * 60, "af"),
+    // Asia
+    abbr("IST", 2 * 60, "il"),
+    abbr("IDT", 3 * 60, "il"),
+    abbr("AST", 3 * 60, "sa"),
+    abbr("GST", 4 * 60, "ae"),
+    abbr("PKT", 5 * 60, "pk"),
+    abbr("IST", 5 * 60 + 30, "in"),
+    abbr("BST", 6 * 60, "bd"),
+    abbr("WIB", 7 * 60, "id"),
+    abbr("CST", 8 * 60, "cn"),
+    abbr("HKT", 8 * 60, "hk"),
+    abbr("SGT", 8 * 60, "sg"),
+    abbr("PHT", 8 * 60, "ph"),
+    abbr("JST", 9 * 60, "jp"),
+    abbr("KST", 9 * 60, "kr"),
+    // Oceania
+    abbr("AWST", 8 * 60, "au"),
+    abbr("ACST", 9 * 60 + 30, "au"),
+    abbr("ACDT", 10 * 60 + 30, "au"),
+    abbr("AEST", 10 * 60, "au"),
+    abbr("AEDT", 11 * 60, "au"),
+    abbr("NZST", 12 * 60, "nz"),
+    abbr("NZDT", 13 * 60, "nz"),
+];
+
+/// Whether `s` has the shape of a zone abbreviation (2-5 ASCII letters).
+pub fn looks_like_abbreviation(s: &str) -> bool {
+    (2..=5).contains(&s.len()) && s.bytes().all(|b| b.is_ascii_alphabetic())
+}
+
+/// Resolve a zone abbreviation (case-insensitive) to its UTC offset.
+///
+/// `prefer` lists region codes, in order of preference, used when the abbreviation is
+/// claimed by more than one region.
+pub fn resolve_abbreviation(abbr: &str, prefer: &[String]) -> Result<FixedOffset, EpochError> {
+    let candidates: Vec<&Abbreviation> = ABBREVIATIONS
+        .iter()
+        .filter(|a| a.abbr.eq_ignore_ascii_case(abbr))
+        .collect();
+
+    let chosen = match candidates.as_slice() {
+        [] => return Err(EpochError::UnknownTimezone(abbr.to_string())),
+        [only] => *only,
+        _ => prefer
+            .iter()
+            .find_map(|p| candidates.iter().find(|a| a.region.eq_ignore_ascii_case(p)))
+            .copied()
+            .ok_or_else(|| EpochError::AmbiguousAbbreviation {
+                abbreviation: abbr.to_ascii_uppercase(),
+                candidates: candidates
+                    .iter()
+                    .map(|a| format!("{} ({})", a.region, fmt_offset(a.offset_minutes)))
+                    .collect(),
+            })?,
+    };
+
+    Ok(FixedOffset::east_opt(chosen.offset_minutes * 60).expect("table offsets are in range"))
+}
+
+/// Parse a numeric UTC offset: `+HH`, `+HHMM` or `+HH:MM` (or with `-`).
+pub fn parse_numeric_offset(s: &str) -> Option<FixedOffset> {
+    let (sign, digits) = match s.as_bytes().first()? {
+        b'+' => (1, &s[1..]),
+        b'-' => (-1, &s[1..]),
+        _ => return None,
+    };
+
+    let (hh, mm) = match digits.len() {
+        2 => (digits, "00"),
+        4 => digits.split_at(2),
+        5 if digits.as_bytes()[2] == b':' => (&digits[..2], &digits[3..]),
+        _ => return None,
+    };
+    if !hh.bytes().chain(mm.bytes()).all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let (hh, mm): (i32, i32) = (hh.parse().ok()?, mm.parse().ok()?);
+    if hh > 23 || mm > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hh * 3600 + mm * 60))
+}
+
+/// Resolve a military (NATO) single-letter zone: `Z` is UTC, `A`-`M` (skipping `J`) are
+/// +1..+12 hours and `N`-`Y` are -1..-12 hours.
+///
+/// `J` ("Juliet") denotes the observer's local time rather than a fixed offset, so it
+/// returns `None` like any other non-zone letter; callers handle it explicitly.
+pub fn military_offset(letter: char) -> Option<FixedOffset> {
+    let hours = match letter.to_ascii_uppercase() {
+        'Z' => 0,
+        c @ 'A'..='I' => c as i32 - 'A' as i32 + 1,
+        c @ 'K'..='M' => c as i32 - 'K' as i32 + 10,
+        c @ 'N'..='Y' => -(c as i32 - 'N' as i32 + 1),
+        _ => return None,
+    };
+    FixedOffset::east_opt(hours * 3600)
+}
+
+fn fmt_offset(minutes: i32) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let m = minutes.abs();
+    format!("{sign}{:02}:{:02}", m / 60, m % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_unique_abbreviation() {
+        let off = resolve_abbreviation("est", &[]).unwrap();
+        assert_eq!(off.local_minus_utc(), -5 * 3600);
+    }
+
+    #[test]
+    fn clashing_abbreviation_needs_preference() {
+        let err = resolve_abbreviation("IST", &[]).unwrap_err().to_string();
+        assert!(
+            err.starts_with("Ambiguous timezone abbreviation IST: "),
+            "{err}"
+        );
+        assert!(err.contains("in (+05:30)"), "{err}");
+
+        let prefer = vec!["ie".to_string(), "in".to_string()];
+        let off = resolve_abbreviation("IST", &prefer).unwrap();
+        assert_eq!(off.local_minus_utc(), 3600);
+    }
+
+    #[test]
+    fn parses_numeric_offsets() {
+        let secs = |s| parse_numeric_offset(s).map(|o| o.local_minus_utc());
+        assert_eq!(secs("+05:30"), Some(5 * 3600 + 30 * 60));
+        assert_eq!(secs("+0530"), Some(5 * 3600 + 30 * 60));
+        assert_eq!(secs("-07"), Some(-7 * 3600));
+        assert_eq!(secs("-00:00"), Some(0));
+        assert_eq!(secs("+24:00"), None);
+        assert_eq!(secs("+5:30"), None);
+        assert_eq!(secs("0530"), None);
+    }
+
+    #[test]
+    fn resolves_military_letters() {
+        let hours = |c| military_offset(c).map(|o| o.local_minus_utc() / 3600);
+        assert_eq!(hours('Z'), Some(0));
+        assert_eq!(hours('A'), Some(1));
+        assert_eq!(hours('I'), Some(9));
+        assert_eq!(hours('K'), Some(10));
+        assert_eq!(hours('m'), Some(12));
+        assert_eq!(hours('N'), Some(-1));
+        assert_eq!(hours('Y'), Some(-12));
+        assert_eq!(hours('J'), None);
+    }
+
+    #[test]
+    fn rejects_unknown_abbreviation() {
+        assert!(resolve_abbreviation("XYZ", &[]).is_err());
+    }
+}
@@ -0,0 +1,249 @@
+//! Calendar periods and the bucket an instant falls into, as used by `uniq`, `group` and
+//! `--start-of` / `--end-of`.
+
+use chrono::{
+    DateTime, Datelike, Duration, Local, Months, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc,
+    Weekday,
+};
+
+use crate::zone::TzChoice;
+
+/// A bucket size. Weeks start on the day callers pass in: Monday for ISO 8601, Sunday in
+/// the US, Saturday in much of the Middle East.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Period {
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Quarter,
+    Year,
+}
+
+/// Start of the period containing a wall-clock time, with weeks starting on `week_start`.
+pub fn truncate(local: NaiveDateTime, period: Period, week_start: Weekday) -> NaiveDateTime {
+    let date = local.date();
+    let ymd = |y, m, d| {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .expect("first day of a month")
+            .and_time(Default::default())
+    };
+    match period {
+        Period::Minute => local.with_second(0).unwrap().with_nanosecond(0).unwrap(),
+        Period::Hour => date.and_hms_opt(local.hour(), 0, 0).unwrap(),
+        Period::Day => date.and_time(Default::default()),
+        Period::Week => {
+            let days = days_into_week(date, week_start);
+            (date - chrono::Days::new(u64::from(days))).and_time(Default::default())
+        }
+        Period::Month => ymd(date.year(), date.month(), 1),
+        Period::Quarter => ymd(date.year(), date.month0() / 3 * 3 + 1, 1),
+        Period::Year => ymd(date.year(), 1, 1),
+    }
+}
+
+/// Days from the last `week_start` to `date`, 0-6.
+fn days_into_week(date: NaiveDate, week_start: Weekday) -> u32 {
+    (date.weekday().num_days_from_monday() + 7 - week_start.num_days_from_monday()) % 7
+}
+
+/// The week of the year `date` falls in, for weeks starting on `week_start`: week 1 is the
+/// one holding January 1, so the first and last weeks of a year may be short. (ISO 8601
+/// weeks, which start on Monday and give any short first week to the previous year, are
+/// [`Datelike::iso_week`].)
+pub fn week_of_year(date: NaiveDate, week_start: Weekday) -> u32 {
+    let jan1 = date.with_ordinal(1).expect("every year has a first day");
+    (date.ordinal0() + days_into_week(jan1, week_start)) / 7 + 1
+}
+
+/// Start of the period containing `utc`, on the wall clock of `tz`.
+///
+/// Minutes and hours are cut at the offset in force at `utc`, so the repeated hour when
+/// clocks go back stays two buckets. Longer periods start at local midnight, taking the
+/// earlier instant if midnight is repeated and the end of the gap if it is skipped (as
+/// when clocks spring forward at midnight in America/Santiago).
+pub fn period_start(
+    utc: DateTime<Utc>,
+    period: Period,
+    tz: TzChoice,
+    week_start: Weekday,
+) -> DateTime<Utc> {
+    let local = tz.naive_local(utc);
+    let offset = local - utc.naive_utc();
+    let start = truncate(local, period, week_start);
+    let at_offset = Utc.from_utc_datetime(&(start - offset));
+
+    match period {
+        Period::Minute | Period::Hour => at_offset,
+        _ => first_utc_from(start, tz).unwrap_or(at_offset),
+    }
+}
+
+/// Last instant of the period containing `utc`, on the wall clock of `tz`: a nanosecond
+/// before the next period starts, with the same handling of clock changes as
+/// [`period_start`].
+pub fn period_end(
+    utc: DateTime<Utc>,
+    period: Period,
+    tz: TzChoice,
+    week_start: Weekday,
+) -> DateTime<Utc> {
+    let local = tz.naive_local(utc);
+    let offset = local - utc.naive_utc();
+    let start = truncate(local, period, week_start);
+    let months = |n| {
+        start
+            .checked_add_months(Months::new(n))
+            .unwrap_or(NaiveDateTime::MAX)
+    };
+    let next = match period {
+        Period::Minute => start + Duration::minutes(1),
+        Period::Hour => start + Duration::hours(1),
+        Period::Day => start + Duration::days(1),
+        Period::Week => start + Duration::weeks(1),
+        Period::Month => months(1),
+        Period::Quarter => months(3),
+        Period::Year => months(12),
+    };
+    let at_offset = Utc.from_utc_datetime(&(next - offset));
+
+    let next = match period {
+        Period::Minute | Period::Hour => at_offset,
+        _ => first_utc_from(next, tz).unwrap_or(at_offset),
+    };
+    next - Duration::nanoseconds(1)
+}
+
+/// The first instant at or after wall-clock `local` in `tz`: the earlier one if `local`
+/// is repeated, or where the clocks land if it is skipped. Gaps run up to a whole day
+/// (Samoa skipped 2011-12-30), so this looks two days ahead.
+pub(crate) fn first_utc_from(local: NaiveDateTime, tz: TzChoice) -> Option<DateTime<Utc>> {
+    (0..=2 * 24 * 60).find_map(|minutes| earliest_utc(local + Duration::minutes(minutes), tz))
+}
+
+fn earliest_utc(local: NaiveDateTime, tz: TzChoice) -> Option<DateTime<Utc>> {
+    match tz {
+        TzChoice::Utc => Some(Utc.from_utc_datetime(&local)),
+        TzChoice::Local => Local
+            .from_local_datetime(&local)
+            .earliest()
+            .map(|dt| dt.with_timezone(&Utc)),
+        TzChoice::Fixed(off) => off
+            .from_local_datetime(&local)
+            .earliest()
+            .map(|dt| dt.with_timezone(&Utc)),
+        #[cfg(feature = "tz")]
+        TzChoice::Named(tz) | TzChoice::PinnedLocal(tz) => tz
+            .from_local_datetime(&local)
+            .earliest()
+            .map(|dt| dt.with_timezone(&Utc)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().to_utc()
+    }
+
+    #[test]
+    fn truncates_wall_clock_times() {
+        let local = NaiveDate::from_ymd_opt(2024, 8, 15)
+            .unwrap()
+            .and_hms_opt(13, 45, 30)
+            .unwrap();
+        let cut = |p| truncate(local, p, Weekday::Mon).to_string();
+        assert_eq!(cut(Period::Minute), "2024-08-15 13:45:00");
+        assert_eq!(cut(Period::Hour), "2024-08-15 13:00:00");
+        assert_eq!(cut(Period::Day), "2024-08-15 00:00:00");
+        assert_eq!(cut(Period::Week), "2024-08-12 00:00:00");
+        assert_eq!(cut(Period::Month), "2024-08-01 00:00:00");
+        assert_eq!(cut(Period::Quarter), "2024-07-01 00:00:00");
+        assert_eq!(cut(Period::Year), "2024-01-01 00:00:00");
+        // Thursday the 15th: the week began on Sunday the 11th, or Saturday the 10th.
+        assert_eq!(
+            truncate(local, Period::Week, Weekday::Sun).to_string(),
+            "2024-08-11 00:00:00"
+        );
+        assert_eq!(
+            truncate(local, Period::Week, Weekday::Sat).to_string(),
+            "2024-08-10 00:00:00"
+        );
+    }
+
+    #[test]
+    fn numbers_weeks_from_the_one_holding_january_1() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2023, m, d).unwrap();
+        // 2023-01-01 is a Sunday.
+        assert_eq!(week_of_year(date(1, 1), Weekday::Sun), 1);
+        assert_eq!(week_of_year(date(1, 7), Weekday::Sun), 1);
+        assert_eq!(week_of_year(date(1, 8), Weekday::Sun), 2);
+        assert_eq!(week_of_year(date(1, 1), Weekday::Mon), 1);
+        assert_eq!(week_of_year(date(1, 2), Weekday::Mon), 2);
+        assert_eq!(week_of_year(date(1, 7), Weekday::Sat), 2);
+        assert_eq!(week_of_year(date(12, 31), Weekday::Sun), 53);
+    }
+
+    #[test]
+    fn days_start_at_midnight_in_the_zone() {
+        let tz = TzChoice::Fixed(chrono::FixedOffset::east_opt(2 * 3600).unwrap());
+        let start = period_start(utc("2024-08-14T23:30:00Z"), Period::Day, tz, Weekday::Mon);
+        assert_eq!(start, utc("2024-08-14T22:00:00Z"));
+    }
+
+    #[test]
+    fn periods_end_a_nanosecond_before_the_next() {
+        let tz = TzChoice::Fixed(chrono::FixedOffset::east_opt(2 * 3600).unwrap());
+        let at = utc("2024-02-14T23:30:15.5Z");
+        let end = |p| period_end(at, p, tz, Weekday::Mon).to_rfc3339();
+        assert_eq!(end(Period::Minute), "2024-02-14T23:30:59.999999999+00:00");
+        assert_eq!(end(Period::Hour), "2024-02-14T23:59:59.999999999+00:00");
+        // 01:30 on the 15th on the zone's clock.
+        assert_eq!(end(Period::Day), "2024-02-15T21:59:59.999999999+00:00");
+        assert_eq!(end(Period::Week), "2024-02-18T21:59:59.999999999+00:00");
+        assert_eq!(
+            period_end(at, Period::Week, tz, Weekday::Sun).to_rfc3339(),
+            "2024-02-17T21:59:59.999999999+00:00"
+        );
+        assert_eq!(end(Period::Month), "2024-02-29T21:59:59.999999999+00:00");
+        assert_eq!(end(Period::Quarter), "2024-03-31T21:59:59.999999999+00:00");
+        assert_eq!(end(Period::Year), "2024-12-31T21:59:59.999999999+00:00");
+    }
+
+    #[cfg(feature = "tz")]
+    #[test]
+    fn repeated_hour_stays_two_buckets() {
+        let tz: TzChoice = "Europe/Paris".parse().unwrap();
+        // 02:30 local happens twice on 2024-10-27: at 00:30Z (CEST) and 01:30Z (CET).
+        let hour = |at| period_start(utc(at), Period::Hour, tz, Weekday::Mon);
+        let first = hour("2024-10-27T00:30:00Z");
+        let second = hour("2024-10-27T01:30:00Z");
+        assert_eq!(first, utc("2024-10-27T00:00:00Z"));
+        assert_eq!(second, utc("2024-10-27T01:00:00Z"));
+
+        let day = period_start(utc("2024-10-27T12:00:00Z"), Period::Day, tz, Weekday::Mon);
+        assert_eq!(day, utc("2024-10-26T22:00:00Z"));
+        // The 25-hour day ends at the next local midnight, now in CET.
+        let end = period_end(utc("2024-10-27T12:00:00Z"), Period::Day, tz, Weekday::Mon);
+        assert_eq!(end, utc("2024-10-27T22:59:59.999999999Z"));
+    }
+
+    #[cfg(feature = "tz")]
+    #[test]
+    fn skipped_midnight_starts_the_day_when_clocks_land() {
+        let tz: TzChoice = "America/Santiago".parse().unwrap();
+        // Clocks go from 00:00 -04 straight to 01:00 -03 on 2024-09-08.
+        let day = period_start(utc("2024-09-08T21:00:00Z"), Period::Day, tz, Weekday::Mon);
+        assert_eq!(day, utc("2024-09-08T04:00:00Z"));
+        assert_eq!(tz.naive_local(day).to_string(), "2024-09-08 01:00:00");
+        // The day before ends just before that.
+        let end = period_end(utc("2024-09-07T12:00:00Z"), Period::Day, tz, Weekday::Mon);
+        assert_eq!(end, utc("2024-09-08T03:59:59.999999999Z"));
+        let month = period_start(utc("2024-09-08T21:00:00Z"), Period::Month, tz, Weekday::Mon);
+        assert_eq!(month, utc("2024-09-01T04:00:00Z"));
+    }
+}
@@ -0,0 +1,138 @@
+//! Named output layouts accepted by `--format` in place of a strftime pattern.
+
+/// A named output layout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Preset {
+    pub name: &'static str,
+    /// strftime pattern it renders with.
+    pub pattern: &'static str,
+    /// Always render in UTC, whatever the output zone.
+    pub utc: bool,
+    pub description: &'static str,
+}
+
+/// Every preset, in the order `--help` lists them.
+pub const PRESETS: &[Preset] = &[
+    Preset {
+        name: "rfc2822",
+        pattern: "%a, %d %b %Y %H:%M:%S %z",
+        utc: false,
+        description: "email `Date:` header: `Tue, 14 Nov 2023 22:13:20 +0000`",
+    },
+    Preset {
+        name: "iso8601",
+        pattern: "%Y-%m-%dT%H:%M:%S%:z",
+        utc: false,
+        description: "ISO 8601 extended, whole seconds: `2023-11-14T22:13:20+00:00`",
+    },
+    Preset {
+        name: "iso8601-basic",
+        pattern: "%Y%m%dT%H%M%S%z",
+        utc: false,
+        description: "ISO 8601 basic, without separators: `20231114T221320+0000`",
+    },
+    Preset {
+        name: "iso8601-basic-utc",
+        pattern: "%Y%m%dT%H%M%SZ",
+        utc: true,
+        description: "ISO 8601 basic in UTC, as S3 signatures (`X-Amz-Date`) use: `20231114T221320Z`",
+    },
+    Preset {
+        name: "unix",
+        pattern: "%s",
+        utc: false,
+        description: "epoch seconds",
+    },
+    Preset {
+        name: "ctime",
+        pattern: "%a %b %e %H:%M:%S %Y",
+        utc: false,
+        description: "C's ctime()/asctime() and `date` on many systems: `Tue Nov 14 22:13:20 2023`",
+    },
+    Preset {
+        name: "kitchen",
+        pattern: "%-I:%M%p",
+        utc: false,
+        description: "wall clock time as Go's `time.Kitchen` prints it: `10:13PM`",
+    },
+    Preset {
+        name: "aws-logs",
+        pattern: "%s%3f",
+        utc: false,
+        description: "epoch milliseconds, as `aws logs --start-time/--end-time` expect",
+    },
+    Preset {
+        name: "git-raw",
+        pattern: "%s %z",
+        utc: false,
+        description: "git's raw date (`1700000000 +0200`), for GIT_AUTHOR_DATE and friends",
+    },
+    Preset {
+        name: "sql",
+        pattern: "%Y-%m-%d %H:%M:%S%.f%:z",
+        utc: false,
+        description: "MySQL/Postgres literal with an explicit offset: `2023-11-14 22:13:20+00:00`",
+    },
+    Preset {
+        name: "http",
+        pattern: crate::layouts::HTTP_DATE,
+        utc: true,
+        description: "HTTP `Date` header (IMF-fixdate), always in GMT",
+    },
+];
+
+/// The preset called `name`, if any.
+pub fn find(name: &str) -> Option<&'static Preset> {
+    PRESETS.iter().find(|p| p.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Formatter;
+    use chrono::DateTime;
+
+    #[test]
+    fn presets_are_valid_patterns() {
+        for preset in PRESETS {
+            assert!(
+                crate::check_strftime(preset.pattern).is_ok(),
+                "{}",
+                preset.name
+            );
+        }
+        assert!(find("nope").is_none());
+    }
+
+    #[test]
+    fn aws_logs_is_epoch_millis() {
+        let dt = DateTime::from_timestamp_millis(1_700_000_000_123).unwrap();
+        let aws = Formatter::new().preset(find("aws-logs").unwrap());
+        assert_eq!(aws.format(dt), "1700000000123");
+    }
+
+    #[test]
+    fn http_is_always_gmt() {
+        let dt = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let http = Formatter::new()
+            .output_tz("+09:00".parse().unwrap())
+            .preset(find("http").unwrap());
+        assert_eq!(http.format(dt), "Tue, 14 Nov 2023 22:13:20 GMT");
+        let basic = Formatter::new()
+            .output_tz("+09:00".parse().unwrap())
+            .preset(find("iso8601-basic-utc").unwrap());
+        assert_eq!(basic.format(dt), "20231114T221320Z");
+    }
+
+    #[test]
+    fn common_layouts() {
+        let dt = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let render = |name| Formatter::new().preset(find(name).unwrap()).format(dt);
+        assert_eq!(render("rfc2822"), "Tue, 14 Nov 2023 22:13:20 +0000");
+        assert_eq!(render("iso8601"), "2023-11-14T22:13:20+00:00");
+        assert_eq!(render("iso8601-basic"), "20231114T221320+0000");
+        assert_eq!(render("unix"), "1700000000");
+        assert_eq!(render("ctime"), "Tue Nov 14 22:13:20 2023");
+        assert_eq!(render("kitchen"), "10:13PM");
+    }
+}
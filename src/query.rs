@@ -0,0 +1,157 @@
+//! `--query`: a small jq-style path for pulling values out of a JSON result.
+//!
+//! A path is a chain of `.field` and `[index]` steps: `.unix_millis`, `.fields.year`,
+//! `.alternatives[0].utc`, `.items[-1]`. Field names that are not plain identifiers go
+//! in quotes, `."time zone"`; `.` alone is the whole result.
+
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Step {
+    Field(String),
+    /// Negative indexes count from the end.
+    Index(i64),
+}
+
+/// A parsed path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Query {
+    source: String,
+    steps: Vec<Step>,
+}
+
+impl FromStr for Query {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let invalid = |why: &str| format!("invalid query `{s}`: {why}");
+        if !s.starts_with(['.', '[']) {
+            return Err(invalid("must start with `.`"));
+        }
+
+        let mut steps = Vec::new();
+        let mut rest = s;
+        if rest == "." {
+            rest = "";
+        }
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('[') {
+                let (index, after) = after
+                    .split_once(']')
+                    .ok_or_else(|| invalid("unclosed `[`"))?;
+                let index = index
+                    .trim()
+                    .parse()
+                    .map_err(|_| invalid(&format!("`{index}` is not an index")))?;
+                steps.push(Step::Index(index));
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix(".\"") {
+                let (name, after) = after
+                    .split_once('"')
+                    .ok_or_else(|| invalid("unclosed `\"`"))?;
+                steps.push(Step::Field(name.to_string()));
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix('.') {
+                let end = after
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+                    .unwrap_or(after.len());
+                if end == 0 {
+                    return Err(invalid("expected a field name after `.`"));
+                }
+                steps.push(Step::Field(after[..end].to_string()));
+                rest = &after[end..];
+            } else {
+                return Err(invalid(&format!("unexpected `{rest}`")));
+            }
+        }
+        Ok(Query {
+            source: s.to_string(),
+            steps,
+        })
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl Query {
+    /// The value at this path in `value`. Unlike jq, a missing field or index is an error
+    /// rather than `null`, so a misspelt path fails loudly in scripts.
+    pub fn select<'a>(&self, value: &'a Value) -> Result<&'a Value, String> {
+        let mut at = value;
+        for (i, step) in self.steps.iter().enumerate() {
+            let found = match (step, at) {
+                (Step::Field(name), Value::Object(map)) => map.get(name),
+                (Step::Index(index), Value::Array(items)) => {
+                    let len = items.len() as i64;
+                    let index = if *index < 0 { len + index } else { *index };
+                    usize::try_from(index).ok().and_then(|i| items.get(i))
+                }
+                _ => None,
+            };
+            at = found.ok_or_else(|| format!("no value at `{}`", path(&self.steps[..=i])))?;
+        }
+        Ok(at)
+    }
+}
+
+/// `steps` written back out, for messages.
+fn path(steps: &[Step]) -> String {
+    steps
+        .iter()
+        .map(|step| match step {
+            Step::Field(name) if name.chars().all(|c| c.is_alphanumeric() || c == '_') => {
+                format!(".{name}")
+            }
+            Step::Field(name) => format!(".\"{name}\""),
+            Step::Index(index) => format!("[{index}]"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn select(path: &str, value: &Value) -> Result<Value, String> {
+        path.parse::<Query>()?.select(value).cloned()
+    }
+
+    #[test]
+    fn follows_fields_and_indexes() {
+        let value = json!({
+            "unix_millis": 1_700_000_000_000_i64,
+            "time zone": "UTC",
+            "alternatives": [{"utc": "a"}, {"utc": "b"}],
+        });
+        assert_eq!(
+            select(".unix_millis", &value),
+            Ok(json!(1_700_000_000_000_i64))
+        );
+        assert_eq!(select(".\"time zone\"", &value), Ok(json!("UTC")));
+        assert_eq!(select(".alternatives[0].utc", &value), Ok(json!("a")));
+        assert_eq!(select(".alternatives[-1].utc", &value), Ok(json!("b")));
+        assert_eq!(select(".", &value), Ok(value.clone()));
+        assert_eq!(
+            select(".alternatives[2].utc", &value),
+            Err("no value at `.alternatives[2]`".to_string())
+        );
+        assert_eq!(
+            select(".unix_millis.x", &value),
+            Err("no value at `.unix_millis.x`".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_paths() {
+        for path in ["unix_millis", "..a", ".a[", ".a[x]", ".\"a", ".a b"] {
+            assert!(path.parse::<Query>().is_err(), "{path}");
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! JSON results rewritten as other documents: TOML for `--toml`, XML for `--xml`.
+
+use serde_json::{Map, Value};
+
+/// Fields written as TOML's native offset date-time rather than as strings.
+const TOML_DATETIMES: &[&str] = &["rfc3339"];
+
+/// `result` as TOML key/value pairs, under a `[[table]]` header when one is given (so
+/// several results make an array of tables). Nulls are left out, as TOML has none.
+pub fn toml(result: &Map<String, Value>, table: Option<&str>) -> String {
+    let mut out = String::new();
+    if let Some(table) = table {
+        out.push_str(&format!("[[{}]]\n", toml_key(table)));
+    }
+    for (key, value) in result {
+        if value.is_null() {
+            continue;
+        }
+        let value = match value {
+            Value::String(s) if TOML_DATETIMES.contains(&key.as_str()) && toml_year(s) => s.clone(),
+            value => toml_value(value),
+        };
+        out.push_str(&format!("{} = {value}\n", toml_key(key)));
+    }
+    out.truncate(out.trim_end().len());
+    out
+}
+
+/// Whether an RFC 3339 string starts with the four-digit year TOML's date-times require,
+/// rather than chrono's signed, wider years outside 0000-9999.
+fn toml_year(rfc3339: &str) -> bool {
+    let bytes = rfc3339.as_bytes();
+    bytes.len() > 4 && bytes[..4].iter().all(u8::is_ascii_digit) && bytes[4] == b'-'
+}
+
+fn toml_key(key: &str) -> String {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if bare {
+        key.to_string()
+    } else {
+        toml_string(key)
+    }
+}
+
+fn toml_value(value: &Value) -> String {
+    match value {
+        Value::Null => "\"\"".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => toml_string(s),
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(toml_value).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Object(map) => {
+            let pairs: Vec<String> = map
+                .iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| format!("{} = {}", toml_key(k), toml_value(v)))
+                .collect();
+            format!("{{ {} }}", pairs.join(", "))
+        }
+    }
+}
+
+/// A basic (double-quoted) TOML string.
+fn toml_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04X}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Heads every XML document; batch output writes it once, above a `<results>` root.
+pub const XML_DECLARATION: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>";
+
+/// `result` as a `<result>` element with one child element per field; nulls become empty
+/// elements. A full document, XML declaration and all, unless `fragment`, which puts the
+/// bare element on one line (batch output writes one per line inside `<results>`).
+pub fn xml(result: &Map<String, Value>, fragment: bool) -> String {
+    let mut out = String::new();
+    if !fragment {
+        out.push_str(XML_DECLARATION);
+        out.push('\n');
+    }
+    let indent = if fragment { None } else { Some(1) };
+    xml_element(&mut out, "result", &Value::Object(result.clone()), indent);
+    out.truncate(out.trim_end().len());
+    out
+}
+
+/// Append `<name>value</name>`; `indent` is the nesting depth when pretty-printing.
+fn xml_element(out: &mut String, name: &str, value: &Value, indent: Option<usize>) {
+    let (pad, newline) = match indent {
+        Some(depth) => ("  ".repeat(depth - 1), "\n"),
+        None => (String::new(), ""),
+    };
+    let children = |out: &mut String, items: &mut dyn Iterator<Item = (&str, &Value)>| {
+        out.push_str(&format!("{pad}<{name}>{newline}"));
+        for (child, value) in items {
+            xml_element(out, child, value, indent.map(|depth| depth + 1));
+        }
+        out.push_str(&format!("{pad}</{name}>{newline}"));
+    };
+    match value {
+        Value::Null => out.push_str(&format!("{pad}<{name}/>{newline}")),
+        Value::Object(map) => children(out, &mut map.iter().map(|(k, v)| (k.as_str(), v))),
+        Value::Array(items) => children(out, &mut items.iter().map(|v| ("item", v))),
+        Value::String(s) => {
+            out.push_str(&format!("{pad}<{name}>{}</{name}>{newline}", xml_text(s)))
+        }
+        value => out.push_str(&format!("{pad}<{name}>{value}</{name}>{newline}")),
+    }
+}
+
+/// `text` with `&`, `<` and `>` escaped for element content.
+fn xml_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn object(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn writes_toml_pairs() {
+        let result = object(json!({
+            "input": "say \"hi\"\n",
+            "rfc3339": "2023-11-14T22:13:20+00:00",
+            "ts_unit": null,
+            "unix_seconds": 1_700_000_000,
+            "odd key": [1, 2],
+        }));
+        assert_eq!(
+            toml(&result, None),
+            "input = \"say \\\"hi\\\"\\n\"\n\
+             \"odd key\" = [1, 2]\n\
+             rfc3339 = 2023-11-14T22:13:20+00:00\n\
+             unix_seconds = 1700000000"
+        );
+        assert!(toml(&result, Some("results")).starts_with("[[results]]\ninput = "));
+    }
+
+    #[test]
+    fn quotes_datetimes_toml_cannot_hold() {
+        for rfc3339 in ["+55840-01-01T00:00:00+00:00", "-0001-12-31T00:00:00+00:00"] {
+            let result = object(json!({ "rfc3339": rfc3339 }));
+            assert_eq!(toml(&result, None), format!("rfc3339 = \"{rfc3339}\""));
+        }
+        let result = object(json!({ "rfc3339": "0000-01-01T00:00:00+00:00" }));
+        assert_eq!(toml(&result, None), "rfc3339 = 0000-01-01T00:00:00+00:00");
+    }
+
+    #[test]
+    fn writes_xml() {
+        let result = object(json!({
+            "input": "a < b & c",
+            "source_offset": null,
+            "unix_seconds": 1_700_000_000,
+        }));
+        assert_eq!(
+            xml(&result, false),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <result>\n  \
+               <input>a &lt; b &amp; c</input>\n  \
+               <source_offset/>\n  \
+               <unix_seconds>1700000000</unix_seconds>\n\
+             </result>"
+        );
+        assert_eq!(
+            xml(&result, true),
+            "<result><input>a &lt; b &amp; c</input><source_offset/>\
+             <unix_seconds>1700000000</unix_seconds></result>"
+        );
+    }
+}
@@ -0,0 +1,108 @@
+//! One instant in every representation the crate knows (`--all`).
+
+use chrono::{DateTime, Utc};
+
+use crate::zone::TzChoice;
+use crate::{Encoding, Formatter, layouts, presets};
+
+/// Seconds from 1601-01-01, the Windows FILETIME epoch, to 1970-01-01.
+const FILETIME_EPOCH_OFFSET: i64 = 11_644_473_600;
+
+/// Windows FILETIME: 100-nanosecond intervals since 1601-01-01 UTC. `None` before 1601,
+/// which FILETIME cannot express.
+pub fn filetime(dt: DateTime<Utc>) -> Option<u64> {
+    let secs = u64::try_from(dt.timestamp() + FILETIME_EPOCH_OFFSET).ok()?;
+    Some(secs * 10_000_000 + u64::from(dt.timestamp_subsec_nanos() / 100))
+}
+
+/// `dt` as `(name, value)` rows: epoch counts, RFC 3339 in UTC, the system zone and
+/// `output_tz` (when that is neither), RFC 2822, ISO week and ordinal dates, and the
+/// numeric encodings of `--to`. Rows a representation cannot express (nanoseconds outside
+/// 1677-2262, FILETIME before 1601) are left out.
+pub fn all(dt: DateTime<Utc>, output_tz: TzChoice) -> Vec<(&'static str, String)> {
+    let utc = Formatter::new();
+    let mut rows = vec![
+        ("unix_seconds", dt.timestamp().to_string()),
+        ("unix_millis", dt.timestamp_millis().to_string()),
+        ("unix_micros", dt.timestamp_micros().to_string()),
+    ];
+    if let Some(nanos) = dt.timestamp_nanos_opt() {
+        rows.push(("unix_nanos", nanos.to_string()));
+    }
+    rows.push(("rfc3339_utc", utc.format(dt)));
+    rows.push((
+        "rfc3339_local",
+        Formatter::new().output_tz(TzChoice::Local).format(dt),
+    ));
+    if !matches!(output_tz, TzChoice::Utc | TzChoice::Local) {
+        rows.push((
+            "rfc3339_output_tz",
+            Formatter::new().output_tz(output_tz).format(dt),
+        ));
+    }
+    let preset = |name| {
+        let preset = presets::find(name).expect("built-in preset");
+        utc.clone().preset(preset).format(dt)
+    };
+    rows.extend([
+        ("rfc2822", preset("rfc2822")),
+        ("http", preset("http")),
+        (
+            "iso_week_date",
+            utc.clone().strftime("%G-W%V-%u").format(dt),
+        ),
+        ("ordinal_date", utc.clone().strftime("%Y-%j").format(dt)),
+        ("julian_day", layouts::encode(dt, Encoding::Julianday)),
+        ("matlab_datenum", layouts::encode(dt, Encoding::Matlab)),
+        ("mac_1904", layouts::encode(dt, Encoding::Mac1904)),
+        ("gps_week", layouts::encode(dt, Encoding::GpsWeek)),
+    ]);
+    if let Some(filetime) = filetime(dt) {
+        rows.push(("filetime", filetime.to_string()));
+    }
+    rows.extend([
+        ("base36", layouts::encode(dt, Encoding::Base36)),
+        ("base62", layouts::encode(dt, Encoding::Base62)),
+    ]);
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn converts_to_filetime() {
+        let dt = Utc.timestamp_opt(1_700_000_000, 123_456_789).unwrap();
+        assert_eq!(filetime(dt), Some(133_444_736_001_234_567));
+        assert_eq!(
+            filetime(Utc.with_ymd_and_hms(1601, 1, 1, 0, 0, 0).unwrap()),
+            Some(0)
+        );
+        assert_eq!(
+            filetime(Utc.with_ymd_and_hms(1600, 12, 31, 0, 0, 0).unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn lists_every_representation() {
+        let dt = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let rows = all(dt, TzChoice::Utc);
+        let get = |name| {
+            rows.iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(get("unix_nanos"), Some("1700000000000000000"));
+        assert_eq!(get("rfc2822"), Some("Tue, 14 Nov 2023 22:13:20 +0000"));
+        assert_eq!(get("iso_week_date"), Some("2023-W46-2"));
+        assert_eq!(get("ordinal_date"), Some("2023-318"));
+        assert_eq!(get("rfc3339_output_tz"), None);
+
+        let tokyo = "+09:00".parse().unwrap();
+        let rows = all(dt, tokyo);
+        assert!(rows.contains(&("rfc3339_output_tz", "2023-11-15T07:13:20+09:00".into())));
+    }
+}
@@ -0,0 +1,209 @@
+//! Finding timestamps in free text, as used by the `scan` subcommand (`scan` feature).
+//!
+//! Each detector pairs a regex for candidate text with a parser for it. Candidates that
+//! do not parse are dropped, and where matches overlap the one starting first (then the
+//! longest) wins, so `2023/11/14 22:13:20 EST` is one match rather than a date and a
+//! stray zone.
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+
+use crate::EpochParser;
+
+/// A timestamp found in a line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Found<'a> {
+    /// Byte offsets of the match in the line.
+    pub start: usize,
+    pub end: usize,
+    pub text: &'a str,
+    /// Name of the detector that recognized it, e.g. `rfc3339` or `unix`.
+    pub kind: &'static str,
+    pub utc: DateTime<Utc>,
+}
+
+type ParseFn = fn(&str, &EpochParser) -> Option<DateTime<Utc>>;
+
+struct Detector {
+    kind: &'static str,
+    regex: Regex,
+    parse: ParseFn,
+}
+
+/// Extracts every timestamp it recognizes from lines of text.
+pub struct Scanner {
+    parser: EpochParser,
+    detectors: Vec<Detector>,
+}
+
+fn with_parser(s: &str, p: &EpochParser) -> Option<DateTime<Utc>> {
+    p.parse(s).ok().map(|(dt, _)| dt)
+}
+
+fn rfc3339(s: &str, p: &EpochParser) -> Option<DateTime<Utc>> {
+    match DateTime::parse_from_rfc3339(s) {
+        Ok(dt) => Some(dt.to_utc()),
+        // Without an offset the input zone applies, like formatted input.
+        Err(_) => {
+            let naive = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+                .or_else(|_| chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f"))
+                .ok()?;
+            crate::naive_to_utc(&naive, p.input_tz).ok()
+        }
+    }
+}
+
+/// The built-in detectors, most specific first.
+const DETECTORS: &[(&str, &str, ParseFn)] = &[
+    (
+        "rfc3339",
+        r"\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:\d{2})?",
+        rfc3339,
+    ),
+    (
+        "formatted",
+        r"\d{4}/\d{2}/\d{2} \d{2}:\d{2}:\d{2}(?: ?(?:[+-]\d{2}(?::?\d{2})?|[A-Z]{1,5}))?",
+        with_parser,
+    ),
+    (
+        "formatted",
+        r"\d{4}/\d{2}/\d{2} \d{2}:\d{2}:\d{2}",
+        with_parser,
+    ),
+    (
+        "clf",
+        r"\b\d{2}/[A-Za-z]{3}/\d{4}:\d{2}:\d{2}:\d{2} [+-]\d{4}",
+        with_parser,
+    ),
+    (
+        "http",
+        r"\b(?:Mon|Tue|Wed|Thu|Fri|Sat|Sun), \d{2} [A-Z][a-z]{2} \d{4} \d{2}:\d{2}:\d{2} GMT\b",
+        with_parser,
+    ),
+    (
+        "syslog",
+        r"\b[A-Z][a-z]{2} [ \d]\d \d{2}:\d{2}:\d{2}\b",
+        with_parser,
+    ),
+    ("week_date", r"\b\d{4}-?W\d{2}-?[1-7]\b", with_parser),
+    ("dtg", r"\b\d{6}[A-Z] [A-Z]{3} \d{2}\b", with_parser),
+    // Seconds and millis in the 2001-2286 range: ten or thirteen digits standing alone.
+    ("unix", r"\b(?:\d{10}|\d{13})\b", with_parser),
+];
+
+impl Scanner {
+    /// A scanner resolving candidates with `parser` (its input zone applies to text
+    /// without one). Expressions are never matched.
+    pub fn new(parser: EpochParser) -> Self {
+        let detectors = DETECTORS
+            .iter()
+            .map(|&(kind, pattern, parse)| Detector {
+                kind,
+                regex: Regex::new(pattern).expect("valid detector regex"),
+                parse,
+            })
+            .collect();
+        Scanner {
+            parser: parser.strict(true),
+            detectors,
+        }
+    }
+
+    /// All timestamps in `line`, in order of appearance.
+    pub fn scan<'a>(&self, line: &'a str) -> Vec<Found<'a>> {
+        let mut candidates: Vec<Found<'a>> = Vec::new();
+        for detector in &self.detectors {
+            for m in detector.regex.find_iter(line) {
+                if let Some(utc) = (detector.parse)(m.as_str(), &self.parser) {
+                    candidates.push(Found {
+                        start: m.start(),
+                        end: m.end(),
+                        text: m.as_str(),
+                        kind: detector.kind,
+                        utc,
+                    });
+                }
+            }
+        }
+
+        candidates.sort_by_key(|f| (f.start, std::cmp::Reverse(f.end)));
+        let mut found: Vec<Found<'a>> = Vec::new();
+        for candidate in candidates {
+            if found.last().is_none_or(|last| candidate.start >= last.end) {
+                found.push(candidate);
+            }
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zone::TzChoice;
+
+    fn scanner() -> Scanner {
+        Scanner::new(EpochParser::new().input_tz(TzChoice::Utc))
+    }
+
+    #[test]
+    fn finds_every_timestamp_in_a_line() {
+        let line = "at 2023-11-14T22:13:20Z id=1700000060 ts=1700000000123 done";
+        let found = scanner().scan(line);
+        let kinds: Vec<_> = found.iter().map(|f| (f.kind, f.utc.timestamp())).collect();
+        assert_eq!(
+            kinds,
+            [
+                ("rfc3339", 1_700_000_000),
+                ("unix", 1_700_000_060),
+                ("unix", 1_700_000_000)
+            ]
+        );
+        assert_eq!(&line[found[1].start..found[1].end], "1700000060");
+    }
+
+    #[test]
+    fn zone_suffix_belongs_to_the_match_when_it_parses() {
+        let found = scanner().scan("2023/11/14 17:13:20 EST GET /index.html");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].text, "2023/11/14 17:13:20 EST");
+        assert_eq!(found[0].utc.timestamp(), 1_700_000_000);
+
+        let found = scanner().scan("2023/11/14 22:13:20 GET /index.html");
+        assert_eq!(found[0].text, "2023/11/14 22:13:20");
+    }
+
+    #[test]
+    fn finds_access_log_times() {
+        let line = r#"10.0.0.1 - - [14/Nov/2023:22:13:20 +0000] "GET / HTTP/1.1" 200"#;
+        let found = scanner().scan(line);
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            (found[0].kind, found[0].text),
+            ("clf", "14/Nov/2023:22:13:20 +0000")
+        );
+        assert_eq!(found[0].utc.timestamp(), 1_700_000_000);
+    }
+
+    #[test]
+    fn finds_syslog_times() {
+        let p = EpochParser::new()
+            .input_tz(TzChoice::Utc)
+            .syslog_year(crate::SyslogYear::Year(2023));
+        let found = Scanner::new(p).scan("Nov  4 22:13:20 host sshd[42]: accepted");
+        assert_eq!(
+            (found[0].kind, found[0].text),
+            ("syslog", "Nov  4 22:13:20")
+        );
+        assert_eq!(found[0].utc.timestamp(), 1_699_136_000);
+    }
+
+    #[test]
+    fn ignores_numbers_that_are_not_timestamps() {
+        assert!(
+            scanner()
+                .scan("port 8080, pid 12345, 123456789012")
+                .is_empty()
+        );
+    }
+}
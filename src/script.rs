@@ -0,0 +1,155 @@
+//! `--script`: a Rhai script that turns each converted instant into custom output
+//! (`script` feature).
+//!
+//! The script runs once per input with two variables in scope: `result`, the instant's
+//! JSON result as a map with the default rendering added as `formatted`, and `instant`,
+//! which formats itself with `instant.format("%F")` or `instant.format("%F", "Asia/Tokyo")`.
+//! The value of its last expression is the output: strings as they are, other values
+//! through `to_string`, and `()` (say, from an `if` without `else`) drops the input from
+//! batch output.
+//!
+//! ```rhai
+//! if result.unix_seconds % 60 == 0 {
+//!     `${result.formatted} on the minute`
+//! }
+//! ```
+
+use chrono::{DateTime, Utc};
+use rhai::{AST, Dynamic, Engine, EvalAltResult, Scope};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use epoch::Formatter;
+use epoch::zone::TzChoice;
+
+/// The instant a script sees as `instant`.
+#[derive(Clone, Copy)]
+struct Instant(DateTime<Utc>);
+
+/// A script compiled once and run for every input, from as many threads as batch mode
+/// uses.
+#[derive(Clone)]
+pub struct Script {
+    path: PathBuf,
+    engine: Arc<Engine>,
+    ast: Arc<AST>,
+}
+
+impl fmt::Debug for Script {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Script").field(&self.path).finish()
+    }
+}
+
+fn format(
+    instant: &mut Instant,
+    pattern: &str,
+    zone: TzChoice,
+) -> Result<String, Box<EvalAltResult>> {
+    epoch::check_strftime(pattern).map_err(|e| e.to_string())?;
+    Ok(Formatter::new()
+        .output_tz(zone)
+        .strftime(pattern)
+        .format(instant.0))
+}
+
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .register_type_with_name::<Instant>("Instant")
+        .register_fn("format", |instant: &mut Instant, pattern: &str| {
+            format(instant, pattern, TzChoice::Utc)
+        })
+        .register_fn(
+            "format",
+            |instant: &mut Instant, pattern: &str, zone: &str| {
+                let zone = zone.parse::<TzChoice>()?;
+                format(instant, pattern, zone)
+            },
+        );
+    engine
+}
+
+impl Script {
+    /// Read and compile the script at `path`; a `--script` value parser.
+    pub fn load(path: &str) -> Result<Script, String> {
+        let path = PathBuf::from(path);
+        let source = std::fs::read_to_string(&path)
+            .map_err(|e| format!("script {}: {e}", path.display()))?;
+        Script::compile(&path, &source)
+    }
+
+    fn compile(path: &Path, source: &str) -> Result<Script, String> {
+        let engine = engine();
+        let ast = engine
+            .compile(source)
+            .map_err(|e| format!("script {}: {e}", path.display()))?;
+        Ok(Script {
+            path: path.to_path_buf(),
+            engine: Arc::new(engine),
+            ast: Arc::new(ast),
+        })
+    }
+
+    /// What the script makes of the instant `utc` and its JSON result `fields`; empty
+    /// when it evaluates to `()`.
+    pub fn run(&self, utc: DateTime<Utc>, fields: &serde_json::Value) -> Result<String, String> {
+        let failed = |e: Box<EvalAltResult>| format!("script {}: {e}", self.path.display());
+        let mut scope = Scope::new();
+        scope.push("result", rhai::serde::to_dynamic(fields).map_err(failed)?);
+        scope.push("instant", Instant(utc));
+        let out: Dynamic = self
+            .engine
+            .eval_ast_with_scope(&mut scope, &self.ast)
+            .map_err(failed)?;
+        Ok(if out.is_unit() {
+            String::new()
+        } else {
+            out.to_string()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn run(source: &str) -> Result<String, String> {
+        let utc = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let fields = json!({ "unix_seconds": 1_700_000_000, "formatted": "22:13" });
+        Script::compile(Path::new("t.rhai"), source)?.run(utc, &fields)
+    }
+
+    #[test]
+    fn prints_the_last_value() {
+        assert_eq!(
+            run(r#""at " + result.formatted"#),
+            Ok("at 22:13".to_string())
+        );
+        assert_eq!(run("result.unix_seconds / 60"), Ok("28333333".to_string()));
+        assert_eq!(
+            run("if result.unix_seconds < 0 { \"old\" }"),
+            Ok(String::new())
+        );
+    }
+
+    #[test]
+    fn formats_the_instant() {
+        assert_eq!(run(r#"instant.format("%F")"#), Ok("2023-11-14".to_string()));
+        assert_eq!(
+            run(r#"instant.format("%F %H:%M", "+09:00")"#),
+            Ok("2023-11-15 07:13".to_string())
+        );
+        assert!(run(r#"instant.format("%Q")"#).is_err());
+        assert!(run(r#"instant.format("%F", "Mars")"#).is_err());
+    }
+
+    #[test]
+    fn reports_script_errors() {
+        let err = Script::compile(Path::new("t.rhai"), "let = 1;").unwrap_err();
+        assert!(err.starts_with("script t.rhai: "), "{err}");
+        assert!(run("undefined_variable").is_err());
+    }
+}
@@ -0,0 +1,183 @@
+//! Serde support for timestamp fields (`serde` feature), re-exported as `epoch::serde`.
+//!
+//! ```
+//! # use chrono::{DateTime, Utc};
+//! #[derive(serde::Deserialize)]
+//! struct Event {
+//!     #[serde(with = "epoch::serde::flexible")]
+//!     at: DateTime<Utc>,
+//! }
+//!
+//! for raw in [r#"{"at": 1700000000}"#, r#"{"at": "1700000000000"}"#, r#"{"at": "2023/11/14 22:13:20"}"#] {
+//!     let event: Event = serde_json::from_str(raw).unwrap();
+//!     assert_eq!(event.at.timestamp(), 1_700_000_000);
+//! }
+//! ```
+
+/// Accepts anything the CLI accepts as INPUT: numbers or numeric strings with seconds /
+/// millis autodetection, formatted datetimes, week dates, zone designators; plus RFC 3339.
+/// Formatted input without a zone is read as UTC so results do not depend on the host.
+///
+/// Serializes as an RFC 3339 string, which deserializes back to the same instant.
+pub mod flexible {
+    use chrono::{DateTime, Utc};
+    use serde::de::{self, Visitor};
+    use serde::{Deserializer, Serializer};
+    use std::fmt;
+
+    use crate::zone::TzChoice;
+
+    struct FlexibleVisitor;
+
+    impl Visitor<'_> for FlexibleVisitor {
+        type Value = DateTime<Utc>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str(
+                "a unix timestamp (seconds or millis, possibly fractional) or a datetime string",
+            )
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+            crate::parse_timestamp_to_utc(v, None)
+                .map(|(dt, _)| dt)
+                .map_err(E::custom)
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+            let v = i64::try_from(v).map_err(|_| E::custom("unix timestamp out of range"))?;
+            self.visit_i64(v)
+        }
+
+        /// Float epochs, as JavaScript writes them: the unit is autodetected from the whole
+        /// part and the fraction kept.
+        fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+            crate::parse_f64_timestamp_to_utc(v, None)
+                .map(|(dt, _)| dt)
+                .map_err(E::custom)
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            if let Ok(dt) = DateTime::parse_from_rfc3339(v) {
+                return Ok(dt.to_utc());
+            }
+            crate::parse_input_to_utc(v.trim(), TzChoice::Utc, None, &[])
+                .map(|(dt, _)| dt)
+                .map_err(E::custom)
+        }
+    }
+
+    pub fn serialize<S: Serializer>(dt: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&dt.to_rfc3339())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<DateTime<Utc>, D::Error> {
+        deserializer.deserialize_any(FlexibleVisitor)
+    }
+
+    /// The same for `Option<DateTime<Utc>>`; `null` is `None`.
+    pub mod option {
+        use chrono::{DateTime, Utc};
+        use serde::de::{self, Visitor};
+        use serde::{Deserializer, Serializer};
+        use std::fmt;
+
+        struct OptionVisitor;
+
+        impl<'de> Visitor<'de> for OptionVisitor {
+            type Value = Option<DateTime<Utc>>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("null, a unix timestamp or a datetime string")
+            }
+
+            fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+                Ok(None)
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+                Ok(None)
+            }
+
+            fn visit_some<D: Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+                super::deserialize(d).map(Some)
+            }
+        }
+
+        pub fn serialize<S: Serializer>(
+            dt: &Option<DateTime<Utc>>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match dt {
+                Some(dt) => super::serialize(dt, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<DateTime<Utc>>, D::Error> {
+            deserializer.deserialize_option(OptionVisitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Event {
+        #[serde(with = "crate::serde::flexible")]
+        at: DateTime<Utc>,
+        #[serde(default, with = "crate::serde::flexible::option")]
+        until: Option<DateTime<Utc>>,
+    }
+
+    fn at(json: &str) -> Result<i64, String> {
+        serde_json::from_str::<Event>(json)
+            .map(|e| e.at.timestamp_millis())
+            .map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn accepts_numbers_and_strings() {
+        assert_eq!(at(r#"{"at": 1700000000}"#), Ok(1_700_000_000_000));
+        assert_eq!(at(r#"{"at": 1700000000123}"#), Ok(1_700_000_000_123));
+        assert_eq!(at(r#"{"at": "1700000000"}"#), Ok(1_700_000_000_000));
+        assert_eq!(
+            at(r#"{"at": "2023/11/14 22:13:20"}"#),
+            Ok(1_700_000_000_000)
+        );
+        assert_eq!(
+            at(r#"{"at": "2023/11/14 23:13:20 +01:00"}"#),
+            Ok(1_700_000_000_000)
+        );
+        assert!(
+            at(r#"{"at": "soon"}"#)
+                .unwrap_err()
+                .contains("Unrecognized input")
+        );
+        assert_eq!(at(r#"{"at": 1.7e9}"#), Ok(1_700_000_000_000));
+        assert_eq!(at(r#"{"at": 1700000000.5}"#), Ok(1_700_000_000_500));
+        assert_eq!(at(r#"{"at": 1700000000123.0}"#), Ok(1_700_000_000_123));
+        assert!(at(r#"{"at": 1e300}"#).is_err());
+        assert!(at(r#"{"at": true}"#).is_err());
+    }
+
+    #[test]
+    fn round_trips_through_rfc3339() {
+        let event: Event = serde_json::from_str(r#"{"at": 1700000000, "until": null}"#).unwrap();
+        assert_eq!(event.until, None);
+
+        let json = serde_json::to_string(&event).unwrap();
+        assert_eq!(json, r#"{"at":"2023-11-14T22:13:20+00:00","until":null}"#);
+        assert_eq!(serde_json::from_str::<Event>(&json).unwrap(), event);
+
+        let event: Event = serde_json::from_str(r#"{"at": 0, "until": "1700000000"}"#).unwrap();
+        assert_eq!(event.until.map(|u| u.timestamp()), Some(1_700_000_000));
+    }
+}
@@ -0,0 +1,282 @@
+//! `epoch serve`: a small HTTP/1.1 server answering with the CLI's JSON results.
+//!
+//! Routes (GET only):
+//! - `/parse?input=...` with optional `input_tz`, `output_tz`, `ts`, `prefer_zone` and
+//!   `fiscal_start`, mirroring the CLI flags
+//! - `/now`, which accepts the same optional parameters
+//!
+//! Errors are JSON too: `{"error": "..."}` with status 400, 404, 405, 431 or 503.
+//!
+//! Each connection gets [`TIMEOUT`] per read or write and [`MAX_REQUEST_BYTES`] for its
+//! request line and headers; past [`MAX_CONNECTIONS`] at once, new ones are turned away
+//! with a 503.
+
+use clap::ValueEnum;
+use serde_json::{Value, json};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use epoch::zone::TzChoice;
+use epoch::{SchemaVersion, TsUnit, parse_input_to_utc, versioned_json};
+
+/// How long a connection may take over any one read or write.
+pub const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Most bytes read for a request line and its headers.
+pub const MAX_REQUEST_BYTES: u64 = 16 * 1024;
+
+/// Most connections served at once.
+pub const MAX_CONNECTIONS: usize = 64;
+
+/// Accept connections on `listen` forever, one thread per connection.
+pub fn run(listen: &str) -> io::Result<()> {
+    let listener = TcpListener::bind(listen)?;
+    eprintln!("Listening on http://{}", listener.local_addr()?);
+    let open = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("Error: {e}");
+                continue;
+            }
+        };
+        if open.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+            open.fetch_sub(1, Ordering::SeqCst);
+            let _ = stream.set_write_timeout(Some(TIMEOUT));
+            let _ = write_response(&stream, error(503, "too many connections; retry later"));
+            continue;
+        }
+        let open = Arc::clone(&open);
+        thread::spawn(move || {
+            let _ = handle_connection(stream);
+            open.fetch_sub(1, Ordering::SeqCst);
+        });
+    }
+    Ok(())
+}
+
+fn handle_connection(stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new((&stream).take(MAX_REQUEST_BYTES));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // drain the headers; requests have no body we care about
+    let mut header = String::new();
+    let mut complete = request_line.ends_with('\n');
+    while complete {
+        header.clear();
+        reader.read_line(&mut header)?;
+        complete = header.ends_with('\n');
+        if header.trim_end().is_empty() {
+            break;
+        }
+    }
+
+    let response = if complete {
+        respond(request_line.trim_end())
+    } else {
+        error(431, "request line or headers too large")
+    };
+    write_response(&stream, response)
+}
+
+fn write_response(stream: &TcpStream, (status, body): (u16, Value)) -> io::Result<()> {
+    let mut stream = stream;
+    let body = body.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        reason(status),
+        body.len()
+    )?;
+    stream.flush()
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        431 => "Request Header Fields Too Large",
+        _ => "Service Unavailable",
+    }
+}
+
+fn error(status: u16, msg: impl Into<String>) -> (u16, Value) {
+    (status, json!({ "error": msg.into() }))
+}
+
+/// Status code and JSON body for a request line such as `GET /parse?input=1 HTTP/1.1`.
+pub fn respond(request_line: &str) -> (u16, Value) {
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return error(400, "malformed request line");
+    };
+    if method != "GET" {
+        return error(405, format!("method {method} not allowed; use GET"));
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let params: Vec<(String, String)> = query
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let (k, v) = p.split_once('=').unwrap_or((p, ""));
+            (percent_decode(k), percent_decode(v))
+        })
+        .collect();
+
+    let input = match path {
+        "/parse" => match param(&params, "input") {
+            Some(input) => input,
+            None => return error(400, "missing query parameter `input`"),
+        },
+        "/now" => "now",
+        _ => return error(404, format!("no route for {path}; try /parse or /now")),
+    };
+
+    match parse_request(input, &params) {
+        Ok(body) => (200, body),
+        Err(msg) => error(400, msg),
+    }
+}
+
+fn param<'a>(params: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    params
+        .iter()
+        .find(|(k, _)| k == name)
+        .map(|(_, v)| v.as_str())
+}
+
+/// The JSON result for `input` under the string-valued options in `params` (the CLI flag
+/// names in snake_case). Shared with `epoch daemon`.
+pub fn parse_request(input: &str, params: &[(String, String)]) -> Result<Value, String> {
+    let param = |name: &'static str| param(params, name);
+    let tz = |name: &'static str, default: TzChoice| -> Result<TzChoice, String> {
+        param(name)
+            .map_or(Ok(default), |v| {
+                v.parse().map_err(|e| format!("invalid {name}: {e}"))
+            })
+            .map(TzChoice::pin_local)
+    };
+    let input_tz = tz("input_tz", TzChoice::Local)?;
+    let output_tz = tz("output_tz", TzChoice::Utc)?;
+    let ts = param("ts")
+        .map(|v| TsUnit::from_str(v, true).map_err(|_| format!("invalid ts: {v}")))
+        .transpose()?;
+    let prefer_zone: Vec<String> = param("prefer_zone")
+        .map(|v| v.split(',').map(String::from).collect())
+        .unwrap_or_default();
+    let fiscal_start = param("fiscal_start")
+        .map(|v| match v.parse::<u32>() {
+            Ok(m @ 1..=12) => Ok(m),
+            _ => Err(format!("invalid fiscal_start: {v} (expected 1-12)")),
+        })
+        .transpose()?;
+    let schema = param("schema")
+        .map(|v| SchemaVersion::from_str(v, true).map_err(|_| format!("invalid schema: {v}")))
+        .transpose()?
+        .unwrap_or_default();
+
+    let (utc_dt, parsed_as) =
+        parse_input_to_utc(input, input_tz, ts, &prefer_zone).map_err(|e| e.to_string())?;
+    Ok(versioned_json(
+        schema,
+        input,
+        utc_dt,
+        &parsed_as,
+        input_tz,
+        output_tz,
+        fiscal_start,
+    ))
+}
+
+/// Decode `%XX` escapes and `+` (space) in a query component.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+            (b'+', _) => out.push(b' '),
+            (b, _) => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_query_components() {
+        assert_eq!(
+            percent_decode("2024%2F01%2F01+10%3A00%3A00"),
+            "2024/01/01 10:00:00"
+        );
+        assert_eq!(percent_decode("Europe/Paris"), "Europe/Paris");
+        assert_eq!(percent_decode("100%"), "100%");
+    }
+
+    #[test]
+    fn parse_route_returns_instant_json() {
+        let (status, body) = respond("GET /parse?input=1700000000&output_tz=Asia%2FTokyo HTTP/1.1");
+        assert_eq!(status, 200);
+        assert_eq!(body["unix_seconds"], 1_700_000_000);
+        assert_eq!(body["output_tz"], "Asia/Tokyo");
+        assert_eq!(body["rfc3339"], "2023-11-15T07:13:20+09:00");
+
+        let (_, body) = respond("GET /parse?input=1700000000&schema=2 HTTP/1.1");
+        assert_eq!(body["schema_version"], 2);
+        assert_eq!(body["iso_week"], 46);
+    }
+
+    #[test]
+    fn reports_errors_as_json() {
+        assert_eq!(respond("GET /parse HTTP/1.1").0, 400);
+        assert_eq!(respond("GET /parse?input=nope HTTP/1.1").0, 400);
+        assert_eq!(respond("GET /parse?input=1&output_tz=Mars HTTP/1.1").0, 400);
+        assert_eq!(respond("GET /elsewhere HTTP/1.1").0, 404);
+        assert_eq!(respond("POST /now HTTP/1.1").0, 405);
+        assert_eq!(respond("GET /now HTTP/1.1").0, 200);
+    }
+
+    #[test]
+    fn stops_reading_oversized_requests() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            // No newline: the server must answer once the limit is reached, not wait for
+            // more.
+            stream
+                .write_all(&vec![b'a'; MAX_REQUEST_BYTES as usize])
+                .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        });
+        let (stream, _) = listener.accept().unwrap();
+        handle_connection(stream).unwrap();
+        let response = client.join().unwrap();
+        assert!(response.starts_with("HTTP/1.1 431 "), "{response}");
+    }
+}
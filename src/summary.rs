@@ -0,0 +1,86 @@
+//! Distribution of a set of instants, as reported by the `summary` subcommand.
+
+use chrono::{DateTime, Duration, Utc};
+
+/// Profile of a set of instants. Gaps are measured between neighbours in time order, so
+/// out-of-order input does not produce negative gaps.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Summary {
+    pub count: usize,
+    pub earliest: DateTime<Utc>,
+    pub latest: DateTime<Utc>,
+    /// The middle instant (the earlier of the two middle ones for an even count).
+    pub median: DateTime<Utc>,
+    /// 90th and 99th percentile of the gaps; `None` for a single instant.
+    pub gap_p90: Option<Duration>,
+    pub gap_p99: Option<Duration>,
+    /// Average rate over the span; `None` when all instants coincide.
+    pub events_per_minute: Option<f64>,
+}
+
+/// Summarize `instants` (sorting them in place); `None` when there are none.
+pub fn summarize(instants: &mut [DateTime<Utc>]) -> Option<Summary> {
+    instants.sort_unstable();
+    let (&earliest, &latest) = (instants.first()?, instants.last()?);
+
+    let mut gaps: Vec<Duration> = instants.windows(2).map(|w| w[1] - w[0]).collect();
+    gaps.sort_unstable();
+
+    let span = latest - earliest;
+    let events_per_minute = (!span.is_zero())
+        .then(|| instants.len() as f64 / (span.num_milliseconds() as f64 / 60_000.0));
+
+    Some(Summary {
+        count: instants.len(),
+        earliest,
+        latest,
+        median: *percentile(instants, 50.0)?,
+        gap_p90: percentile(&gaps, 90.0).copied(),
+        gap_p99: percentile(&gaps, 99.0).copied(),
+        events_per_minute,
+    })
+}
+
+/// Nearest-rank percentile of sorted values: the smallest value with at least `p`% of
+/// the values at or below it.
+pub fn percentile<T>(sorted: &[T], p: f64) -> Option<&T> {
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.clamp(1, sorted.len().max(1)) - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(secs, 0).unwrap()
+    }
+
+    #[test]
+    fn nearest_rank_percentiles() {
+        let values: Vec<i32> = (1..=10).collect();
+        assert_eq!(percentile(&values, 50.0), Some(&5));
+        assert_eq!(percentile(&values, 90.0), Some(&9));
+        assert_eq!(percentile(&values, 99.0), Some(&10));
+        assert_eq!(percentile(&values, 0.0), Some(&1));
+        assert_eq!(percentile::<i32>(&[], 50.0), None);
+    }
+
+    #[test]
+    fn summarizes_unordered_instants() {
+        let mut instants: Vec<_> = [120, 0, 60, 180, 600].into_iter().map(at).collect();
+        let s = summarize(&mut instants).unwrap();
+
+        assert_eq!(s.count, 5);
+        assert_eq!((s.earliest, s.latest, s.median), (at(0), at(600), at(120)));
+        assert_eq!(s.gap_p90, Some(Duration::seconds(420)));
+        assert_eq!(s.events_per_minute, Some(0.5));
+    }
+
+    #[test]
+    fn single_instant_has_no_gaps_or_rate() {
+        let s = summarize(&mut [at(0)]).unwrap();
+        assert_eq!((s.gap_p90, s.events_per_minute), (None, None));
+        assert!(summarize(&mut []).is_none());
+    }
+}
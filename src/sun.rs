@@ -0,0 +1,195 @@
+//! Sunrise, sunset and twilight for a date and place, for `epoch sun`.
+//!
+//! Uses the sunrise equation with the Sun's equation of center and the equation of time,
+//! which is good to about a minute away from the poles; nearer them, where the Sun
+//! skims the horizon, small errors in declination move the times a lot more.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+/// 2000-01-01T12:00:00Z (J2000.0) as Unix seconds; solar times are counted in days from
+/// it.
+const J2000_UNIX: f64 = 946_728_000.0;
+
+/// Obliquity of the ecliptic, in degrees.
+const OBLIQUITY: f64 = 23.4397;
+
+/// Altitudes of the Sun's centre that mark the day's events, in degrees.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Horizon {
+    /// Sunrise and sunset: the upper limb on the horizon, allowing for refraction.
+    Sunrise,
+    /// Civil dawn and dusk, 6° below the horizon.
+    Civil,
+    /// Nautical dawn and dusk, 12° below.
+    Nautical,
+    /// Astronomical dawn and dusk, 18° below.
+    Astronomical,
+}
+
+impl Horizon {
+    pub fn altitude(self) -> f64 {
+        match self {
+            Horizon::Sunrise => -0.833,
+            Horizon::Civil => -6.0,
+            Horizon::Nautical => -12.0,
+            Horizon::Astronomical => -18.0,
+        }
+    }
+}
+
+/// When the Sun crosses an altitude, if it does that day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Crossing {
+    Between {
+        rise: DateTime<Utc>,
+        set: DateTime<Utc>,
+    },
+    /// The Sun stays above the altitude all day (at the horizon: polar day).
+    AlwaysAbove,
+    /// The Sun stays below the altitude all day (at the horizon: polar night).
+    AlwaysBelow,
+}
+
+/// The Sun's path over one day at one place.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SolarDay {
+    latitude: f64,
+    /// Solar noon, in days from J2000.0.
+    transit: f64,
+    /// Declination of the Sun, in degrees.
+    declination: f64,
+}
+
+/// The Sun on `date` (the local calendar date) at `latitude` (north positive) and
+/// `longitude` (east positive), both in degrees.
+pub fn solar_day(date: NaiveDate, latitude: f64, longitude: f64) -> SolarDay {
+    let sin = |deg: f64| deg.to_radians().sin();
+    let days = (date - NaiveDate::from_ymd_opt(2000, 1, 1).expect("valid date")).num_days();
+    let mean_noon = days as f64 - longitude / 360.0;
+    let anomaly = (357.5291 + 0.985_600_28 * mean_noon).rem_euclid(360.0);
+    let center = 1.9148 * sin(anomaly) + 0.02 * sin(2.0 * anomaly) + 0.0003 * sin(3.0 * anomaly);
+    let ecliptic_longitude = (anomaly + center + 180.0 + 102.9372).rem_euclid(360.0);
+    SolarDay {
+        latitude,
+        transit: mean_noon + 0.0053 * sin(anomaly) - 0.0069 * sin(2.0 * ecliptic_longitude),
+        declination: (sin(ecliptic_longitude) * sin(OBLIQUITY))
+            .asin()
+            .to_degrees(),
+    }
+}
+
+impl SolarDay {
+    /// Solar noon: when the Sun is highest, due south (or north) of the place.
+    pub fn noon(&self) -> DateTime<Utc> {
+        to_utc(self.transit)
+    }
+
+    /// Time between sunrise and sunset: all 24 hours during polar day, none during polar
+    /// night.
+    pub fn day_length(&self) -> Duration {
+        match self.crossing(Horizon::Sunrise) {
+            Crossing::Between { rise, set } => set - rise,
+            Crossing::AlwaysAbove => Duration::days(1),
+            Crossing::AlwaysBelow => Duration::zero(),
+        }
+    }
+
+    /// When the Sun's centre rises through and sets through `horizon`.
+    pub fn crossing(&self, horizon: Horizon) -> Crossing {
+        let (sin, cos) = (
+            |deg: f64| deg.to_radians().sin(),
+            |deg: f64| deg.to_radians().cos(),
+        );
+        let cos_hour_angle = (sin(horizon.altitude()) - sin(self.latitude) * sin(self.declination))
+            / (cos(self.latitude) * cos(self.declination));
+        if cos_hour_angle < -1.0 {
+            return Crossing::AlwaysAbove;
+        }
+        if cos_hour_angle > 1.0 {
+            return Crossing::AlwaysBelow;
+        }
+        let half_day = cos_hour_angle.acos().to_degrees() / 360.0;
+        Crossing::Between {
+            rise: to_utc(self.transit - half_day),
+            set: to_utc(self.transit + half_day),
+        }
+    }
+}
+
+/// Days from J2000.0 as a UTC instant, to the nearest second.
+fn to_utc(days: f64) -> DateTime<Utc> {
+    let secs = (J2000_UNIX + days * 86_400.0).round() as i64;
+    DateTime::from_timestamp(secs, 0).expect("solar times are within chrono's range")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn hm(crossing: Crossing) -> (String, String) {
+        let Crossing::Between { rise, set } = crossing else {
+            panic!("no crossing: {crossing:?}");
+        };
+        let hm = |dt: DateTime<Utc>| dt.format("%H:%M").to_string();
+        (hm(rise), hm(set))
+    }
+
+    #[test]
+    fn finds_sunrise_and_sunset() {
+        // Bucharest, whose solar noon in mid-November is about 10:00 UTC.
+        let day = solar_day(date("2023-11-14"), 44.43, 26.10);
+        assert_eq!(
+            hm(day.crossing(Horizon::Sunrise)),
+            ("05:09".into(), "14:50".into())
+        );
+        // New York, west of Greenwich: the evening falls on the next UTC day.
+        let day = solar_day(date("2023-11-14"), 40.71, -74.01);
+        assert_eq!(
+            hm(day.crossing(Horizon::Sunrise)),
+            ("11:41".into(), "21:39".into())
+        );
+    }
+
+    #[test]
+    fn orders_twilights_around_the_day() {
+        let day = solar_day(date("2023-03-20"), 51.48, 0.0);
+        let times: Vec<_> = [
+            Horizon::Astronomical,
+            Horizon::Nautical,
+            Horizon::Civil,
+            Horizon::Sunrise,
+        ]
+        .map(|h| match day.crossing(h) {
+            Crossing::Between { rise, set } => (rise, set),
+            other => panic!("{other:?}"),
+        })
+        .into();
+        assert!(times.windows(2).all(|w| w[0].0 < w[1].0 && w[0].1 > w[1].1));
+        // Equinox at Greenwich: about twelve hours between sunrise and sunset.
+        let (rise, set) = times[3];
+        assert!(((set - rise).num_minutes() - 12 * 60 - 8).abs() <= 5);
+        assert_eq!(day.day_length(), set - rise);
+        assert!((day.noon() - (rise + (set - rise) / 2)).num_seconds().abs() <= 1);
+        assert_eq!(day.noon().format("%H:%M").to_string(), "12:07");
+    }
+
+    #[test]
+    fn handles_polar_day_and_night() {
+        let tromso = (69.65, 18.96);
+        let midsummer = solar_day(date("2023-06-21"), tromso.0, tromso.1);
+        assert_eq!(midsummer.crossing(Horizon::Sunrise), Crossing::AlwaysAbove);
+        assert_eq!(midsummer.day_length(), Duration::days(1));
+        let midwinter = solar_day(date("2023-12-21"), tromso.0, tromso.1);
+        assert_eq!(midwinter.crossing(Horizon::Sunrise), Crossing::AlwaysBelow);
+        assert_eq!(midwinter.day_length(), Duration::zero());
+        assert_eq!(midwinter.noon().format("%H:%M").to_string(), "10:41");
+        assert!(matches!(
+            midwinter.crossing(Horizon::Nautical),
+            Crossing::Between { .. }
+        ));
+    }
+}
@@ -0,0 +1,128 @@
+//! Results laid out as aligned columns for `--output table` and `--output markdown`.
+
+/// How a table is drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Style {
+    /// Columns padded to line up, with a rule under the header.
+    Plain,
+    /// Plain, boxed in with ASCII borders (`--borders`).
+    Bordered,
+    /// A GitHub-flavored Markdown table, `|` in cells escaped.
+    Markdown,
+}
+
+/// `rows` under `header`, one line each, cells padded to the widest in their column.
+pub fn render(header: &[&str], rows: &[Vec<String>], style: Style) -> String {
+    let escaped: Vec<Vec<String>>;
+    let rows = match style {
+        Style::Markdown => {
+            escaped = rows
+                .iter()
+                .map(|row| row.iter().map(|cell| cell.replace('|', "\\|")).collect())
+                .collect();
+            &escaped
+        }
+        _ => rows,
+    };
+    let widths: Vec<usize> = (0..header.len())
+        .map(|col| {
+            rows.iter()
+                .filter_map(|row| row.get(col))
+                .map(|cell| cell.chars().count())
+                .chain([header[col].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let line = |cells: &mut dyn Iterator<Item = &str>| -> String {
+        let padded: Vec<String> = cells
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{cell:<width$}"))
+            .collect();
+        match style {
+            Style::Plain => padded.join("  ").trim_end().to_string(),
+            Style::Bordered | Style::Markdown => format!("| {} |", padded.join(" | ")),
+        }
+    };
+    let rule = match style {
+        Style::Plain => widths
+            .iter()
+            .map(|&w| "-".repeat(w))
+            .collect::<Vec<_>>()
+            .join("  "),
+        Style::Bordered => {
+            let dashes: Vec<String> = widths.iter().map(|&w| "-".repeat(w + 2)).collect();
+            format!("+{}+", dashes.join("+"))
+        }
+        Style::Markdown => {
+            let dashes: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+            format!("| {} |", dashes.join(" | "))
+        }
+    };
+
+    let mut lines = Vec::with_capacity(rows.len() + 4);
+    if style == Style::Bordered {
+        lines.push(rule.clone());
+    }
+    lines.push(line(&mut header.iter().copied()));
+    lines.push(rule.clone());
+    for row in rows {
+        lines.push(line(&mut row.iter().map(String::as_str)));
+    }
+    if style == Style::Bordered {
+        lines.push(rule);
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows() -> Vec<Vec<String>> {
+        vec![
+            vec!["1700000000".into(), "2023-11-14T22:13:20+00:00".into()],
+            vec!["now".into(), "2023-11-14T22:13:20+00:00".into()],
+        ]
+    }
+
+    #[test]
+    fn aligns_columns() {
+        assert_eq!(
+            render(&["input", "rfc3339"], &rows(), Style::Plain),
+            "\
+input       rfc3339
+----------  -------------------------
+1700000000  2023-11-14T22:13:20+00:00
+now         2023-11-14T22:13:20+00:00"
+        );
+    }
+
+    #[test]
+    fn draws_borders() {
+        assert_eq!(
+            render(&["input", "rfc3339"], &rows(), Style::Bordered),
+            "\
++------------+---------------------------+
+| input      | rfc3339                   |
++------------+---------------------------+
+| 1700000000 | 2023-11-14T22:13:20+00:00 |
+| now        | 2023-11-14T22:13:20+00:00 |
++------------+---------------------------+"
+        );
+    }
+
+    #[test]
+    fn writes_markdown() {
+        let mut rows = rows();
+        rows[1][0] = "a|b".into();
+        assert_eq!(
+            render(&["input", "rfc3339"], &rows, Style::Markdown),
+            "\
+| input      | rfc3339                   |
+| ---------- | ------------------------- |
+| 1700000000 | 2023-11-14T22:13:20+00:00 |
+| a\\|b       | 2023-11-14T22:13:20+00:00 |"
+        );
+    }
+}
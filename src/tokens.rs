@@ -0,0 +1,535 @@
+//! Translating date format patterns between languages, as used by the `fmt-convert`
+//! subcommand: strftime (C, Python, chrono), Go reference-time layouts, moment.js and
+//! Java's `DateTimeFormatter`.
+//!
+//! A pattern is read into the fields it prints and the literal text between them, then
+//! written back with the target's tokens. A field the target cannot print is an error
+//! rather than a silent approximation, and so is a result the target would read back
+//! differently (Go, for one, has no way to escape literal digits).
+
+use std::fmt;
+
+/// A format pattern language.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Dialect {
+    /// `%Y-%m-%d %H:%M:%S`
+    Strftime,
+    /// `2006-01-02 15:04:05`
+    Go,
+    /// `YYYY-MM-DD HH:mm:ss`
+    Moment,
+    /// `yyyy-MM-dd HH:mm:ss`
+    Java,
+}
+
+impl fmt::Display for Dialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Dialect::Strftime => "strftime",
+            Dialect::Go => "Go",
+            Dialect::Moment => "moment.js",
+            Dialect::Java => "Java",
+        })
+    }
+}
+
+/// Something a pattern prints.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Field {
+    Year,
+    Year2,
+    Month,
+    MonthNoPad,
+    MonthAbbr,
+    MonthName,
+    Day,
+    DayNoPad,
+    DaySpacePad,
+    DayOfYear,
+    WeekdayAbbr,
+    WeekdayName,
+    Hour,
+    HourNoPad,
+    Hour12,
+    Hour12NoPad,
+    Minute,
+    MinuteNoPad,
+    Second,
+    SecondNoPad,
+    Millis,
+    Micros,
+    Nanos,
+    AmPm,
+    AmPmLower,
+    Offset,
+    OffsetColon,
+    /// `Z` for UTC, otherwise `+07:00`.
+    OffsetZ,
+    ZoneAbbr,
+    UnixSeconds,
+    UnixMillis,
+}
+
+impl Field {
+    fn is_fraction(self) -> bool {
+        matches!(self, Field::Millis | Field::Micros | Field::Nanos)
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Field::Year => "the four-digit year",
+            Field::Year2 => "the two-digit year",
+            Field::Month => "the zero-padded month",
+            Field::MonthNoPad => "the unpadded month",
+            Field::MonthAbbr => "the abbreviated month name",
+            Field::MonthName => "the full month name",
+            Field::Day => "the zero-padded day",
+            Field::DayNoPad => "the unpadded day",
+            Field::DaySpacePad => "the space-padded day",
+            Field::DayOfYear => "the day of the year",
+            Field::WeekdayAbbr => "the abbreviated weekday name",
+            Field::WeekdayName => "the full weekday name",
+            Field::Hour => "the zero-padded 24-hour hour",
+            Field::HourNoPad => "the unpadded 24-hour hour",
+            Field::Hour12 => "the zero-padded 12-hour hour",
+            Field::Hour12NoPad => "the unpadded 12-hour hour",
+            Field::Minute => "the zero-padded minute",
+            Field::MinuteNoPad => "the unpadded minute",
+            Field::Second => "the zero-padded second",
+            Field::SecondNoPad => "the unpadded second",
+            Field::Millis => "milliseconds",
+            Field::Micros => "microseconds",
+            Field::Nanos => "nanoseconds",
+            Field::AmPm => "AM/PM",
+            Field::AmPmLower => "lowercase am/pm",
+            Field::Offset => "the offset as +0700",
+            Field::OffsetColon => "the offset as +07:00",
+            Field::OffsetZ => "the offset as Z or +07:00",
+            Field::ZoneAbbr => "the zone abbreviation",
+            Field::UnixSeconds => "epoch seconds",
+            Field::UnixMillis => "epoch milliseconds",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Field(Field),
+    Literal(String),
+}
+
+/// Whether the tokens so far end in a `.` or `,`, which Go requires before a fraction.
+fn after_separator(tokens: &[Token]) -> bool {
+    matches!(tokens.last(), Some(Token::Literal(text)) if text.ends_with(['.', ',']))
+}
+
+/// Go reads any run of `0`s or `9`s after a `.` or `,` (and before anything but a
+/// digit) as fractional seconds: zeros keep trailing zeros, nines drop them. Only the
+/// zero-padded millis, micros and nanos have counterparts elsewhere.
+fn check_go_fraction(rest: &str) -> Result<(), String> {
+    let Some(digit @ ('0' | '9')) = rest.chars().next() else {
+        return Ok(());
+    };
+    let run = rest.len() - rest.trim_start_matches(digit).len();
+    if rest[run..].starts_with(|c: char| c.is_ascii_digit()) {
+        return Ok(());
+    }
+    match (digit, run) {
+        ('0', 3 | 6 | 9) => Ok(()),
+        _ => Err(format!(
+            "unsupported Go fractional seconds `{}`; only 000, 000000 and 000000000 have equivalents",
+            &rest[..run]
+        )),
+    }
+}
+
+/// Each dialect's tokens; when several print the same field, the first is written.
+const STRFTIME: &[(&str, Field)] = &[
+    ("%Y", Field::Year),
+    ("%y", Field::Year2),
+    ("%m", Field::Month),
+    ("%-m", Field::MonthNoPad),
+    ("%b", Field::MonthAbbr),
+    ("%B", Field::MonthName),
+    ("%d", Field::Day),
+    ("%-d", Field::DayNoPad),
+    ("%e", Field::DaySpacePad),
+    ("%j", Field::DayOfYear),
+    ("%a", Field::WeekdayAbbr),
+    ("%A", Field::WeekdayName),
+    ("%H", Field::Hour),
+    ("%-H", Field::HourNoPad),
+    ("%I", Field::Hour12),
+    ("%-I", Field::Hour12NoPad),
+    ("%M", Field::Minute),
+    ("%-M", Field::MinuteNoPad),
+    ("%S", Field::Second),
+    ("%-S", Field::SecondNoPad),
+    ("%3f", Field::Millis),
+    ("%6f", Field::Micros),
+    ("%9f", Field::Nanos),
+    ("%p", Field::AmPm),
+    ("%P", Field::AmPmLower),
+    ("%z", Field::Offset),
+    ("%:z", Field::OffsetColon),
+    ("%Z", Field::ZoneAbbr),
+    ("%s", Field::UnixSeconds),
+];
+
+/// strftime shorthands, read as what they stand for.
+const STRFTIME_SHORTHANDS: &[(&str, &str)] = &[
+    ("%F", "%Y-%m-%d"),
+    ("%T", "%H:%M:%S"),
+    ("%R", "%H:%M"),
+    ("%D", "%m/%d/%y"),
+    ("%.3f", ".%3f"),
+    ("%.6f", ".%6f"),
+    ("%.9f", ".%9f"),
+];
+
+const GO: &[(&str, Field)] = &[
+    ("2006", Field::Year),
+    ("06", Field::Year2),
+    ("01", Field::Month),
+    ("1", Field::MonthNoPad),
+    ("Jan", Field::MonthAbbr),
+    ("January", Field::MonthName),
+    ("02", Field::Day),
+    ("2", Field::DayNoPad),
+    ("_2", Field::DaySpacePad),
+    ("002", Field::DayOfYear),
+    ("Mon", Field::WeekdayAbbr),
+    ("Monday", Field::WeekdayName),
+    ("15", Field::Hour),
+    ("03", Field::Hour12),
+    ("3", Field::Hour12NoPad),
+    ("04", Field::Minute),
+    ("4", Field::MinuteNoPad),
+    ("05", Field::Second),
+    ("5", Field::SecondNoPad),
+    // Go only reads these right after a `.` or `,`, which stays a literal.
+    ("000", Field::Millis),
+    ("000000", Field::Micros),
+    ("000000000", Field::Nanos),
+    ("PM", Field::AmPm),
+    ("pm", Field::AmPmLower),
+    ("-0700", Field::Offset),
+    ("-07:00", Field::OffsetColon),
+    ("Z07:00", Field::OffsetZ),
+    ("MST", Field::ZoneAbbr),
+];
+
+const MOMENT: &[(&str, Field)] = &[
+    ("YYYY", Field::Year),
+    ("YY", Field::Year2),
+    ("MM", Field::Month),
+    ("M", Field::MonthNoPad),
+    ("MMM", Field::MonthAbbr),
+    ("MMMM", Field::MonthName),
+    ("DD", Field::Day),
+    ("D", Field::DayNoPad),
+    ("DDDD", Field::DayOfYear),
+    ("ddd", Field::WeekdayAbbr),
+    ("dddd", Field::WeekdayName),
+    ("HH", Field::Hour),
+    ("H", Field::HourNoPad),
+    ("hh", Field::Hour12),
+    ("h", Field::Hour12NoPad),
+    ("mm", Field::Minute),
+    ("m", Field::MinuteNoPad),
+    ("ss", Field::Second),
+    ("s", Field::SecondNoPad),
+    ("SSS", Field::Millis),
+    ("SSSSSS", Field::Micros),
+    ("SSSSSSSSS", Field::Nanos),
+    ("A", Field::AmPm),
+    ("a", Field::AmPmLower),
+    ("ZZ", Field::Offset),
+    ("Z", Field::OffsetColon),
+    ("z", Field::ZoneAbbr),
+    ("X", Field::UnixSeconds),
+    ("x", Field::UnixMillis),
+];
+
+const JAVA: &[(&str, Field)] = &[
+    ("yyyy", Field::Year),
+    ("uuuu", Field::Year),
+    ("yy", Field::Year2),
+    ("uu", Field::Year2),
+    ("MM", Field::Month),
+    ("M", Field::MonthNoPad),
+    ("MMM", Field::MonthAbbr),
+    ("MMMM", Field::MonthName),
+    ("dd", Field::Day),
+    ("d", Field::DayNoPad),
+    ("DDD", Field::DayOfYear),
+    ("EEE", Field::WeekdayAbbr),
+    ("EEEE", Field::WeekdayName),
+    ("HH", Field::Hour),
+    ("H", Field::HourNoPad),
+    ("hh", Field::Hour12),
+    ("h", Field::Hour12NoPad),
+    ("mm", Field::Minute),
+    ("m", Field::MinuteNoPad),
+    ("ss", Field::Second),
+    ("s", Field::SecondNoPad),
+    ("SSS", Field::Millis),
+    ("SSSSSS", Field::Micros),
+    ("SSSSSSSSS", Field::Nanos),
+    ("a", Field::AmPm),
+    ("xx", Field::Offset),
+    ("Z", Field::Offset),
+    ("xxx", Field::OffsetColon),
+    ("XXX", Field::OffsetZ),
+    ("z", Field::ZoneAbbr),
+];
+
+impl Dialect {
+    fn table(self) -> &'static [(&'static str, Field)] {
+        match self {
+            Dialect::Strftime => STRFTIME,
+            Dialect::Go => GO,
+            Dialect::Moment => MOMENT,
+            Dialect::Java => JAVA,
+        }
+    }
+
+    /// The longest token at the start of `s`.
+    fn token_at(self, s: &str) -> Option<(&'static str, Field)> {
+        self.table()
+            .iter()
+            .filter(|(token, _)| s.starts_with(token))
+            .max_by_key(|(token, _)| token.len())
+            .copied()
+    }
+
+    fn tokenize(self, pattern: &str) -> Result<Vec<Token>, String> {
+        let mut tokens = Vec::new();
+        let literal = |tokens: &mut Vec<Token>, s: &str| match tokens.last_mut() {
+            Some(Token::Literal(text)) => text.push_str(s),
+            _ => tokens.push(Token::Literal(s.to_string())),
+        };
+        let mut rest = pattern;
+        while let Some(c) = rest.chars().next() {
+            if self == Dialect::Go && after_separator(&tokens) {
+                check_go_fraction(rest)?;
+            }
+            if let Some((token, field)) = self.token_at(rest)
+                && !(self == Dialect::Go && field.is_fraction() && !after_separator(&tokens))
+            {
+                tokens.push(Token::Field(field));
+                rest = &rest[token.len()..];
+                continue;
+            }
+            match (self, c) {
+                (Dialect::Strftime, '%') => {
+                    if let Some(after) = rest.strip_prefix("%%") {
+                        literal(&mut tokens, "%");
+                        rest = after;
+                    } else if let Some(&(short, expanded)) = STRFTIME_SHORTHANDS
+                        .iter()
+                        .find(|(s, _)| rest.starts_with(s))
+                    {
+                        for token in self.tokenize(expanded)? {
+                            match token {
+                                Token::Literal(text) => literal(&mut tokens, &text),
+                                field => tokens.push(field),
+                            }
+                        }
+                        rest = &rest[short.len()..];
+                    } else {
+                        let spec: String = rest.chars().take(2).collect();
+                        return Err(format!("unsupported strftime specifier `{spec}`"));
+                    }
+                }
+                (Dialect::Moment, '[') => {
+                    let (text, after) = rest[1..]
+                        .split_once(']')
+                        .ok_or("unclosed `[` in moment.js pattern")?;
+                    literal(&mut tokens, text);
+                    rest = after;
+                }
+                (Dialect::Java, '\'') => {
+                    if let Some(after) = rest.strip_prefix("''") {
+                        literal(&mut tokens, "'");
+                        rest = after;
+                        continue;
+                    }
+                    // Inside quotes, `''` is a quote too.
+                    rest = &rest[1..];
+                    loop {
+                        let (text, after) = rest
+                            .split_once('\'')
+                            .ok_or("unclosed `'` in Java pattern")?;
+                        literal(&mut tokens, text);
+                        match after.strip_prefix('\'') {
+                            Some(after) => {
+                                literal(&mut tokens, "'");
+                                rest = after;
+                            }
+                            None => {
+                                rest = after;
+                                break;
+                            }
+                        }
+                    }
+                }
+                (Dialect::Moment, c) if c.is_ascii_alphabetic() => {
+                    let run: String = rest.chars().take_while(|&x| x == c).collect();
+                    return Err(format!("unsupported moment.js tokens `{run}`"));
+                }
+                (Dialect::Java, c) if c.is_ascii_alphabetic() => {
+                    let run: String = rest.chars().take_while(|&x| x == c).collect();
+                    return Err(format!("unsupported Java pattern letters `{run}`"));
+                }
+                _ => {
+                    literal(&mut tokens, &rest[..c.len_utf8()]);
+                    rest = &rest[c.len_utf8()..];
+                }
+            }
+        }
+        Ok(tokens)
+    }
+
+    fn write_literal(self, text: &str, out: &mut String) {
+        match self {
+            Dialect::Strftime => out.push_str(&text.replace('%', "%%")),
+            Dialect::Go => out.push_str(text),
+            Dialect::Moment if text.contains(|c: char| c.is_ascii_alphabetic() || c == '[') => {
+                out.push('[');
+                out.push_str(text);
+                out.push(']');
+            }
+            Dialect::Java if text == "'" => out.push_str("''"),
+            Dialect::Java if text.contains(|c: char| c.is_ascii_alphabetic() || c == '\'') => {
+                out.push('\'');
+                out.push_str(&text.replace('\'', "''"));
+                out.push('\'');
+            }
+            Dialect::Moment | Dialect::Java => out.push_str(text),
+        }
+    }
+}
+
+/// `pattern`, written in `from`, rewritten in `to`.
+pub fn convert(pattern: &str, from: Dialect, to: Dialect) -> Result<String, String> {
+    let tokens = from.tokenize(pattern)?;
+    let mut out = String::new();
+    for token in &tokens {
+        match token {
+            Token::Literal(text) => to.write_literal(text, &mut out),
+            Token::Field(field)
+                if to == Dialect::Go && field.is_fraction() && !out.ends_with(['.', ',']) =>
+            {
+                return Err(format!(
+                    "Go can only print {} after a `.` or `,`",
+                    field.describe()
+                ));
+            }
+            Token::Field(field) => {
+                let (written, _) = to
+                    .table()
+                    .iter()
+                    .find(|(_, f)| f == field)
+                    .ok_or_else(|| format!("{to} has no token for {}", field.describe()))?;
+                out.push_str(written);
+            }
+        }
+    }
+    if to.tokenize(&out).as_ref() != Ok(&tokens) {
+        return Err(format!(
+            "`{out}` would not read back the same in {to}; move literal text that looks like a {to} token out of the pattern"
+        ));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_between_dialects() {
+        let strftime = "%Y-%m-%d %H:%M:%S";
+        assert_eq!(
+            convert(strftime, Dialect::Strftime, Dialect::Go).unwrap(),
+            "2006-01-02 15:04:05"
+        );
+        assert_eq!(
+            convert(strftime, Dialect::Strftime, Dialect::Moment).unwrap(),
+            "YYYY-MM-DD HH:mm:ss"
+        );
+        assert_eq!(
+            convert(strftime, Dialect::Strftime, Dialect::Java).unwrap(),
+            "yyyy-MM-dd HH:mm:ss"
+        );
+        assert_eq!(
+            convert(
+                "Mon, 02 Jan 2006 15:04:05.000 -0700",
+                Dialect::Go,
+                Dialect::Strftime
+            )
+            .unwrap(),
+            "%a, %d %b %Y %H:%M:%S.%3f %z"
+        );
+        assert_eq!(
+            convert("%FT%T%:z", Dialect::Strftime, Dialect::Java).unwrap(),
+            "yyyy-MM-dd'T'HH:mm:ssxxx"
+        );
+        assert_eq!(
+            convert("YYYY-MM-DD[T]HH:mm:ss.SSSZ", Dialect::Moment, Dialect::Java).unwrap(),
+            "yyyy-MM-dd'T'HH:mm:ss.SSSxxx"
+        );
+        assert_eq!(
+            convert("h:mm a 'o''clock'", Dialect::Java, Dialect::Moment).unwrap(),
+            "h:mm A[ o'clock]"
+        );
+    }
+
+    #[test]
+    fn refuses_what_the_target_cannot_say() {
+        let err = convert("%s", Dialect::Strftime, Dialect::Go).unwrap_err();
+        assert_eq!(err, "Go has no token for epoch seconds");
+        // Literal digits cannot be escaped in a Go layout.
+        assert!(convert("%Y 1", Dialect::Strftime, Dialect::Go).is_err());
+        // Go's fractional seconds need a `.` or `,` in front.
+        assert!(convert("%S%3f", Dialect::Strftime, Dialect::Go).is_err());
+        assert!(convert("%Q", Dialect::Strftime, Dialect::Go).is_err());
+        assert!(convert("yyyy qq", Dialect::Java, Dialect::Go).is_err());
+        assert!(convert("[oops", Dialect::Moment, Dialect::Go).is_err());
+    }
+
+    #[test]
+    fn rejects_tokens_it_does_not_know() {
+        // moment.js letters outside `[...]` are tokens, like Java's, never literal text.
+        for pattern in ["YYYY-[W]WW", "Do MMMM", "Q", "gggg-ww", "w"] {
+            let err = convert(pattern, Dialect::Moment, Dialect::Strftime).unwrap_err();
+            assert!(
+                err.starts_with("unsupported moment.js tokens"),
+                "{pattern}: {err}"
+            );
+        }
+        assert_eq!(
+            convert("YYYY-[W]MM", Dialect::Moment, Dialect::Strftime).unwrap(),
+            "%Y-W%m"
+        );
+
+        for pattern in ["15:04:05.999", "15:04:05.9", "15:04:05,00", "05.0000"] {
+            let err = convert(pattern, Dialect::Go, Dialect::Strftime).unwrap_err();
+            assert!(
+                err.starts_with("unsupported Go fractional seconds"),
+                "{pattern}: {err}"
+            );
+        }
+        assert_eq!(
+            convert("15:04:05,000000", Dialect::Go, Dialect::Strftime).unwrap(),
+            "%H:%M:%S,%6f"
+        );
+        // Digits after the run make it an ordinary field, as in `01.02.2006`.
+        assert_eq!(
+            convert("01.02.2006", Dialect::Go, Dialect::Strftime).unwrap(),
+            "%m.%d.%Y"
+        );
+    }
+}
@@ -0,0 +1,170 @@
+//! `epoch tui`: an interactive converter that re-renders on every keystroke.
+
+use chrono::{DateTime, Utc};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::io::{self, IsTerminal};
+
+use epoch::zone::TzChoice;
+
+const ZONE_FORMAT: &str = "%Y-%m-%d %H:%M:%S %:z";
+
+/// A numeric input read as seconds, millis and micros; empty for anything else.
+pub fn unit_lines(input: &str) -> Vec<String> {
+    let Ok(raw) = input.trim().parse::<i64>() else {
+        return Vec::new();
+    };
+    [
+        ("seconds", DateTime::from_timestamp(raw, 0)),
+        ("millis", DateTime::from_timestamp_millis(raw)),
+        ("micros", DateTime::from_timestamp_micros(raw)),
+    ]
+    .into_iter()
+    .map(|(unit, dt)| {
+        let shown = dt.map_or("out of range".to_string(), |dt| dt.to_rfc3339());
+        format!("{unit:<8} {shown}")
+    })
+    .collect()
+}
+
+/// The parsed instant in each of `zones`, or the parse error.
+pub fn zone_lines(
+    input: &str,
+    zones: &[TzChoice],
+    parse: &dyn Fn(&str) -> Result<DateTime<Utc>, String>,
+) -> Result<Vec<String>, String> {
+    let utc = parse(input.trim())?;
+    let width = zones.iter().map(|z| z.to_string().len()).max().unwrap_or(0);
+    Ok(zones
+        .iter()
+        .map(|zone| {
+            let shown = epoch::format_output(utc, *zone, Some(ZONE_FORMAT));
+            format!("{:<width$}  {shown}", zone.to_string())
+        })
+        .collect())
+}
+
+fn draw(
+    frame: &mut Frame,
+    input: &str,
+    zones: &[TzChoice],
+    parse: &dyn Fn(&str) -> Result<DateTime<Utc>, String>,
+) {
+    let [input_area, units_area, zones_area] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Length(5),
+        Constraint::Min(0),
+    ])
+    .areas(frame.area());
+
+    frame.render_widget(
+        Paragraph::new(input).block(Block::bordered().title(" Input (Enter / Esc to quit) ")),
+        input_area,
+    );
+    frame.set_cursor_position((
+        input_area.x + 1 + input.chars().count() as u16,
+        input_area.y + 1,
+    ));
+
+    let units: Vec<Line> = unit_lines(input).into_iter().map(Line::from).collect();
+    frame.render_widget(
+        Paragraph::new(units).block(Block::bordered().title(" As unix time ")),
+        units_area,
+    );
+
+    let zones = if input.trim().is_empty() {
+        Paragraph::new("")
+    } else {
+        match zone_lines(input, zones, parse) {
+            Ok(lines) => Paragraph::new(lines.into_iter().map(Line::from).collect::<Vec<_>>()),
+            Err(e) => Paragraph::new(e).style(Style::default().fg(Color::Red)),
+        }
+    };
+    frame.render_widget(zones.block(Block::bordered().title(" Zones ")), zones_area);
+}
+
+fn event_loop(
+    terminal: &mut DefaultTerminal,
+    zones: &[TzChoice],
+    parse: &dyn Fn(&str) -> Result<DateTime<Utc>, String>,
+) -> io::Result<()> {
+    let mut input = String::new();
+    loop {
+        terminal.draw(|frame| draw(frame, &input, zones, parse))?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Enter | KeyCode::Esc => return Ok(()),
+            KeyCode::Char('c' | 'd') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Ok(());
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => input.clear(),
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Run the interactive converter until Enter, Esc or Ctrl-C.
+pub fn run(
+    zones: &[TzChoice],
+    parse: &dyn Fn(&str) -> Result<DateTime<Utc>, String>,
+) -> io::Result<()> {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return Err(io::Error::other("epoch tui needs an interactive terminal"));
+    }
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, zones, parse);
+    ratatui::restore();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn parse(s: &str) -> Result<DateTime<Utc>, String> {
+        s.parse::<i64>()
+            .map(|secs| Utc.timestamp_opt(secs, 0).unwrap())
+            .map_err(|_| "Expected format: YYYY/MM/DD HH:MM:SS".to_string())
+    }
+
+    #[test]
+    fn reads_numbers_in_each_unit() {
+        assert_eq!(
+            unit_lines("1700000000"),
+            [
+                "seconds  2023-11-14T22:13:20+00:00",
+                "millis   1970-01-20T16:13:20+00:00",
+                "micros   1970-01-01T00:28:20+00:00",
+            ]
+        );
+        assert!(unit_lines("2024/01/01 00:00:00").is_empty());
+    }
+
+    #[test]
+    fn shows_zones_or_the_parse_error() {
+        let zones = [TzChoice::Utc, "Asia/Tokyo".parse().unwrap()];
+        assert_eq!(
+            zone_lines("1700000000", &zones, &parse).unwrap(),
+            [
+                "UTC         2023-11-14 22:13:20 +00:00",
+                "Asia/Tokyo  2023-11-15 07:13:20 +09:00",
+            ]
+        );
+        assert!(zone_lines("nope", &zones, &parse).is_err());
+    }
+}
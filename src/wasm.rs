@@ -0,0 +1,89 @@
+//! JavaScript bindings (`wasm` feature), built with e.g.
+//! `wasm-pack build --target web --no-default-features --features wasm`.
+//!
+//! Instants cross the boundary as unix milliseconds, since that is what JS `Date` uses.
+
+use chrono::{DateTime, Utc};
+use wasm_bindgen::prelude::*;
+
+use crate::zone::TzChoice;
+use crate::{ParsedAs, TsUnit};
+
+fn tz(s: &str) -> Result<TzChoice, JsError> {
+    s.parse().map_err(|e: String| JsError::new(&e))
+}
+
+fn ts_unit(ts: Option<String>) -> Result<Option<TsUnit>, JsError> {
+    match ts.as_deref() {
+        None => Ok(None),
+        Some("seconds") => Ok(Some(TsUnit::Seconds)),
+        Some("millis") => Ok(Some(TsUnit::Millis)),
+        Some("micros") => Ok(Some(TsUnit::Micros)),
+        Some("nanos") => Ok(Some(TsUnit::Nanos)),
+        Some(other) => Err(JsError::new(&format!(
+            "invalid ts `{other}`: expected seconds, millis, micros or nanos"
+        ))),
+    }
+}
+
+fn parse(
+    input: &str,
+    input_tz: &str,
+    ts: Option<String>,
+    prefer_zone: Option<String>,
+) -> Result<(DateTime<Utc>, ParsedAs, TzChoice), JsError> {
+    let input_tz = tz(input_tz)?;
+    let prefer_zone: Vec<String> = prefer_zone
+        .iter()
+        .flat_map(|p| p.split(','))
+        .map(String::from)
+        .collect();
+    let (utc, parsed_as) = crate::parse_input_to_utc(input, input_tz, ts_unit(ts)?, &prefer_zone)
+        .map_err(|e| JsError::new(&e.to_string()))?;
+    Ok((utc, parsed_as, input_tz))
+}
+
+/// Parse `input` exactly like the CLI does and return unix milliseconds.
+///
+/// `input_tz` takes the `--input-tz` values (`utc`, `local`, `Europe/Paris`, `+05:30`);
+/// `ts` is `seconds`, `millis`, `micros` or `nanos`; `prefer_zone` is a comma-separated
+/// region list.
+#[wasm_bindgen(js_name = parseInputToUtc)]
+pub fn parse_input_to_utc(
+    input: &str,
+    input_tz: &str,
+    ts: Option<String>,
+    prefer_zone: Option<String>,
+) -> Result<f64, JsError> {
+    let (utc, _, _) = parse(input, input_tz, ts, prefer_zone)?;
+    Ok(utc.timestamp_millis() as f64)
+}
+
+/// Render unix milliseconds in `output_tz`, as RFC 3339 or with a strftime `format`.
+#[wasm_bindgen(js_name = formatOutput)]
+pub fn format_output(
+    unix_millis: f64,
+    output_tz: &str,
+    format: Option<String>,
+) -> Result<String, JsError> {
+    let utc = DateTime::from_timestamp_millis(unix_millis as i64)
+        .ok_or_else(|| JsError::new("timestamp out of range"))?;
+    if let Some(format) = &format {
+        crate::check_strftime(format).map_err(|e| JsError::new(&e.to_string()))?;
+    }
+    Ok(crate::format_output(utc, tz(output_tz)?, format.as_deref()))
+}
+
+/// The CLI's `--json` result for `input`, as a JSON string.
+#[wasm_bindgen(js_name = parseToJson)]
+pub fn parse_to_json(
+    input: &str,
+    input_tz: &str,
+    output_tz: &str,
+    ts: Option<String>,
+    prefer_zone: Option<String>,
+) -> Result<String, JsError> {
+    let (utc, parsed_as, input_tz) = parse(input, input_tz, ts, prefer_zone)?;
+    let json = crate::instant_json(input, utc, &parsed_as, input_tz, tz(output_tz)?, None);
+    Ok(json.to_string())
+}
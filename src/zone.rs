@@ -0,0 +1,369 @@
+//! Timezone selection: `utc`, `local`, IANA zone names and fixed offsets.
+//!
+//! IANA zone names, and the zone queries built on them, need the `tz` feature.
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, Utc};
+#[cfg(feature = "tz")]
+use chrono::{Duration, Offset, TimeZone};
+#[cfg(feature = "tz")]
+use chrono_tz::{OffsetComponents, TZ_VARIANTS, Tz};
+use std::env;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::offset;
+
+/// A timezone accepted by `--input-tz` / `--output-tz`: `utc`, `local`, an IANA zone name
+/// such as `Europe/Paris`, or a fixed offset such as `+05:30` / `-0700`.
+#[derive(Copy, Clone, Debug)]
+pub enum TzChoice {
+    Utc,
+    Local,
+    Fixed(FixedOffset),
+    #[cfg(feature = "tz")]
+    Named(Tz),
+    /// `local`, pinned to the IANA zone it resolved to (see [`TzChoice::pin_local`]).
+    #[cfg(feature = "tz")]
+    PinnedLocal(Tz),
+}
+
+impl FromStr for TzChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("utc") {
+            Ok(TzChoice::Utc)
+        } else if s.eq_ignore_ascii_case("local") {
+            Ok(TzChoice::Local)
+        } else if let Some(off) = offset::parse_numeric_offset(s) {
+            Ok(TzChoice::Fixed(off))
+        } else {
+            Self::from_zone_name(s)
+        }
+    }
+}
+
+impl TzChoice {
+    /// `Local` resolved once to the IANA zone it maps to, so that converting many instants
+    /// uses chrono-tz's compiled rules instead of consulting the system zone every time.
+    /// Other choices, and local zones chrono-tz does not know (such as arbitrary POSIX
+    /// `TZ` strings), are returned unchanged.
+    #[cfg(feature = "tz")]
+    pub fn pin_local(self) -> TzChoice {
+        static LOCAL: OnceLock<Option<Tz>> = OnceLock::new();
+        let local = LOCAL.get_or_init(|| {
+            local_zone_name().and_then(|name| Tz::from_str_insensitive(&name).ok())
+        });
+        match (self, local) {
+            (TzChoice::Local, Some(tz)) => TzChoice::PinnedLocal(*tz),
+            _ => self,
+        }
+    }
+
+    #[cfg(feature = "tz")]
+    fn from_zone_name(s: &str) -> Result<Self, String> {
+        Tz::from_str_insensitive(s).map(TzChoice::Named).map_err(|_| {
+                format!(
+                    "expected `utc`, `local`, an IANA zone (see `tz list`) or an offset like +05:30, got `{s}`"
+                )
+            })
+    }
+
+    #[cfg(not(feature = "tz"))]
+    fn from_zone_name(s: &str) -> Result<Self, String> {
+        Err(format!(
+            "expected `utc`, `local` or an offset like +05:30, got `{s}` (IANA zones need the `tz` feature)"
+        ))
+    }
+
+    /// Wall-clock time in this zone at `utc`.
+    pub fn naive_local(&self, utc: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            TzChoice::Utc => utc.naive_utc(),
+            TzChoice::Local => utc.with_timezone(&Local).naive_local(),
+            TzChoice::Fixed(off) => utc.with_timezone(off).naive_local(),
+            #[cfg(feature = "tz")]
+            TzChoice::Named(tz) | TzChoice::PinnedLocal(tz) => utc.with_timezone(tz).naive_local(),
+        }
+    }
+}
+
+impl fmt::Display for TzChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TzChoice::Utc => f.write_str("UTC"),
+            TzChoice::Local => f.write_str("local"),
+            #[cfg(feature = "tz")]
+            TzChoice::PinnedLocal(_) => f.write_str("local"),
+            TzChoice::Fixed(off) => write!(f, "{off}"),
+            #[cfg(feature = "tz")]
+            TzChoice::Named(tz) => f.write_str(tz.name()),
+        }
+    }
+}
+
+/// IANA zones whose name contains `pattern` (case-insensitive), in name order.
+#[cfg(feature = "tz")]
+pub fn list_zones(pattern: Option<&str>) -> impl Iterator<Item = Tz> {
+    let pattern = pattern.map(str::to_ascii_lowercase);
+    TZ_VARIANTS
+        .iter()
+        .copied()
+        .filter(move |tz| match &pattern {
+            Some(p) => tz.name().to_ascii_lowercase().contains(p.as_str()),
+            None => true,
+        })
+}
+
+/// Directories searched for TZif files named by `TZ`.
+const ZONEINFO_DIRS: &[&str] = &[
+    "/usr/share/zoneinfo",
+    "/usr/lib/zoneinfo",
+    "/usr/share/lib/zoneinfo",
+];
+
+/// Resolve an IANA zone name; `local` maps to the zone the local time resolves to.
+#[cfg(feature = "tz")]
+pub fn resolve_iana(name: &str) -> Result<Tz, String> {
+    let resolved = if name.eq_ignore_ascii_case("local") {
+        local_zone_name().ok_or("Could not determine the local timezone")?
+    } else {
+        name.to_string()
+    };
+    Tz::from_str_insensitive(&resolved)
+        .map_err(|_| format!("Unknown timezone: {resolved} (see `tz list`)"))
+}
+
+/// The offset a zone observes at some instant.
+#[cfg(feature = "tz")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ZoneOffset {
+    pub utc_offset: FixedOffset,
+    /// Daylight-saving component of `utc_offset` (zero outside DST).
+    pub dst_offset: Duration,
+    pub abbreviation: String,
+}
+
+#[cfg(feature = "tz")]
+impl ZoneOffset {
+    pub fn is_dst(&self) -> bool {
+        !self.dst_offset.is_zero()
+    }
+}
+
+#[cfg(feature = "tz")]
+pub fn offset_at(tz: Tz, at: DateTime<Utc>) -> ZoneOffset {
+    let offset = tz.offset_from_utc_datetime(&at.naive_utc());
+    ZoneOffset {
+        utc_offset: offset.fix(),
+        dst_offset: offset.dst_offset(),
+        abbreviation: offset.to_string(),
+    }
+}
+
+/// A change in the offset a zone observes.
+#[cfg(feature = "tz")]
+#[derive(Clone, Debug)]
+pub struct Transition {
+    /// First instant observing the new offset.
+    pub at: DateTime<Utc>,
+    pub before: ZoneOffset,
+    pub after: ZoneOffset,
+}
+
+#[cfg(feature = "tz")]
+impl Transition {
+    /// How far the clocks move: positive when they go forward.
+    pub fn change(&self) -> Duration {
+        Duration::seconds(i64::from(
+            self.after.utc_offset.local_minus_utc() - self.before.utc_offset.local_minus_utc(),
+        ))
+    }
+}
+
+/// Step used when scanning for transitions. Zones never change offset twice within it.
+#[cfg(feature = "tz")]
+const SCAN_STEP_HOURS: i64 = 6;
+/// How far to scan before concluding a zone has no (further) transitions.
+#[cfg(feature = "tz")]
+const SCAN_HORIZON_DAYS: i64 = 50 * 366;
+
+/// The first transition strictly after `after`, if one happens within the scan horizon.
+#[cfg(feature = "tz")]
+pub fn next_transition(tz: Tz, after: DateTime<Utc>) -> Option<Transition> {
+    scan_transition(tz, after, Duration::hours(SCAN_STEP_HOURS))
+}
+
+/// The latest transition at or before `at`, if one happened within the scan horizon.
+#[cfg(feature = "tz")]
+pub fn prev_transition(tz: Tz, at: DateTime<Utc>) -> Option<Transition> {
+    scan_transition(tz, at, Duration::hours(-SCAN_STEP_HOURS))
+}
+
+#[cfg(feature = "tz")]
+fn scan_transition(tz: Tz, from: DateTime<Utc>, step: Duration) -> Option<Transition> {
+    // Transitions happen on whole seconds, so the scan can work in unix seconds.
+    let at = |secs: i64| DateTime::from_timestamp(secs, 0);
+    let start = offset_at(tz, from);
+    let steps = SCAN_HORIZON_DAYS * 24 / SCAN_STEP_HOURS;
+
+    let mut probe = from.timestamp();
+    for _ in 0..steps {
+        let next = probe + step.num_seconds();
+        if offset_at(tz, at(next)?) != start {
+            // Bisect the window down to the second the offset changes.
+            let (mut lo, mut hi) = (probe.min(next), probe.max(next));
+            let lo_offset = offset_at(tz, at(lo)?);
+            while hi - lo > 1 {
+                let mid = lo + (hi - lo) / 2;
+                if offset_at(tz, at(mid)?) == lo_offset {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            return Some(Transition {
+                at: at(hi)?,
+                before: lo_offset,
+                after: offset_at(tz, at(hi)?),
+            });
+        }
+        probe = next;
+    }
+    None
+}
+
+/// Name of the zone `local` resolves to.
+///
+/// The `TZ` environment variable wins when it names something usable (this matches how
+/// chrono picks the local zone); otherwise the system zone is reported. The lookup is
+/// done once per process, which matters for long-running `serve` / `daemon` modes.
+pub fn local_zone_name() -> Option<String> {
+    static NAME: OnceLock<Option<String>> = OnceLock::new();
+    NAME.get_or_init(|| {
+        env::var("TZ")
+            .ok()
+            .and_then(|tz| zone_name_from_tz_var(&tz))
+            .or_else(|| iana_time_zone::get_timezone().ok())
+    })
+    .clone()
+}
+
+/// Interpret a `TZ` value: `Asia/Tokyo`, `:Asia/Tokyo`, `:/etc/localtime`, a path to a
+/// TZif file, or a POSIX TZ string such as `EST5EDT`.
+///
+/// Paths are reported by the zone name they point at when they live under a zoneinfo
+/// directory. Returns `None` for empty or unusable values, in which case the system zone
+/// applies.
+fn zone_name_from_tz_var(tz: &str) -> Option<String> {
+    let tz = tz.strip_prefix(':').unwrap_or(tz);
+    if tz.is_empty() {
+        return None;
+    }
+
+    if tz.starts_with('/') {
+        let path = Path::new(tz).canonicalize().ok()?;
+        let name = path
+            .to_str()?
+            .split_once("/zoneinfo/")
+            .map_or(path.to_str()?, |(_, name)| name);
+        return Some(name.to_string());
+    }
+
+    let in_zoneinfo = ZONEINFO_DIRS
+        .iter()
+        .any(|dir| Path::new(dir).join(tz).is_file());
+    // POSIX TZ strings always carry an hour offset, e.g. `EST5EDT` or `<+09>-9`.
+    let posix = tz.bytes().any(|b| b.is_ascii_digit());
+
+    (in_zoneinfo || posix).then(|| tz.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tz_choices() {
+        assert!(matches!("UTC".parse(), Ok(TzChoice::Utc)));
+        assert!(matches!("local".parse(), Ok(TzChoice::Local)));
+
+        let tz: TzChoice = "-0700".parse().unwrap();
+        assert_eq!(tz.to_string(), "-07:00");
+
+        assert!("Mars/Olympus".parse::<TzChoice>().is_err());
+    }
+
+    #[cfg(feature = "tz")]
+    #[test]
+    fn parses_iana_zone_names() {
+        let tz: TzChoice = "europe/paris".parse().unwrap();
+        assert_eq!(tz.to_string(), "Europe/Paris");
+    }
+
+    #[cfg(feature = "tz")]
+    #[test]
+    fn pinned_local_keeps_its_name_and_offsets() {
+        let pinned = TzChoice::Local.pin_local();
+        assert_eq!(pinned.to_string(), "local");
+        assert!(matches!(TzChoice::Utc.pin_local(), TzChoice::Utc));
+
+        let at = Utc.with_ymd_and_hms(2023, 7, 1, 12, 0, 0).unwrap();
+        assert_eq!(pinned.naive_local(at), TzChoice::Local.naive_local(at));
+    }
+
+    #[cfg(feature = "tz")]
+    #[test]
+    fn filters_zone_list() {
+        let names: Vec<&str> = list_zones(Some("bucha")).map(|tz| tz.name()).collect();
+        assert_eq!(names, ["Europe/Bucharest"]);
+        assert!(list_zones(None).count() > 400);
+    }
+
+    #[cfg(feature = "tz")]
+    #[test]
+    fn finds_surrounding_dst_transitions() {
+        let tz = chrono_tz::Europe::Bucharest;
+        let at = Utc.with_ymd_and_hms(2023, 7, 1, 12, 0, 0).unwrap();
+
+        let now = offset_at(tz, at);
+        assert!(now.is_dst());
+        assert_eq!(now.abbreviation, "EEST");
+
+        let prev = prev_transition(tz, at).unwrap();
+        assert_eq!(prev.at, Utc.with_ymd_and_hms(2023, 3, 26, 1, 0, 0).unwrap());
+        assert_eq!(prev.before.abbreviation, "EET");
+        assert_eq!(prev.after, now);
+
+        let next = next_transition(tz, at).unwrap();
+        assert_eq!(
+            next.at,
+            Utc.with_ymd_and_hms(2023, 10, 29, 1, 0, 0).unwrap()
+        );
+        assert_eq!(next.after.utc_offset.local_minus_utc(), 2 * 3600);
+        assert!(!next.after.is_dst());
+    }
+
+    #[cfg(feature = "tz")]
+    #[test]
+    fn zones_without_transitions_report_none() {
+        let at = Utc.with_ymd_and_hms(2023, 7, 1, 12, 0, 0).unwrap();
+        assert!(next_transition(chrono_tz::UTC, at).is_none());
+    }
+
+    #[test]
+    fn reads_posix_tz_strings_and_ignores_empty_values() {
+        assert_eq!(zone_name_from_tz_var("EST5EDT").as_deref(), Some("EST5EDT"));
+        assert_eq!(zone_name_from_tz_var(":UTC0").as_deref(), Some("UTC0"));
+        assert_eq!(zone_name_from_tz_var(""), None);
+        assert_eq!(zone_name_from_tz_var(":"), None);
+    }
+
+    #[test]
+    fn rejects_unknown_zone_names() {
+        assert_eq!(zone_name_from_tz_var("Bogus/Zone"), None);
+        assert_eq!(zone_name_from_tz_var("/nonexistent/zoneinfo/Bogus"), None);
+    }
+}
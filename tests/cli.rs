@@ -50,3 +50,2257 @@ fn cli_json_is_valid_json_pretty() {
     assert_eq!(v["unix_seconds"], 1700000000);
     assert!(v["rfc3339"].as_str().unwrap().starts_with("2023-11-14T"));
 }
+
+#[test]
+fn cli_json_reports_week_date_input() {
+    let out = Command::new(bin())
+        .args(["2024-W05-3", "--input-tz", "utc", "--json"])
+        .output()
+        .expect("run timeparse");
+
+    assert!(out.status.success());
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid json");
+    assert_eq!(v["parsed_as"], "week_date");
+    assert_eq!(v["unix_seconds"], 1706659200);
+}
+
+#[test]
+fn cli_ambiguous_zone_abbreviation_exits_with_tz_error() {
+    let out = Command::new(bin())
+        .arg("2023/11/14 22:13:20 IST")
+        .output()
+        .expect("run timeparse");
+
+    assert_eq!(out.status.code(), Some(4));
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(stderr.contains("--prefer-zone"));
+
+    let out = Command::new(bin())
+        .args(["2023/11/14 22:13:20 IST", "--prefer-zone", "in", "--unix"])
+        .output()
+        .expect("run timeparse");
+
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8(out.stdout).unwrap().trim(), "1699980200");
+}
+
+#[test]
+fn cli_json_records_source_offset() {
+    let out = Command::new(bin())
+        .args(["2023/11/15 03:43:20 +05:30", "--json"])
+        .output()
+        .expect("run timeparse");
+
+    assert!(out.status.success());
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid json");
+    assert_eq!(v["unix_seconds"], 1700000000);
+    assert_eq!(v["source_offset"], "+05:30");
+
+    let out = Command::new(bin())
+        .args(["1700000000", "--json"])
+        .output()
+        .expect("run timeparse");
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid json");
+    assert!(v["source_offset"].is_null());
+}
+
+#[test]
+fn cli_fixed_offset_output_tz() {
+    let out = Command::new(bin())
+        .args(["1700000000", "--output-tz", "-0700"])
+        .output()
+        .expect("run timeparse");
+
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().trim(),
+        "2023-11-14T15:13:20-07:00"
+    );
+}
+
+#[test]
+fn cli_local_follows_tz_environment_variable() {
+    let out = Command::new(bin())
+        .args(["1700000000", "--output-tz", "local", "--json"])
+        .env("TZ", "Asia/Tokyo")
+        .output()
+        .expect("run timeparse");
+
+    assert!(out.status.success());
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid json");
+    assert_eq!(v["local_tz"], "Asia/Tokyo");
+    assert_eq!(v["rfc3339"], "2023-11-15T07:13:20+09:00");
+}
+
+#[test]
+fn cli_tz_list_filters_zone_names() {
+    let out = Command::new(bin())
+        .args(["tz", "list", "york"])
+        .output()
+        .expect("run timeparse");
+
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert_eq!(stdout.lines().collect::<Vec<_>>(), ["America/New_York"]);
+}
+
+#[test]
+fn cli_tz_info_json_reports_dst_and_transitions() {
+    let out = Command::new(bin())
+        .args([
+            "tz",
+            "info",
+            "Europe/Bucharest",
+            "2023/07/01 12:00:00",
+            "--json",
+        ])
+        .output()
+        .expect("run timeparse");
+
+    assert!(out.status.success());
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid json");
+    assert_eq!(v["utc_offset"], "+03:00");
+    assert_eq!(v["abbreviation"], "EEST");
+    assert_eq!(v["dst"], true);
+    assert_eq!(v["previous_transition"]["at"], "2023-03-26T01:00:00+00:00");
+    assert_eq!(v["next_transition"]["at"], "2023-10-29T01:00:00+00:00");
+    assert_eq!(v["next_transition"]["to_abbreviation"], "EET");
+}
+
+#[test]
+fn cli_dst_next_and_prev() {
+    let out = Command::new(bin())
+        .args([
+            "dst-next",
+            "--tz",
+            "America/New_York",
+            "2024/01/15 12:00:00",
+        ])
+        .output()
+        .expect("run timeparse");
+
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(
+        stdout.starts_with("2024-03-10T03:00:00-04:00  clocks go forward 1h"),
+        "{stdout}"
+    );
+
+    let out = Command::new(bin())
+        .args([
+            "dst-prev",
+            "--tz",
+            "America/New_York",
+            "2024/01/15 12:00:00",
+            "--json",
+        ])
+        .output()
+        .expect("run timeparse");
+
+    assert!(out.status.success());
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid json");
+    assert_eq!(v["at"], "2023-11-05T06:00:00+00:00");
+    assert_eq!(v["change_seconds"], -3600);
+}
+
+#[test]
+fn cli_add_business_days_skips_weekend() {
+    // Friday 2024-12-27 10:00 UTC + 1 business day = Monday 2024-12-30 10:00 UTC
+    let out = Command::new(bin())
+        .args(["add", "2024/12/27 10:00:00", "1bd", "--input-tz", "utc"])
+        .output()
+        .expect("run timeparse");
+
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().trim(),
+        "2024-12-30T10:00:00+00:00"
+    );
+
+    let out = Command::new(bin())
+        .args(["sub", "1700000000", "2h", "--unix"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(String::from_utf8(out.stdout).unwrap().trim(), "1699992800");
+}
+
+#[test]
+fn cli_is_holiday_uses_exit_code() {
+    let out = Command::new(bin())
+        .args(["is-holiday", "2024/12/25 12:00:00", "--holidays", "US"])
+        .output()
+        .expect("run timeparse");
+
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().trim(),
+        "Christmas Day"
+    );
+
+    let out = Command::new(bin())
+        .args(["is-holiday", "2024/12/27 12:00:00", "--holidays", "US"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(out.status.code(), Some(1));
+}
+
+#[test]
+fn cli_get_fiscal_quarter() {
+    let out = Command::new(bin())
+        .args([
+            "1700000000",
+            "--fiscal-start",
+            "04",
+            "--get",
+            "fiscal_quarter",
+        ])
+        .output()
+        .expect("run timeparse");
+
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8(out.stdout).unwrap().trim(), "3");
+
+    let out = Command::new(bin())
+        .args(["1700000000", "--fiscal-start", "04", "--json"])
+        .output()
+        .expect("run timeparse");
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid json");
+    assert_eq!(v["fiscal_year"], 2024);
+    assert_eq!(v["fiscal_period"], 8);
+}
+
+#[test]
+fn cli_query() {
+    let run = |query: &str| {
+        Command::new(bin())
+            .args(["1700000000", "--query", query])
+            .output()
+            .expect("run timeparse")
+    };
+    let out = run(".unix_millis");
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), "1700000000000\n");
+    // Strings come out bare.
+    let out = run(".rfc3339");
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "2023-11-14T22:13:20+00:00\n"
+    );
+
+    assert_eq!(run(".unix_milis").status.code(), Some(2));
+    assert_eq!(run("unix_millis").status.code(), Some(2));
+}
+
+#[test]
+fn cli_schema() {
+    let out = Command::new(bin())
+        .args(["schema", "--output", "json"])
+        .output()
+        .expect("run timeparse");
+    assert!(out.status.success());
+    let schema: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid json");
+    assert_eq!(
+        schema["$schema"],
+        "https://json-schema.org/draft/2020-12/schema"
+    );
+
+    let out = Command::new(bin())
+        .args(["1700000000", "--json"])
+        .output()
+        .expect("run timeparse");
+    let result: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid json");
+    for field in schema["required"].as_array().unwrap() {
+        assert!(result.get(field.as_str().unwrap()).is_some(), "{field}");
+    }
+}
+
+#[test]
+fn cli_schema_version_2() {
+    let out = Command::new(bin())
+        .args(["1700000000", "--schema", "2", "--json"])
+        .output()
+        .expect("run timeparse");
+    assert!(out.status.success());
+    let result: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid json");
+    assert_eq!(result["schema_version"], 2);
+    assert_eq!(result["quarter"], 4);
+    assert_eq!(result["leap_year"], false);
+
+    let out = Command::new(bin())
+        .args(["schema", "--schema", "2"])
+        .output()
+        .expect("run timeparse");
+    let schema: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid json");
+    for field in schema["required"].as_array().unwrap() {
+        assert!(result.get(field.as_str().unwrap()).is_some(), "{field}");
+    }
+}
+
+#[test]
+fn cli_all_representations() {
+    let out = Command::new(bin())
+        .args(["1700000000", "--all"])
+        .output()
+        .expect("run timeparse");
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    let rows: Vec<Vec<&str>> = stdout
+        .lines()
+        .map(|l| l.splitn(2, ' ').map(str::trim).collect())
+        .collect();
+    assert_eq!(rows[0], ["unix_seconds", "1700000000"]);
+    assert!(rows.contains(&vec!["filetime", "133444736000000000"]));
+    assert!(rows.contains(&vec!["rfc2822", "Tue, 14 Nov 2023 22:13:20 +0000"]));
+
+    let out = Command::new(bin())
+        .args(["1700000000", "--all", "--json"])
+        .output()
+        .expect("run timeparse");
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid json");
+    assert_eq!(v["iso_week_date"], "2023-W46-2");
+    assert_eq!(v["julian_day"], "2460263.425925926");
+}
+
+#[test]
+fn cli_ics_event() {
+    let out = Command::new(bin())
+        .args([
+            "ics",
+            "2023/11/14 22:13:20",
+            "--input-tz",
+            "utc",
+            "--duration",
+            "90m",
+            "--summary",
+            "Deploy",
+            "--uid",
+            "deploy-1@example.com",
+        ])
+        .output()
+        .expect("run timeparse");
+    assert!(out.status.success());
+    let ics = String::from_utf8(out.stdout).unwrap();
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    for line in [
+        "UID:deploy-1@example.com",
+        "DTSTART:20231114T221320Z",
+        "DTEND:20231114T234320Z",
+        "SUMMARY:Deploy",
+    ] {
+        assert!(ics.contains(&format!("\r\n{line}\r\n")), "{line}");
+    }
+
+    let out = Command::new(bin())
+        .args(["ics", "1700000000", "--end", "1600000000", "--summary", "x"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn cli_ics_read() {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("feed.ics");
+    std::fs::write(
+        &path,
+        "BEGIN:VCALENDAR\r\n\
+         BEGIN:VEVENT\r\n\
+         DTSTART;TZID=\"+02:00\":20231115T001320\r\n\
+         DTEND:20231114T2313\r\n 20Z\r\n\
+         RRULE:FREQ=DAILY;COUNT=2\r\n\
+         END:VEVENT\r\n\
+         BEGIN:VEVENT\r\n\
+         DTSTART;TZID=Nowhere/Special:20231115T001320\r\n\
+         END:VEVENT\r\n\
+         END:VCALENDAR\r\n",
+    )
+    .unwrap();
+
+    let out = Command::new(bin())
+        .arg("ics-read")
+        .arg(&path)
+        .args(["--format", "%s"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(out.status.code(), Some(3));
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "DTSTART: 1700000000\nDTEND: 1700003600\nRRULE: FREQ=DAILY;COUNT=2 (no UNTIL)\n"
+    );
+    assert!(
+        String::from_utf8(out.stderr)
+            .unwrap()
+            .starts_with("line 9: Error: DTSTART: TZID")
+    );
+}
+
+#[test]
+fn cli_toml_output() {
+    let out = Command::new(bin())
+        .args(["1700000000", "--toml", "--fiscal-start", "4"])
+        .output()
+        .expect("run timeparse");
+    assert!(out.status.success());
+    let toml = String::from_utf8(out.stdout).unwrap();
+    let lines: Vec<&str> = toml.lines().collect();
+    assert!(lines.contains(&"rfc3339 = 2023-11-14T22:13:20+00:00"));
+    assert!(lines.contains(&"input = \"1700000000\""));
+    assert!(lines.contains(&"fiscal_quarter = 3"));
+    // TOML has no null; absent fields are left out.
+    assert!(!toml.contains("source_offset"));
+}
+
+#[test]
+fn cli_xml_output() {
+    let out = Command::new(bin())
+        .args(["1700000000", "--xml"])
+        .output()
+        .expect("run timeparse");
+    assert!(out.status.success());
+    let xml = String::from_utf8(out.stdout).unwrap();
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<result>\n"));
+    assert!(xml.contains("\n  <unix_seconds>1700000000</unix_seconds>\n"));
+    assert!(xml.contains("\n  <source_offset/>\n"));
+    assert!(xml.ends_with("</result>\n"));
+
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(bin())
+        .args(["-", "--xml"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("run timeparse");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"1700000000\n1700000001\n")
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+
+    assert!(out.status.success());
+    let xml = String::from_utf8(out.stdout).unwrap();
+    let lines: Vec<&str> = xml.lines().collect();
+    assert_eq!(lines.len(), 5, "{xml}");
+    assert_eq!(lines[0], "<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+    assert_eq!(lines[1], "<results>");
+    assert!(lines[2].starts_with("<result><input>1700000000</input>"));
+    assert!(lines[3].starts_with("<result><input>1700000001</input>"));
+    assert_eq!(lines[4], "</results>");
+    assert_eq!(xml.matches("<?xml").count(), 1);
+}
+
+#[test]
+fn cli_age_breakdown() {
+    let out = Command::new(bin())
+        .args(["age", "1990-06-15", "2023-11-14"])
+        .output()
+        .expect("run timeparse");
+
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().trim(),
+        "33 years, 4 months, 30 days"
+    );
+
+    let out = Command::new(bin())
+        .args(["age", "2000/02/29", "2001-03-01", "--json"])
+        .output()
+        .expect("run timeparse");
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid json");
+    assert_eq!(v["years"], 1);
+    assert_eq!(v["months"], 0);
+    assert_eq!(v["days"], 1);
+    assert_eq!(v["total_days"], 366);
+}
+
+#[test]
+fn cli_diff_with_tolerance() {
+    let run = |args: &[&str]| {
+        Command::new(bin())
+            .args(args)
+            .output()
+            .expect("run timeparse")
+    };
+
+    let out = run(&["diff", "1700000000", "1700005400"]);
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8(out.stdout).unwrap().trim(), "5400");
+
+    let out = run(&["diff", "1700000000", "1700005400", "--tolerance", "1h30m"]);
+    assert!(out.status.success());
+
+    let out = run(&["diff", "1700000000", "1700005401", "--tolerance", "PT1H30M"]);
+    assert_eq!(out.status.code(), Some(1));
+
+    let out = run(&["add", "1700000000", "1h30m", "--unix"]);
+    assert_eq!(String::from_utf8(out.stdout).unwrap().trim(), "1700005400");
+}
+
+#[test]
+fn cli_compare() {
+    let run = |args: &[&str]| {
+        let out = Command::new(bin())
+            .arg("compare")
+            .args(args)
+            .output()
+            .expect("run timeparse");
+        (
+            out.status.code(),
+            String::from_utf8(out.stdout).unwrap().trim().to_string(),
+        )
+    };
+    let answer = |code, s: &str| (Some(code), s.to_string());
+    assert_eq!(run(&["1700000000", "1700000003"]), answer(0, "before"));
+    assert_eq!(run(&["1700000003", "1700000000"]), answer(2, "after"));
+    assert_eq!(
+        run(&["2023-11-14T22:13:20Z", "1700000000"]),
+        answer(1, "equal")
+    );
+    assert_eq!(
+        run(&["1700000003", "1700000000", "--tolerance", "5s"]),
+        answer(1, "equal")
+    );
+    assert_eq!(
+        run(&["1700000000", "1700000006", "--tolerance", "5s"]),
+        answer(0, "before")
+    );
+
+    let (code, out) = run(&["1700000000", "1700000060", "--tolerance", "PT1M", "--json"]);
+    assert_eq!(code, Some(1));
+    let v: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(v["result"], "equal");
+    assert_eq!(v["seconds"], 60);
+    assert_eq!(v["tolerance_seconds"], 60);
+
+    assert_eq!(run(&["nonsense", "1700000000"]).0, Some(3));
+}
+
+#[test]
+fn cli_diff_iso_duration() {
+    let out = Command::new(bin())
+        .args([
+            "diff",
+            "2024/01/01 00:00:00",
+            "2024/01/03 03:04:05",
+            "--duration-format",
+            "iso",
+        ])
+        .output()
+        .expect("run timeparse");
+
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8(out.stdout).unwrap().trim(), "P2DT3H4M5S");
+}
+
+#[test]
+fn cli_diff_human_duration() {
+    let out = Command::new(bin())
+        .args([
+            "diff",
+            "1700000000",
+            "1700183845",
+            "--duration-format",
+            "human",
+            "--max-units",
+            "3",
+        ])
+        .output()
+        .expect("run timeparse");
+
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().trim(),
+        "2 days 3 hours 4 minutes"
+    );
+}
+
+#[test]
+fn cli_expression_input() {
+    let out = Command::new(bin())
+        .args(["1700000000 + 3d - 2h", "--json"])
+        .output()
+        .expect("run timeparse");
+
+    assert!(out.status.success());
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid json");
+    assert_eq!(v["parsed_as"], "expression");
+    assert_eq!(v["unix_seconds"], 1_700_252_000);
+
+    let out = Command::new(bin())
+        .args(["(2025/01/01 00:00:00) + 6w", "--input-tz", "UTC", "--unix"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(String::from_utf8(out.stdout).unwrap().trim(), "1739318400");
+}
+
+#[test]
+fn cli_serve_answers_http() {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::time::Duration;
+
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let mut server = Command::new(bin())
+        .args(["serve", "--listen", &format!("127.0.0.1:{port}")])
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .expect("run timeparse");
+
+    let mut stream = None;
+    for _ in 0..50 {
+        if let Ok(s) = TcpStream::connect(("127.0.0.1", port)) {
+            stream = Some(s);
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    let mut stream = stream.expect("server listening");
+    stream
+        .write_all(b"GET /parse?input=1700000000 HTTP/1.1\r\nHost: x\r\n\r\n")
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    server.kill().unwrap();
+    server.wait().unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+    let body = response.split("\r\n\r\n").nth(1).unwrap();
+    let v: serde_json::Value = serde_json::from_str(body).expect("valid json");
+    assert_eq!(v["rfc3339"], "2023-11-14T22:13:20+00:00");
+}
+
+#[test]
+fn cli_daemon_answers_each_line() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(bin())
+        .arg("daemon")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("run timeparse");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"{\"id\": 1, \"input\": \"1700000000\"}\n{\"id\": 2, \"input\": \"bad\"}\n")
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+
+    assert!(out.status.success());
+    let lines: Vec<serde_json::Value> = String::from_utf8(out.stdout)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("valid json"))
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["unix_seconds"], 1_700_000_000);
+    assert_eq!(lines[1]["id"], 2);
+    assert!(lines[1]["error"].is_string());
+}
+
+#[test]
+fn cli_batch_converts_stdin_lines_in_order() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(bin())
+        .args(["-", "--jobs", "4", "--unix", "--input-tz", "utc"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("run timeparse");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"1700000000000\n2023/11/14 22:13:21\n\nnot a date\n1700000002\n")
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+
+    assert_eq!(out.status.code(), Some(3));
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "1700000000\n1700000001\n1700000002\n"
+    );
+    assert!(
+        String::from_utf8(out.stderr)
+            .unwrap()
+            .starts_with("line 4: Error:")
+    );
+}
+
+#[test]
+fn cli_table_output() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(bin())
+        .args(["-", "--output", "table", "--input-tz", "utc"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("run timeparse");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"1700000000\n2023/11/14 22:14:20\n")
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "\
+input                unix        rfc3339                    tz
+-------------------  ----------  -------------------------  ---
+1700000000           1700000000  2023-11-14T22:13:20+00:00  UTC
+2023/11/14 22:14:20  1700000060  2023-11-14T22:14:20+00:00  UTC
+"
+    );
+}
+
+#[test]
+fn cli_markdown_output() {
+    let out = Command::new(bin())
+        .args([
+            "1700000000",
+            "--output",
+            "markdown",
+            "--output-tz",
+            "+02:00",
+        ])
+        .output()
+        .expect("run timeparse");
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "\
+| input      | unix       | rfc3339                   | tz     |
+| ---------- | ---------- | ------------------------- | ------ |
+| 1700000000 | 1700000000 | 2023-11-15T00:13:20+02:00 | +02:00 |
+"
+    );
+}
+
+#[test]
+fn cli_input_file_reports_progress() {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("input-file.txt");
+    std::fs::write(&path, "1700000000\n1700000060\n").unwrap();
+
+    let out = Command::new(bin())
+        .arg("--input-file")
+        .arg(&path)
+        .args(["--progress", "--unix"])
+        .output()
+        .expect("run timeparse");
+
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "1700000000\n1700000060\n"
+    );
+    assert!(
+        String::from_utf8(out.stderr)
+            .unwrap()
+            .ends_with("\r2 lines (100.0%)\n")
+    );
+}
+
+#[test]
+fn cli_rejects_invalid_format_pattern() {
+    let out = Command::new(bin())
+        .args(["1700000000", "--format", "%Y %Q"])
+        .output()
+        .expect("run timeparse");
+
+    assert_eq!(out.status.code(), Some(2));
+    assert!(
+        String::from_utf8(out.stderr)
+            .unwrap()
+            .contains("invalid strftime pattern")
+    );
+}
+
+#[test]
+fn cli_batch_stats_summarize_converted_instants() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(bin())
+        .args(["-", "--stats", "--json"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("run timeparse");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"1700003661\n1700000000\n")
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+
+    assert!(out.status.success());
+    // stdout stays NDJSON results only; the summary goes to stderr.
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), 2);
+    assert!(!stdout.contains("stats"));
+    let stats: serde_json::Value = serde_json::from_slice(&out.stderr).unwrap();
+    assert_eq!(stats["stats"]["count"], 2);
+    assert_eq!(stats["stats"]["min"], "2023-11-14T22:13:20+00:00");
+    assert_eq!(stats["stats"]["span_seconds"], 3661);
+}
+
+#[test]
+fn cli_summary_reports_gaps_and_rate() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(bin())
+        .args(["summary", "--json"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("run timeparse");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"1700000120\n1700000000\n1700000060\n1700000180\n1700000600\n")
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+
+    assert!(out.status.success());
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid json");
+    assert_eq!(v["count"], 5);
+    assert_eq!(v["earliest"], "2023-11-14T22:13:20+00:00");
+    assert_eq!(v["median"], "2023-11-14T22:15:20+00:00");
+    assert_eq!(v["gap_p90_seconds"], 420.0);
+    assert_eq!(v["events_per_minute"], 0.5);
+}
+
+#[test]
+fn cli_uniq_keeps_first_line_per_period() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let run = |extra: &[&str]| {
+        let mut child = Command::new(bin())
+            .args(["uniq", "--by", "hour"])
+            .args(extra)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("run timeparse");
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(b"1700000000\n1700000100\n1700003600\n1700000200\n")
+            .unwrap();
+        let out = child.wait_with_output().unwrap();
+        assert!(out.status.success());
+        String::from_utf8(out.stdout).unwrap()
+    };
+
+    assert_eq!(run(&[]), "1700000000\n1700003600\n");
+    assert_eq!(
+        run(&["--starts", "--output-tz", "+05:30"]),
+        "2023-11-15T03:00:00+05:30\n2023-11-15T04:00:00+05:30\n"
+    );
+}
+
+#[test]
+fn cli_group_counts_per_day_in_output_zone() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    // 23:30Z on the 14th is already the 15th in Paris; naive UTC grouping would say 3 + 1.
+    let mut child = Command::new(bin())
+        .args(["group", "--by", "day", "--output-tz", "Europe/Paris"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("run timeparse");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"1700006400\n1700004600\n1700001000\n1699954200\n")
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "period_start,count\n2023-11-14T00:00:00+01:00,2\n2023-11-15T00:00:00+01:00,2\n"
+    );
+}
+
+#[test]
+fn cli_scan_emits_ndjson_per_match() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(bin())
+        .arg("scan")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("run timeparse");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"no times here\nstart=2023-11-14T22:13:20Z end=1700000060\n")
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+
+    assert!(out.status.success());
+    let lines: Vec<serde_json::Value> = String::from_utf8(out.stdout)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("valid json"))
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["line"], 2);
+    assert_eq!(lines[0]["start"], 6);
+    assert_eq!(lines[0]["kind"], "rfc3339");
+    assert_eq!(lines[1]["match"], "1700000060");
+    assert_eq!(lines[1]["unix_seconds"], 1700000060);
+}
+
+#[test]
+fn cli_reads_common_log_format() {
+    let out = Command::new(bin())
+        .args(["[14/Nov/2023:23:13:20 +0100]", "--json"])
+        .output()
+        .expect("run timeparse");
+
+    assert!(out.status.success());
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid json");
+    assert_eq!(v["parsed_as"], "clf");
+    assert_eq!(v["unix_seconds"], 1700000000);
+    assert_eq!(v["source_offset"], "+01:00");
+}
+
+#[test]
+fn cli_reads_syslog_times() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(bin())
+        .args(["-", "--input-tz", "utc", "--syslog-year", "2023"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("run timeparse");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"Nov 14 22:13:20\nNov  4 22:13:20\n2023-11-15T00:13:20.003+02:00\n")
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "2023-11-14T22:13:20+00:00\n2023-11-04T22:13:20+00:00\n2023-11-14T22:13:20.003+00:00\n"
+    );
+}
+
+#[test]
+fn cli_reads_journal_export_streams() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(bin())
+        .args(["-", "--from", "journal"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("run timeparse");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(
+            b"__CURSOR=s=1\n__REALTIME_TIMESTAMP=1700000000123456\nMESSAGE=up\n\n\
+              __REALTIME_TIMESTAMP=1700000001000000\nMESSAGE=down\n",
+        )
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "2023-11-14T22:13:20.123456+00:00\n2023-11-14T22:13:21+00:00\n"
+    );
+}
+
+#[test]
+fn cli_keeps_rfc3339_nanoseconds() {
+    let out = Command::new(bin())
+        .args(["2023-11-14T22:13:20.123456789Z", "--json"])
+        .output()
+        .expect("run timeparse");
+
+    assert!(out.status.success());
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid json");
+    assert_eq!(v["parsed_as"], "rfc3339");
+    assert_eq!(v["unix_millis"], 1_700_000_000_123_i64);
+    assert_eq!(v["unix_nanos"], 1_700_000_000_123_456_789_i64);
+    assert_eq!(v["rfc3339"], "2023-11-14T22:13:20.123456789+00:00");
+}
+
+#[test]
+fn cli_aws_presets() {
+    let out = Command::new(bin())
+        .args([
+            "--from",
+            "s3",
+            "2023-11-14T22:13:20.123Z",
+            "--format",
+            "aws-logs",
+        ])
+        .output()
+        .expect("run timeparse");
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().trim(),
+        "1700000000123"
+    );
+
+    let out = Command::new(bin())
+        .args(["--from", "cloudtrail", "2023-11-14 22:13:20"])
+        .output()
+        .expect("run timeparse");
+    assert!(!out.status.success());
+}
+
+#[test]
+fn cli_http_dates_round_trip() {
+    let out = Command::new(bin())
+        .args(["Tue, 14 Nov 2023 22:13:20 GMT", "--unix"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(String::from_utf8(out.stdout).unwrap().trim(), "1700000000");
+
+    let out = Command::new(bin())
+        .args([
+            "1700000000",
+            "--format",
+            "http",
+            "--output-tz",
+            "Asia/Tokyo",
+        ])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().trim(),
+        "Tue, 14 Nov 2023 22:13:20 GMT"
+    );
+}
+
+#[test]
+fn cli_reads_email_dates() {
+    let out = Command::new(bin())
+        .args(["Date: Tue, 14 Nov 2023 17:13:20 EST", "--json"])
+        .output()
+        .expect("run timeparse");
+
+    assert!(out.status.success());
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid json");
+    assert_eq!(v["parsed_as"], "email");
+    assert_eq!(v["unix_seconds"], 1700000000);
+    assert_eq!(v["source_offset"], "-05:00");
+
+    // A two-digit year is the obsolete email form, not an HTTP date in year 23.
+    let out = Command::new(bin())
+        .args(["Tue, 14 Nov 23 22:13:20 GMT", "--json"])
+        .output()
+        .expect("run timeparse");
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid json");
+    assert_eq!(v["parsed_as"], "email");
+    assert_eq!(v["unix_seconds"], 1700000000);
+}
+
+#[test]
+fn cli_git_dates_round_trip() {
+    let out = Command::new(bin())
+        .args(["Wed Nov 15 00:13:20 2023 +0200", "--format", "git-raw"])
+        .args(["--output-tz", "+02:00"])
+        .output()
+        .expect("run timeparse");
+
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().trim(),
+        "1700000000 +0200"
+    );
+}
+
+#[test]
+fn cli_sql_literals() {
+    let out = Command::new(bin())
+        .args([
+            "2023-11-14 22:13:20.5",
+            "--input-tz",
+            "utc",
+            "--format",
+            "sql",
+        ])
+        .args(["--output-tz", "+02:00"])
+        .output()
+        .expect("run timeparse");
+
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().trim(),
+        "2023-11-15 00:13:20.500+02:00"
+    );
+}
+
+#[test]
+fn cli_julian_days_round_trip() {
+    let out = Command::new(bin())
+        .args(["--from", "julianday", "2460263.42592593", "--unix"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(String::from_utf8(out.stdout).unwrap().trim(), "1700000000");
+
+    let out = Command::new(bin())
+        .args(["1700000000", "--to", "julianday"])
+        .output()
+        .expect("run timeparse");
+    let jd: f64 = String::from_utf8(out.stdout)
+        .unwrap()
+        .trim()
+        .parse()
+        .unwrap();
+    assert!((jd - 2460263.42592593).abs() < 1e-8);
+}
+
+#[test]
+fn cli_matlab_datenums() {
+    let out = Command::new(bin())
+        .args(["--from", "matlab", "739204.925925926"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().trim(),
+        "2023-11-14T22:13:20+00:00"
+    );
+}
+
+#[test]
+fn cli_mac_1904_seconds() {
+    let out = Command::new(bin())
+        .args(["2023-11-14T22:13:20.5Z", "--to", "mac1904"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().trim(),
+        "3782844800.5"
+    );
+}
+
+#[test]
+fn cli_gps_week_round_trip() {
+    let out = Command::new(bin())
+        .args(["--from", "gps-week", "2288:252818"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().trim(),
+        "2023-11-14T22:13:20+00:00"
+    );
+
+    let out = Command::new(bin())
+        .args(["2023-11-14T22:13:20.5Z", "--to", "gps-week"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().trim(),
+        "2288:252818.5"
+    );
+}
+
+#[test]
+fn cli_input_format_with_century_pivot() {
+    let out = Command::new(bin())
+        .args([
+            "14/11/69",
+            "--input-format",
+            "%d/%m/%y",
+            "--input-tz",
+            "utc",
+        ])
+        .args(["--century-pivot", "50"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().trim(),
+        "1969-11-14T00:00:00+00:00"
+    );
+}
+
+#[test]
+fn cli_detect_window_reads_old_millis() {
+    let out = Command::new(bin())
+        .args(["631152000000", "--detect-window", "1980..2100"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().trim(),
+        "1990-01-01T00:00:00+00:00"
+    );
+
+    let out = Command::new(bin())
+        .args(["1", "--detect-window", "2040..1990"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn cli_forced_nanos() {
+    let out = Command::new(bin())
+        .args(["1700000000123456789", "--ts", "nanos", "--json"])
+        .output()
+        .expect("run timeparse");
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid json");
+    assert_eq!(v["ts_unit"], "nanos");
+    assert_eq!(v["unix_nanos"], 1_700_000_000_123_456_789_i64);
+}
+
+#[test]
+fn cli_unix_in_another_radix() {
+    let out = Command::new(bin())
+        .args(["1700000000", "--unix", "--radix", "base36"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(String::from_utf8(out.stdout).unwrap().trim(), "s44we8");
+
+    let out = Command::new(bin())
+        .args(["1700000000", "--radix", "hex"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn cli_reads_radix_codes() {
+    let out = Command::new(bin())
+        .args(["1r31eq", "--from", "base62", "--unix"])
+        .output()
+        .expect("run timeparse");
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8(out.stdout).unwrap().trim(), "1700000000");
+
+    let out = Command::new(bin())
+        .args(["S44WE8", "--from", "base36", "--json"])
+        .output()
+        .expect("run timeparse");
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("\"unix_seconds\": 1700000000"), "{stdout}");
+}
+
+#[test]
+fn cli_scientific_notation() {
+    let out = Command::new(bin())
+        .args(["1.7E12", "--unix"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(String::from_utf8(out.stdout).unwrap().trim(), "1700000000");
+    assert!(out.stderr.is_empty());
+
+    let out = Command::new(bin())
+        .args(["1.7e18", "--ts", "nanos", "--unix"])
+        .output()
+        .expect("run timeparse");
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8(out.stdout).unwrap().trim(), "1700000000");
+    assert!(String::from_utf8(out.stderr).unwrap().contains("2^53"));
+
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(bin())
+        .args(["-", "--ts", "nanos", "--unix"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("run timeparse");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"1.7e12\n1.2345678901234567e17\n")
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), "1700\n123456789\n");
+    assert!(
+        String::from_utf8(out.stderr)
+            .unwrap()
+            .starts_with("line 2: Warning: 1.2345678901234567e17 is beyond 2^53")
+    );
+}
+
+#[test]
+fn cli_digit_separators() {
+    for input in ["1_700_000_000", "1,700,000,000"] {
+        let out = Command::new(bin())
+            .args([input, "--unix"])
+            .output()
+            .expect("run timeparse");
+        assert!(out.status.success(), "{input}");
+        assert_eq!(String::from_utf8(out.stdout).unwrap().trim(), "1700000000");
+    }
+}
+
+#[test]
+fn cli_lenient_cleans_copied_values() {
+    let out = Command::new(bin())
+        .args(["--lenient", "[\"1700000000000ms\"],", "--unix"])
+        .output()
+        .expect("run timeparse");
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8(out.stdout).unwrap().trim(), "1700000000");
+
+    let out = Command::new(bin())
+        .args(["\"1700000000\"", "--unix"])
+        .output()
+        .expect("run timeparse");
+    assert!(!out.status.success());
+}
+
+#[test]
+fn cli_paste_reads_the_clipboard() {
+    let out = Command::new(bin())
+        .args(["--paste", "--unix"])
+        .env("EPOCH_PASTE_COMMAND", "printf ' 2023-11-14T22:13:20Z\\n'")
+        .output()
+        .expect("run timeparse");
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8(out.stdout).unwrap().trim(), "1700000000");
+
+    let out = Command::new(bin())
+        .args(["--paste"])
+        .env("EPOCH_PASTE_COMMAND", "true")
+        .output()
+        .expect("run timeparse");
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8(out.stderr).unwrap().contains("empty"));
+}
+
+#[test]
+fn cli_verbose_traces_to_stderr() {
+    let out = Command::new(bin())
+        .args(["-v", "1700000000", "--unix"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(String::from_utf8(out.stdout).unwrap().trim(), "1700000000");
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(stderr.contains("trace: unit: seconds"), "{stderr}");
+
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(bin())
+        .args(["-", "-v", "--unix", "--jobs", "2"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("run timeparse");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"1700000000\n1700000000000\n")
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+
+    assert!(out.status.success());
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "1700000000\n1700000000\n"
+    );
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(stderr.contains("line 1: trace: unit: seconds"), "{stderr}");
+    assert!(stderr.contains("line 2: trace: unit: millis"), "{stderr}");
+    assert!(stderr.lines().all(|l| l.starts_with("line ")), "{stderr}");
+}
+
+#[test]
+fn cli_explain() {
+    let out = Command::new(bin())
+        .args(["explain", "1700000000000"])
+        .output()
+        .expect("run timeparse");
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("read as timestamp in millis"), "{stdout}");
+    assert!(
+        stdout.contains("as micros it would be 1970-01-20T16:13:20+00:00"),
+        "{stdout}"
+    );
+
+    let out = Command::new(bin())
+        .args([
+            "explain",
+            "2023/11/14 22:13:20",
+            "--input-tz",
+            "+02:00",
+            "--json",
+        ])
+        .output()
+        .expect("run timeparse");
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid json");
+    assert_eq!(v["parsed_as"], "formatted");
+    assert_eq!(v["zone"], "+02:00");
+    assert_eq!(v["alternatives"][0]["reading"], "in UTC");
+    assert_eq!(v["alternatives"][0]["rfc3339"], "2023-11-14T22:13:20+00:00");
+}
+
+#[test]
+fn cli_named_format_presets() {
+    for (preset, expected) in [
+        ("rfc2822", "Tue, 14 Nov 2023 22:13:20 +0000"),
+        ("iso8601-basic", "20231114T221320+0000"),
+        ("kitchen", "10:13PM"),
+    ] {
+        let out = Command::new(bin())
+            .args(["1700000000", "--output-tz", "utc", "--format", preset])
+            .output()
+            .expect("run timeparse");
+        assert_eq!(String::from_utf8(out.stdout).unwrap().trim(), expected);
+    }
+}
+
+#[test]
+fn cli_html_time() {
+    let run = |extra: &[&str]| {
+        let out = Command::new(bin())
+            .args(["1700000000", "--format", "html-time"])
+            .args(extra)
+            .output()
+            .expect("run timeparse");
+        String::from_utf8(out.stdout).unwrap().trim().to_string()
+    };
+    assert_eq!(
+        run(&[]),
+        r#"<time datetime="2023-11-14T22:13:20Z">14 Nov 2023, 22:13 UTC</time>"#
+    );
+    assert_eq!(
+        run(&["--html-display", "%B %-d", "--output-tz", "+01:00"]),
+        r#"<time datetime="2023-11-14T23:13:20+01:00">November 14</time>"#
+    );
+}
+
+#[test]
+fn cli_proto_timestamp() {
+    let out = Command::new(bin())
+        .args(["1700000000123", "--format", "proto-timestamp"])
+        .output()
+        .expect("run timeparse");
+    let printed = String::from_utf8(out.stdout).unwrap();
+    assert_eq!(
+        printed.trim(),
+        r#"{"seconds": 1700000000, "nanos": 123000000}"#
+    );
+
+    let out = Command::new(bin())
+        .args(["--from", "proto-timestamp", printed.trim()])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().trim(),
+        "2023-11-14T22:13:20.123+00:00"
+    );
+}
+
+#[test]
+fn cli_milestones() {
+    let out = Command::new(bin())
+        .args(["milestones", "1700000000", "--count", "2"])
+        .output()
+        .expect("run timeparse");
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(
+        lines[0],
+        "1800000000   2027-01-15T08:00:00+00:00  in 1157 days 9 hours     1.8 billion"
+    );
+    assert!(lines[1].ends_with("1.9 billion"));
+
+    let out = Command::new(bin())
+        .args(["milestones", "2147483000", "--count", "1", "--json"])
+        .output()
+        .expect("run timeparse");
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v[0]["unix_seconds"], 2_147_483_648_i64);
+    assert_eq!(v[0]["seconds_left"], 648);
+    assert_eq!(v[0]["rfc3339"], "2038-01-19T03:14:08+00:00");
+}
+
+#[test]
+fn cli_moon() {
+    let out = Command::new(bin())
+        .args(["moon", "2023-11-27T09:16:00Z"])
+        .output()
+        .expect("run timeparse");
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(
+        stdout.starts_with("2023-11-27T09:16:00+00:00  Full Moon, 100.0% illuminated, 14."),
+        "{stdout}"
+    );
+
+    let out = Command::new(bin())
+        .args(["moon", "2023-11-20T10:50:00Z", "--json"])
+        .output()
+        .expect("run timeparse");
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["phase"], "First Quarter");
+    assert_eq!(v["waxing"], true);
+    let illumination = v["illumination"].as_f64().unwrap();
+    assert!((illumination - 0.5).abs() < 0.01, "{v}");
+}
+
+#[test]
+fn cli_sun() {
+    let out = Command::new(bin())
+        .args(["sun", "2023-11-14", "--lat", "44.43", "--lon", "26.10"])
+        .args(["--output-tz", "Europe/Bucharest"])
+        .output()
+        .expect("run timeparse");
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    let names: Vec<&str> = stdout.lines().map(|l| l[..18].trim_end()).collect();
+    assert_eq!(
+        names,
+        [
+            "astronomical dawn",
+            "nautical dawn",
+            "civil dawn",
+            "sunrise",
+            "solar noon",
+            "sunset",
+            "civil dusk",
+            "nautical dusk",
+            "astronomical dusk",
+            "day length"
+        ]
+    );
+    assert!(stdout.contains("sunrise            2023-11-14T07:09:40+02:00\n"));
+    assert!(stdout.contains("solar noon         2023-11-14T12:00:03+02:00\n"));
+    assert!(stdout.ends_with("day length         9 hours 40 minutes 47 seconds\n"));
+
+    let out = Command::new(bin())
+        .args([
+            "sun",
+            "2023-06-21",
+            "--lat",
+            "69.65",
+            "--lon",
+            "18.96",
+            "--json",
+        ])
+        .output()
+        .expect("run timeparse");
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert!(v["sunrise"].is_null());
+    assert_eq!(v["polar"], "day");
+    assert_eq!(v["day_length_seconds"], 86_400);
+    assert!(v["solar_noon"].is_string());
+
+    let out = Command::new(bin())
+        .args(["sun", "--lat", "91", "--lon", "0"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn cli_calendar() {
+    for (calendar, expected) in [
+        ("hebrew", "1 Kislev 5784"),
+        ("islamic", "1 Jumada al-Awwal 1445"),
+        ("persian", "23 Aban 1402"),
+        ("japanese", "令和5年11月14日"),
+        ("japanese-ascii", "R5.11.14"),
+        ("julian", "1 November 2023"),
+    ] {
+        let out = Command::new(bin())
+            .args(["1700000000", "--calendar", calendar])
+            .output()
+            .expect("run timeparse");
+        assert_eq!(String::from_utf8(out.stdout).unwrap().trim(), expected);
+    }
+}
+
+#[test]
+fn cli_iso8601_basic() {
+    let out = Command::new(bin())
+        .args(["20231115T001320+0200", "--format", "iso8601-basic-utc"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().trim(),
+        "20231114T221320Z"
+    );
+
+    let out = Command::new(bin())
+        .args(["20231114T221320Z", "--json"])
+        .output()
+        .expect("run timeparse");
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["parsed_as"], "iso8601_basic");
+    assert_eq!(v["unix_seconds"], 1_700_000_000);
+}
+
+#[test]
+fn cli_compact_digits() {
+    let read = |args: &[&str]| {
+        let out = Command::new(bin())
+            .args(args)
+            .args(["--input-tz", "utc", "--json"])
+            .output()
+            .expect("run timeparse");
+        let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+        (v["parsed_as"].clone(), v["unix_seconds"].clone())
+    };
+    assert_eq!(read(&["20231114221320"]).0, "compact");
+    assert_eq!(read(&["20231114221320"]).1, 1_700_000_000);
+    assert_eq!(read(&["202311142213"]).1, 1_699_999_980);
+    assert_eq!(read(&["20231114221320", "--no-compact"]).0, "timestamp");
+    assert_eq!(read(&["20231114221320", "--ts", "micros"]).0, "timestamp");
+    assert_eq!(read(&["17000000000000"]).0, "timestamp");
+}
+
+#[test]
+fn cli_date_only() {
+    for input in ["2023-11-14", "2023/11/14", "20231114"] {
+        let out = Command::new(bin())
+            .args([input, "--input-tz", "America/New_York", "--json"])
+            .output()
+            .expect("run timeparse");
+        let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+        assert_eq!(v["parsed_as"], "date", "{input}");
+        assert_eq!(v["unix_seconds"], 1_699_938_000, "{input}");
+    }
+    let out = Command::new(bin())
+        .args(["20231114", "--no-compact", "--unix"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(String::from_utf8(out.stdout).unwrap().trim(), "20231114");
+
+    // Santiago skips midnight on 2024-09-08; the day starts when clocks land.
+    let out = Command::new(bin())
+        .args(["2024-09-08", "--input-tz", "America/Santiago"])
+        .args(["--output-tz", "America/Santiago"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().trim(),
+        "2024-09-08T01:00:00-03:00"
+    );
+    let out = Command::new(bin())
+        .arg("2024-02-30")
+        .output()
+        .expect("run timeparse");
+    assert_eq!(out.status.code(), Some(3));
+    assert!(
+        String::from_utf8(out.stderr)
+            .unwrap()
+            .contains("not a calendar date")
+    );
+}
+
+#[test]
+fn cli_at_sets_the_time_of_date_only_input() {
+    let unix = |args: &[&str]| {
+        let out = Command::new(bin())
+            .args(args)
+            .args(["--input-tz", "utc", "--unix"])
+            .output()
+            .expect("run timeparse");
+        assert!(out.status.success(), "{args:?}");
+        String::from_utf8(out.stdout).unwrap().trim().to_string()
+    };
+    assert_eq!(unix(&["2023-11-14", "--at", "end"]), "1700006399");
+    assert_eq!(unix(&["2023-11-14", "--at", "noon"]), "1699963200");
+    assert_eq!(unix(&["2023-11-14", "--at", "start"]), "1699920000");
+    assert_eq!(
+        unix(&["20231114", "--default-time", "22:13:20"]),
+        "1700000000"
+    );
+    // Input with its own time keeps it.
+    assert_eq!(unix(&["2023/11/14 22:13:20", "--at", "end"]), "1700000000");
+
+    let out = Command::new(bin())
+        .args(["2023-11-14", "--at", "evening"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn cli_year_month() {
+    let read = |args: &[&str]| {
+        let out = Command::new(bin())
+            .args(args)
+            .args(["--input-tz", "utc", "--json"])
+            .output()
+            .expect("run timeparse");
+        serde_json::from_slice::<serde_json::Value>(&out.stdout).unwrap()
+    };
+    let v = read(&["2023-11"]);
+    assert_eq!(v["parsed_as"], "month");
+    assert_eq!(v["rfc3339"], "2023-11-01T00:00:00+00:00");
+    let v = read(&["2023/11", "--at", "end"]);
+    assert_eq!(v["rfc3339"], "2023-11-30T23:59:59+00:00");
+}
+
+#[test]
+fn cli_time_only() {
+    let read = |args: &[&str]| {
+        let out = Command::new(bin())
+            .args(args)
+            .args(["--input-tz", "America/New_York", "--json"])
+            .output()
+            .expect("run timeparse");
+        serde_json::from_slice::<serde_json::Value>(&out.stdout).unwrap()
+    };
+    for input in ["17:13:20", "5:13:20pm"] {
+        let v = read(&[input, "--date", "2023-11-14"]);
+        assert_eq!(v["parsed_as"], "time", "{input}");
+        assert_eq!(v["unix_seconds"], 1_700_000_000, "{input}");
+    }
+    // Without --date, today in --input-tz.
+    let v = read(&["10:13pm", "--output-tz", "America/New_York"]);
+    let rfc3339 = v["rfc3339"].as_str().unwrap();
+    assert!(rfc3339.contains("T22:13:00-0"), "{rfc3339}");
+}
+
+#[test]
+fn cli_end_of() {
+    let run = |args: &[&str]| {
+        let out = Command::new(bin())
+            .args(["1700000000"])
+            .args(args)
+            .output()
+            .expect("run timeparse");
+        String::from_utf8(out.stdout).unwrap().trim().to_string()
+    };
+    assert_eq!(
+        run(&["--end-of", "day", "--output-tz", "+02:00"]),
+        "2023-11-15T23:59:59.999999999+02:00"
+    );
+    assert_eq!(
+        run(&["--end-of", "month"]),
+        "2023-11-30T23:59:59.999999999+00:00"
+    );
+    assert_eq!(run(&["--end-of", "hour", "--unix"]), "1700002799");
+    assert_eq!(
+        run(&["--end-of", "week", "--format", "%a %F %T"]),
+        "Sun 2023-11-19 23:59:59"
+    );
+}
+
+#[test]
+fn cli_start_of() {
+    let run = |args: &[&str]| {
+        let out = Command::new(bin())
+            .args(["1700000000"])
+            .args(args)
+            .output()
+            .expect("run timeparse");
+        (
+            out.status.code(),
+            String::from_utf8(out.stdout).unwrap().trim().to_string(),
+        )
+    };
+    assert_eq!(
+        run(&["--start-of", "month", "--unix"]),
+        (Some(0), "1698796800".to_string())
+    );
+    assert_eq!(
+        run(&["--start-of", "day", "--output-tz", "America/New_York"]).1,
+        "2023-11-14T00:00:00-05:00"
+    );
+    assert_eq!(
+        run(&["--start-of", "quarter", "--output-tz", "+02:00"]).1,
+        "2023-10-01T00:00:00+02:00"
+    );
+    assert_eq!(run(&["--start-of", "day", "--end-of", "day"]).0, Some(2));
+    // Santiago skips midnight on 2024-09-08: the day starts when clocks land at 01:00.
+    let out = Command::new(bin())
+        .args([
+            "2024/09/08 18:00:00",
+            "--input-tz",
+            "utc",
+            "--start-of",
+            "day",
+        ])
+        .args(["--output-tz", "America/Santiago"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().trim(),
+        "2024-09-08T01:00:00-03:00"
+    );
+}
+
+#[test]
+fn cli_clamp() {
+    let run = |args: &[&str]| {
+        let out = Command::new(bin())
+            .args(args)
+            .args([
+                "--clamp-min",
+                "2000-01-01",
+                "--clamp-max",
+                "2038-01-19T03:14:07Z",
+            ])
+            .output()
+            .expect("run timeparse");
+        (
+            out.status.code(),
+            String::from_utf8(out.stdout).unwrap().trim().to_string(),
+        )
+    };
+    assert_eq!(run(&["0", "--unix"]).1, "946684800");
+    assert_eq!(run(&["99999999999", "--unix"]).1, "2147483647");
+    assert_eq!(run(&["1700000000"]).1, "2023-11-14T22:13:20+00:00");
+
+    let v: serde_json::Value = serde_json::from_str(&run(&["0", "--json"]).1).unwrap();
+    assert_eq!(v["rfc3339"], "2000-01-01T00:00:00+00:00");
+    assert_eq!(v["clamped"], true);
+    let v: serde_json::Value = serde_json::from_str(&run(&["1700000000", "--json"]).1).unwrap();
+    assert_eq!(v["clamped"], false);
+
+    let out = Command::new(bin())
+        .args([
+            "0",
+            "--clamp-min",
+            "2038-01-01",
+            "--clamp-max",
+            "2000-01-01",
+        ])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(out.status.code(), Some(2));
+    // Checked up front, even when batch input turns out to be empty.
+    let out = Command::new(bin())
+        .args([
+            "-",
+            "--clamp-min",
+            "2038-01-01",
+            "--clamp-max",
+            "2000-01-01",
+        ])
+        .stdin(std::process::Stdio::null())
+        .output()
+        .expect("run timeparse");
+    assert_eq!(out.status.code(), Some(2));
+
+    // Bounds without an offset are UTC, whatever the host zone.
+    let out = Command::new(bin())
+        .args(["2023-11-14T00:00:00Z", "--clamp-min", "2023/11/15"])
+        .env("TZ", "Asia/Tokyo")
+        .output()
+        .expect("run timeparse");
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().trim(),
+        "2023-11-15T00:00:00+00:00"
+    );
+}
+
+#[test]
+fn cli_week_start() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let run = |args: &[&str]| {
+        let out = Command::new(bin())
+            .args(["1700000000"])
+            .args(args)
+            .output()
+            .expect("run timeparse");
+        String::from_utf8(out.stdout).unwrap().trim().to_string()
+    };
+    // Tuesday 2023-11-14.
+    assert_eq!(
+        run(&["--start-of", "week", "--week-start", "sun"]),
+        "2023-11-12T00:00:00+00:00"
+    );
+    assert_eq!(
+        run(&[
+            "--end-of",
+            "week",
+            "--week-start",
+            "sat",
+            "--format",
+            "%a %F"
+        ]),
+        "Fri 2023-11-17"
+    );
+    let v: serde_json::Value =
+        serde_json::from_str(&run(&["--json", "--week-start", "sunday"])).unwrap();
+    assert_eq!(
+        (v["week"].as_u64(), v["week_start"].as_str()),
+        (Some(46), Some("sun"))
+    );
+    let v: serde_json::Value = serde_json::from_str(&run(&["--json"])).unwrap();
+    assert!(v.get("week").is_none());
+
+    // Saturday and the Monday after share a week that starts on Saturday.
+    let mut child = Command::new(bin())
+        .args(["group", "--by", "week", "--week-start", "sat"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("run timeparse");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"2023-11-11\n2023-11-13\n")
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "period_start,count\n2023-11-11T00:00:00+00:00,2\n"
+    );
+}
+
+#[test]
+fn cli_next_and_prev_weekday() {
+    let run = |args: &[&str]| {
+        let out = Command::new(bin())
+            .args(args)
+            .output()
+            .expect("run timeparse");
+        assert!(out.status.success(), "{args:?}");
+        String::from_utf8(out.stdout).unwrap().trim().to_string()
+    };
+    // 2023-11-14 is a Tuesday.
+    assert_eq!(
+        run(&[
+            "next",
+            "monday",
+            "2023-11-14",
+            "--tz",
+            "America/New_York",
+            "--at",
+            "09:00"
+        ]),
+        "2023-11-20T14:00:00+00:00"
+    );
+    assert_eq!(
+        run(&["prev", "fri", "2023-11-14", "--input-tz", "utc", "--unix"]),
+        "1699574400"
+    );
+    assert_eq!(
+        run(&[
+            "next",
+            "tue",
+            "2023-11-14",
+            "--input-tz",
+            "utc",
+            "--at",
+            "end"
+        ]),
+        "2023-11-21T23:59:59+00:00"
+    );
+}
+
+#[test]
+fn cli_nth_weekday() {
+    let run = |args: &[&str]| {
+        let out = Command::new(bin())
+            .arg("nth")
+            .args(args)
+            .output()
+            .expect("run timeparse");
+        (
+            out.status.code(),
+            String::from_utf8(out.stdout).unwrap().trim().to_string(),
+        )
+    };
+    assert_eq!(
+        run(&["2", "tuesday", "2025-03", "--input-tz", "utc"]),
+        (Some(0), "2025-03-11T00:00:00+00:00".to_string())
+    );
+    // EU summer time ends at 03:00 CEST on the last Sunday of October.
+    assert_eq!(
+        run(&[
+            "-1", "sun", "2024-10", "--tz", "+02:00", "--at", "03:00", "--unix"
+        ])
+        .1,
+        "1729990800"
+    );
+    assert_eq!(run(&["5", "tue", "2025-03"]).0, Some(1));
+    assert_eq!(run(&["0", "tue", "2025-03"]).0, Some(2));
+    assert_eq!(run(&["2", "tue", "2025-13"]).0, Some(2));
+}
+
+#[test]
+fn cli_easter() {
+    let run = |args: &[&str]| {
+        let out = Command::new(bin())
+            .arg("easter")
+            .args(args)
+            .output()
+            .expect("run timeparse");
+        String::from_utf8(out.stdout).unwrap()
+    };
+    assert_eq!(run(&["2026"]).trim(), "2026-04-05");
+    assert_eq!(run(&["2026", "--orthodox"]).trim(), "2026-04-12");
+    let feasts = run(&["2026", "--feasts"]);
+    assert!(
+        feasts.starts_with("2026-02-18  Wed  Ash Wednesday\n"),
+        "{feasts}"
+    );
+    assert!(
+        feasts.contains("2026-05-25  Mon  Whit Monday\n"),
+        "{feasts}"
+    );
+
+    let v: serde_json::Value =
+        serde_json::from_str(&run(&["2024", "--orthodox", "--feasts", "--json"])).unwrap();
+    assert_eq!(v["computus"], "julian");
+    assert_eq!(v["easter"], "2024-05-05");
+    assert_eq!(v["feasts"][0]["name"], "Clean Monday");
+    assert_eq!(v["feasts"][0]["days_from_easter"], -48);
+}
+
+#[test]
+fn cli_is_weekend_and_business_day() {
+    let run = |args: &[&str]| {
+        let out = Command::new(bin())
+            .arg("is")
+            .args(args)
+            .args(["--input-tz", "utc"])
+            .output()
+            .expect("run timeparse");
+        (
+            out.status.code(),
+            String::from_utf8(out.stdout).unwrap().trim().to_string(),
+        )
+    };
+    assert_eq!(run(&["weekend", "2023-11-18"]), (Some(0), String::new()));
+    assert_eq!(run(&["weekend", "2023-11-14"]).0, Some(1));
+    assert_eq!(
+        run(&["weekend", "2023-11-17", "--weekend", "fri,sat", "--print"]),
+        (Some(0), "yes (Friday)".to_string())
+    );
+    assert_eq!(
+        run(&["business-day", "1700000000", "--print"]),
+        (Some(0), "yes (Tuesday)".to_string())
+    );
+    assert_eq!(
+        run(&["business-day", "2023-12-25", "--holidays", "gb", "--print"]),
+        (Some(1), "no (Christmas Day)".to_string())
+    );
+    assert_eq!(
+        run(&["business-day", "2023-12-23", "--print"]),
+        (Some(1), "no (Saturday, weekend)".to_string())
+    );
+}
+
+#[test]
+fn cli_is_leap_year() {
+    let run = |args: &[&str]| {
+        let out = Command::new(bin())
+            .args(["is", "leap-year"])
+            .args(args)
+            .output()
+            .expect("run timeparse");
+        (
+            out.status.code(),
+            String::from_utf8(out.stdout).unwrap().trim().to_string(),
+        )
+    };
+    assert_eq!(run(&["2024"]), (Some(0), String::new()));
+    assert_eq!(run(&["1900"]).0, Some(1));
+    assert_eq!(
+        run(&["2000", "--print"]),
+        (Some(0), "yes (2000 has 366 days)".to_string())
+    );
+    // Anything longer than a year is read as INPUT.
+    assert_eq!(run(&["1700000000"]).0, Some(1));
+    let (code, stdout) = run(&["2024-02-29", "--json"]);
+    assert_eq!(code, Some(0));
+    let v: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(v["year"], 2024);
+    assert_eq!(v["leap_year"], true);
+    assert_eq!(v["days"], 366);
+}
+
+#[test]
+fn cli_days_in() {
+    let run = |args: &[&str]| {
+        let out = Command::new(bin())
+            .arg("days-in")
+            .args(args)
+            .args(["--input-tz", "utc"])
+            .output()
+            .expect("run timeparse");
+        assert!(out.status.success(), "{args:?}");
+        String::from_utf8(out.stdout).unwrap().trim().to_string()
+    };
+    assert_eq!(run(&["2024-02"]), "29");
+    assert_eq!(run(&["2100/02"]), "28");
+    assert_eq!(run(&["1700000000"]), "30");
+    assert_eq!(run(&["2023-12", "--last"]), "2023-12-31");
+    let v: serde_json::Value = serde_json::from_str(&run(&["2023-11-14", "--json"])).unwrap();
+    assert_eq!(v["month"], "2023-11");
+    assert_eq!(v["days"], 30);
+    assert_eq!(v["last_date"], "2023-11-30");
+}
+
+#[test]
+fn cli_fmt_convert() {
+    let out = Command::new(bin())
+        .args(["fmt-convert", "--to", "go", "%Y-%m-%d %H:%M:%S"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().trim(),
+        "2006-01-02 15:04:05"
+    );
+
+    let out = Command::new(bin())
+        .args([
+            "fmt-convert",
+            "--from",
+            "java",
+            "--to",
+            "moment",
+            "yyyy-MM-dd'T'HH:mm",
+        ])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap().trim(),
+        "YYYY-MM-DD[T]HH:mm"
+    );
+
+    let out = Command::new(bin())
+        .args(["fmt-convert", "--to", "go", "%s"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn cli_formats_lists_the_detection_table() {
+    let out = Command::new(bin())
+        .args(["formats", "--json"])
+        .output()
+        .expect("run timeparse");
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid json");
+    let formats = v.as_array().unwrap();
+    assert_eq!(formats[0]["name"], "compact");
+    assert_eq!(formats[2]["name"], "timestamp");
+    assert_eq!(formats.last().unwrap()["name"], "expression");
+    // Every listed example is accepted as INPUT.
+    for format in formats {
+        let example = format["example"].as_str().unwrap();
+        let out = Command::new(bin())
+            .args([example, "--input-tz", "utc"])
+            .output()
+            .expect("run timeparse");
+        assert!(out.status.success(), "{example}");
+    }
+}
+
+#[test]
+fn cli_custom_detectors() {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("detectors.json");
+    std::fs::write(
+        &path,
+        r#"{"detectors": [
+            {"name": "app", "regex": "^APP\\[(?P<ts>[^\\]]+)\\]", "format": "%d.%m.%Y %H:%M:%S", "tz": "utc"}
+        ]}"#,
+    )
+    .unwrap();
+
+    let out = Command::new(bin())
+        .args(["APP[14.11.2023 22:13:20] boot", "--unix", "--detectors"])
+        .arg(&path)
+        .output()
+        .expect("run timeparse");
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8(out.stdout).unwrap().trim(), "1700000000");
+
+    std::fs::write(&path, r#"{"detectors": [{"name": "broken"}]}"#).unwrap();
+    let out = Command::new(bin())
+        .args(["1700000000", "--detectors"])
+        .arg(&path)
+        .output()
+        .expect("run timeparse");
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[cfg(feature = "script")]
+#[test]
+fn cli_script() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("transform.rhai");
+    std::fs::write(
+        &path,
+        r#"if result.unix_seconds % 60 == 20 {
+    `boot at ${result.formatted} (${instant.format("%a", "Asia/Tokyo")} in Tokyo)`
+}
+"#,
+    )
+    .unwrap();
+
+    let mut child = Command::new(bin())
+        .args(["-", "--format", "%H:%M", "--script"])
+        .arg(&path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("run timeparse");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"1700000000\n1700000030\n")
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+    assert!(out.status.success());
+    // The second line evaluates to (), so it is dropped.
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "boot at 22:13 (Wed in Tokyo)\n"
+    );
+
+    let out = Command::new(bin())
+        .args(["1700000000", "--script", "/nonexistent/transform.rhai"])
+        .output()
+        .expect("run timeparse");
+    assert_eq!(out.status.code(), Some(2));
+}